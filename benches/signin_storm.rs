@@ -0,0 +1,156 @@
+//! Measures the latency of an unrelated endpoint while the server is flooded with sign ins, once with
+//! passwords verified inline on the async workers and once through `ArgonSecurityUtil`.
+//!
+//! Run with `cargo bench --bench signin_storm`.
+
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use rest_api::mocks::stub_config_with;
+use rest_api::server::utils::argon_utils::{ArgonSecurityUtil, DynArgonUtil};
+
+/// Concurrent clients signing in over and over.
+const SIGNIN_CLIENTS: usize = 64;
+/// Worker threads of the server runtime, kept small so a stalled worker shows up clearly.
+const SERVER_WORKERS: usize = 2;
+const STORM_DURATION: Duration = Duration::from_secs(5);
+const PROBE_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    Inline,
+    Pooled,
+}
+
+struct Hashing {
+    argon_util: DynArgonUtil,
+    stored_password: String,
+}
+
+fn main() {
+    for mode in [Mode::Inline, Mode::Pooled] {
+        let mut latencies = run_storm(mode);
+        latencies.sort();
+
+        println!(
+            "{:<8} health checks: {:>5}  p50: {:>9.2?}  p99: {:>9.2?}  max: {:>9.2?}",
+            format!("{:?}", mode).to_lowercase(),
+            latencies.len(),
+            percentile(&latencies, 50.0),
+            percentile(&latencies, 99.0),
+            latencies.last().copied().unwrap_or_default(),
+        );
+    }
+}
+
+fn run_storm(mode: Mode) -> Vec<Duration> {
+    let server_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(SERVER_WORKERS)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let address = server_runtime.block_on(start_server(mode));
+
+    // clients run on their own runtime so they are not slowed down by the server under test
+    let client_runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let latencies = client_runtime.block_on(async move {
+        let client = reqwest::Client::new();
+        let deadline = Instant::now() + STORM_DURATION;
+
+        for _ in 0..SIGNIN_CLIENTS {
+            let client = client.clone();
+            tokio::spawn(async move {
+                while Instant::now() < deadline {
+                    let _ = client
+                        .post(format!("http://{}/signin", address))
+                        .send()
+                        .await;
+                }
+            });
+        }
+
+        let mut latencies = Vec::new();
+
+        while Instant::now() < deadline {
+            let started_at = Instant::now();
+            client
+                .get(format!("http://{}/health", address))
+                .send()
+                .await
+                .expect("health check failed");
+            latencies.push(started_at.elapsed());
+
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+
+        latencies
+    });
+
+    client_runtime.shutdown_timeout(Duration::from_secs(1));
+    server_runtime.shutdown_timeout(Duration::from_secs(1));
+
+    latencies
+}
+
+async fn start_server(mode: Mode) -> SocketAddr {
+    let argon_util = Arc::new(ArgonSecurityUtil::new(stub_config_with(&[]))) as DynArgonUtil;
+    let stored_password = argon_util.hash_password("password").await.unwrap();
+
+    let signin = match mode {
+        Mode::Inline => post(signin_inline),
+        Mode::Pooled => post(signin_pooled),
+    };
+
+    let router = Router::new()
+        .route("/signin", signin)
+        .route("/health", get(|| async { "ok" }))
+        .layer(Extension(Arc::new(Hashing {
+            argon_util,
+            stored_password,
+        })));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(
+        axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(router.into_make_service()),
+    );
+
+    address
+}
+
+async fn signin_inline(Extension(hashing): Extension<Arc<Hashing>>) -> String {
+    let valid = argon2::verify_encoded(&hashing.stored_password, b"password").unwrap();
+
+    valid.to_string()
+}
+
+async fn signin_pooled(Extension(hashing): Extension<Arc<Hashing>>) -> String {
+    let valid = hashing
+        .argon_util
+        .verify_password(&hashing.stored_password, String::from("password"))
+        .await
+        .unwrap();
+
+    valid.to_string()
+}
+
+fn percentile(sorted_latencies: &[Duration], percentile: f64) -> Duration {
+    if sorted_latencies.is_empty() {
+        return Duration::default();
+    }
+
+    let index = ((percentile / 100.0) * (sorted_latencies.len() - 1) as f64).round() as usize;
+
+    sorted_latencies[index]
+}
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+alter table sessions
+    add column if not exists created_at timestamptz not null default current_timestamp;
+
+create index if not exists sessions_user_id_idx on sessions (user_id);
@@ -0,0 +1,4 @@
+-- tracks the only refresh token of the session (token family) that may still be exchanged
+
+alter table sessions
+    add column if not exists refresh_token_id uuid not null default uuid_generate_v4();
@@ -0,0 +1,16 @@
+-- single-use password reset tokens, only a hash of the emailed token is stored
+
+create table if not exists password_reset_tokens
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid          not null references users (id) on delete cascade,
+    token_hash  varchar       not null,
+    exp         timestamptz   not null,
+    used_at     timestamptz,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table password_reset_tokens
+    add constraint password_reset_tokens_id_pk primary key (id);
+
+create unique index if not exists password_reset_tokens_token_hash_idx on password_reset_tokens (token_hash);
@@ -0,0 +1,20 @@
+-- email verification, tokens carry the address being verified so email changes only apply once confirmed
+
+alter table users
+    add column if not exists verified_at timestamptz;
+
+create table if not exists email_verification_tokens
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid          not null references users (id) on delete cascade,
+    email       varchar       not null,
+    token_hash  varchar       not null,
+    exp         timestamptz   not null,
+    used_at     timestamptz,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table email_verification_tokens
+    add constraint email_verification_tokens_id_pk primary key (id);
+
+create unique index if not exists email_verification_tokens_token_hash_idx on email_verification_tokens (token_hash);
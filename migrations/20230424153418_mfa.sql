@@ -0,0 +1,20 @@
+-- optional TOTP two-factor authentication, `mfa_enabled_at` is only set once enrollment is confirmed
+
+alter table users
+    add column if not exists mfa_secret varchar,
+    add column if not exists mfa_enabled_at timestamptz,
+    add column if not exists mfa_last_used_step bigint;
+
+create table if not exists recovery_codes
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid          not null references users (id) on delete cascade,
+    code_hash   varchar       not null,
+    used_at     timestamptz,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table recovery_codes
+    add constraint recovery_codes_id_pk primary key (id);
+
+create index if not exists recovery_codes_user_id_idx on recovery_codes (user_id);
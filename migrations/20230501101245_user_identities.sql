@@ -0,0 +1,33 @@
+-- external identities (OpenID Connect subjects) linked to users, and the pending state of authorization code logins
+
+create table if not exists user_identities
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid          not null references users (id) on delete cascade,
+    provider    varchar       not null,
+    subject     varchar       not null,
+    email       varchar,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table user_identities
+    add constraint user_identities_id_pk primary key (id);
+
+create unique index if not exists user_identities_provider_subject_idx on user_identities (provider, subject);
+
+create index if not exists user_identities_user_id_idx on user_identities (user_id);
+
+create table if not exists oidc_login_states
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    state_hash      varchar       not null,
+    code_verifier   varchar       not null,
+    nonce           varchar       not null,
+    exp             timestamptz   not null,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table oidc_login_states
+    add constraint oidc_login_states_id_pk primary key (id);
+
+create unique index if not exists oidc_login_states_state_hash_idx on oidc_login_states (state_hash);
@@ -0,0 +1,22 @@
+-- long-lived API keys for scripts and integrations, only a hash of the key is stored and the
+-- non-secret prefix is used to look it up
+
+create table if not exists api_keys
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    user_id         uuid          not null references users (id) on delete cascade,
+    name            varchar       not null,
+    prefix          varchar       not null,
+    key_hash        varchar       not null,
+    scopes          varchar[]     not null default '{}',
+    exp             timestamptz   not null,
+    last_used_at    timestamptz,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table api_keys
+    add constraint api_keys_id_pk primary key (id);
+
+create unique index if not exists api_keys_prefix_idx on api_keys (prefix);
+
+create index if not exists api_keys_user_id_idx on api_keys (user_id);
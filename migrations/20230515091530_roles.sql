@@ -0,0 +1,36 @@
+-- role based access control, roles grant permissions and users hold any number of roles
+
+create table if not exists roles
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    name        varchar       not null,
+    permissions varchar[]     not null default '{}',
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table roles
+    add constraint roles_id_pk primary key (id);
+
+create unique index if not exists roles_name_idx on roles (name);
+
+create table if not exists user_roles
+(
+    user_id     uuid          not null references users (id) on delete cascade,
+    role_id     uuid          not null references roles (id) on delete cascade,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table user_roles
+    add constraint user_roles_pk primary key (user_id, role_id);
+
+insert into roles (name, permissions)
+values ('admin', '{categories:read,categories:write,users:read,users:write}'),
+       ('user', '{categories:read,categories:write}')
+on conflict (name) do nothing;
+
+-- every existing user gets the default role new users are created with
+insert into user_roles (user_id, role_id)
+select users.id, roles.id
+from users, roles
+where roles.name = 'user'
+on conflict do nothing;
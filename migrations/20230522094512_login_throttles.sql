@@ -0,0 +1,29 @@
+-- failed sign in attempts tracked per account (email) and per client IP, along with an audit trail of lockouts
+
+create table if not exists login_throttles
+(
+    scope           varchar       not null,
+    subject         varchar       not null,
+    failed_attempts integer       not null default 0,
+    last_failed_at  timestamptz   not null default current_timestamp,
+    locked_until    timestamptz
+);
+
+alter table login_throttles
+    add constraint login_throttles_pk primary key (scope, subject);
+
+create table if not exists lockout_events
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    scope           varchar       not null,
+    subject         varchar       not null,
+    event           varchar       not null,
+    failed_attempts integer       not null,
+    locked_until    timestamptz,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table lockout_events
+    add constraint lockout_events_id_pk primary key (id);
+
+create index if not exists lockout_events_scope_subject_idx on lockout_events (scope, subject);
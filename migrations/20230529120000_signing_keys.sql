@@ -0,0 +1,19 @@
+-- asymmetric keys access tokens are signed with, each key is published ahead of its activation and kept
+-- around after it has been replaced so tokens it signed remain valid
+
+create table if not exists signing_keys
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    kid             varchar       not null,
+    algorithm       varchar       not null,
+    private_key     varchar       not null,
+    public_jwk      varchar       not null,
+    activates_at    timestamptz   not null,
+    expires_at      timestamptz   not null,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table signing_keys
+    add constraint signing_keys_id_pk primary key (id);
+
+create unique index if not exists signing_keys_kid_idx on signing_keys (kid);
@@ -0,0 +1,24 @@
+-- access tokens revoked before they expired, entries are only kept until the tokens they deny would have expired
+
+create table if not exists revoked_access_tokens
+(
+    jti         uuid          not null,
+    expires_at  timestamptz   not null,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table revoked_access_tokens
+    add constraint revoked_access_tokens_pk primary key (jti);
+
+-- every access token issued to the user before `issued_before` is revoked
+
+create table if not exists revoked_user_access_tokens
+(
+    user_id         uuid          not null,
+    issued_before   timestamptz   not null,
+    expires_at      timestamptz   not null,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table revoked_user_access_tokens
+    add constraint revoked_user_access_tokens_pk primary key (user_id);
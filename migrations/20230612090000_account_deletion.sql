@@ -0,0 +1,5 @@
+-- accounts scheduled for deletion are hard deleted, along with everything cascading from them, once the grace period passes
+alter table users
+    add column if not exists delete_after timestamptz;
+
+create index if not exists users_delete_after_idx on users (delete_after) where delete_after is not null;
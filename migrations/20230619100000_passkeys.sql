@@ -0,0 +1,35 @@
+-- WebAuthn credentials registered as passkeys, and the pending challenges of registration and authentication ceremonies
+
+create table if not exists passkeys
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    user_id         uuid          not null references users (id) on delete cascade,
+    name            varchar       not null,
+    credential_id   varchar       not null,
+    public_key      bytea         not null,
+    sign_count      bigint        not null default 0,
+    last_used_at    timestamptz,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table passkeys
+    add constraint passkeys_id_pk primary key (id);
+
+create unique index if not exists passkeys_credential_id_idx on passkeys (credential_id);
+
+create index if not exists passkeys_user_id_idx on passkeys (user_id);
+
+create table if not exists webauthn_challenges
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    challenge_hash  varchar       not null,
+    ceremony        varchar       not null,
+    user_id         uuid          references users (id) on delete cascade,
+    exp             timestamptz   not null,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table webauthn_challenges
+    add constraint webauthn_challenges_id_pk primary key (id);
+
+create unique index if not exists webauthn_challenges_challenge_hash_idx on webauthn_challenges (challenge_hash);
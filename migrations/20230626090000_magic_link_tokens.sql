@@ -0,0 +1,19 @@
+-- single-use sign in links, only a hash of the emailed token is stored. rows are kept after use so recent
+-- requests can be counted when rate limiting
+
+create table if not exists magic_link_tokens
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid          not null references users (id) on delete cascade,
+    token_hash  varchar       not null,
+    exp         timestamptz   not null,
+    used_at     timestamptz,
+    created_at  timestamptz   not null default current_timestamp
+);
+
+alter table magic_link_tokens
+    add constraint magic_link_tokens_id_pk primary key (id);
+
+create unique index if not exists magic_link_tokens_token_hash_idx on magic_link_tokens (token_hash);
+
+create index if not exists magic_link_tokens_user_id_created_at_idx on magic_link_tokens (user_id, created_at);
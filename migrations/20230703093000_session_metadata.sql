@@ -0,0 +1,8 @@
+-- where and on what a session was started, so users can recognise their devices
+
+alter table sessions
+    add column if not exists ip_address varchar,
+    add column if not exists browser varchar,
+    add column if not exists os varchar,
+    add column if not exists device varchar,
+    add column if not exists last_seen_at timestamptz not null default current_timestamp;
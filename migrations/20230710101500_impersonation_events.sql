@@ -0,0 +1,20 @@
+-- audit trail of admins acting as other users, kept without foreign keys so it outlives the accounts involved
+
+create table if not exists impersonation_events
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    actor_id        uuid          not null,
+    user_id         uuid          not null,
+    event           varchar       not null,
+    access_token_id uuid,
+    method          varchar,
+    path            varchar,
+    created_at      timestamptz   not null default current_timestamp
+);
+
+alter table impersonation_events
+    add constraint impersonation_events_id_pk primary key (id);
+
+create index if not exists impersonation_events_user_id_idx on impersonation_events (user_id);
+
+create index if not exists impersonation_events_actor_id_idx on impersonation_events (actor_id);
@@ -0,0 +1,34 @@
+-- money moving in or out, amounts are exact decimals with up to four places and always positive, the type tells
+-- which way they go
+drop type if exists transaction_type;
+
+create type transaction_type as ENUM (
+  'Expense','Income'
+  );
+
+create table if not exists transactions
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    amount      numeric(19, 4)   not null check (amount > 0),
+    currency    varchar(3)       not null,
+    tx_type     transaction_type not null default 'Expense',
+    date        date             not null,
+    payee       varchar          not null default '',
+    note        varchar,
+    category_id uuid             references categories (id) on delete set null,
+    user_id     uuid             not null references users (id) on delete cascade,
+    created_at  timestamptz      not null default current_timestamp,
+    updated_at  timestamptz      not null default current_timestamp
+);
+
+alter table transactions
+    add constraint transactions_id_pk primary key (id);
+
+create index if not exists transactions_user_id_date_idx on transactions (user_id, date);
+
+create index if not exists transactions_category_id_idx on transactions (category_id);
+
+update roles
+set permissions = array_cat(permissions, '{transactions:read,transactions:write}')
+where name in ('admin', 'user')
+  and not permissions @> '{transactions:read}';
@@ -0,0 +1,24 @@
+-- monthly spending limits per category, months are stored as their first day
+
+create table if not exists budgets
+(
+    id          uuid DEFAULT uuid_generate_v4 (),
+    user_id     uuid           not null references users (id) on delete cascade,
+    category_id uuid           not null references categories (id) on delete cascade,
+    month       date           not null check (extract(day from month) = 1),
+    amount      numeric(19, 4) not null check (amount >= 0),
+    created_at  timestamptz    not null default current_timestamp,
+    updated_at  timestamptz    not null default current_timestamp
+);
+
+alter table budgets
+    add constraint budgets_id_pk primary key (id);
+
+create unique index if not exists budgets_category_id_month_idx on budgets (category_id, month);
+
+create index if not exists budgets_user_id_month_idx on budgets (user_id, month);
+
+update roles
+set permissions = array_cat(permissions, '{budgets:read,budgets:write}')
+where name in ('admin', 'user')
+  and not permissions @> '{budgets:read}';
@@ -0,0 +1,5 @@
+-- transfers move money between two accounts of a user as a linked pair, new enum values cannot be used in the
+-- transaction adding them so they get a migration of their own
+alter type transaction_type add value if not exists 'TransferOut';
+
+alter type transaction_type add value if not exists 'TransferIn';
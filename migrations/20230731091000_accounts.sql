@@ -0,0 +1,44 @@
+-- the places users hold money in, balances are derived from the opening balance and the transactions of an account
+drop type if exists account_type;
+
+create type account_type as ENUM (
+  'Checking','Savings','Cash','CreditCard'
+  );
+
+create table if not exists accounts
+(
+    id              uuid DEFAULT uuid_generate_v4 (),
+    name            varchar        not null default '',
+    acc_type        account_type   not null default 'Checking',
+    currency        varchar(3)     not null,
+    opening_balance numeric(19, 4) not null default 0,
+    user_id         uuid           not null references users (id) on delete cascade,
+    created_at      timestamptz    not null default current_timestamp,
+    updated_at      timestamptz    not null default current_timestamp
+);
+
+alter table accounts
+    add constraint accounts_id_pk primary key (id);
+
+create index if not exists accounts_user_id_idx on accounts (user_id);
+
+-- both legs of a transfer share the transfer id
+alter table transactions
+    add column if not exists account_id uuid references accounts (id) on delete set null,
+    add column if not exists transfer_id uuid;
+
+create index if not exists transactions_account_id_date_idx on transactions (account_id, date);
+
+create index if not exists transactions_transfer_id_idx on transactions (transfer_id) where transfer_id is not null;
+
+create or replace view account_movements as
+select account_id,
+       sum(case when tx_type in ('Income', 'TransferIn') then amount else -amount end) as net
+from transactions
+where account_id is not null
+group by account_id;
+
+update roles
+set permissions = array_cat(permissions, '{accounts:read,accounts:write}')
+where name in ('admin', 'user')
+  and not permissions @> '{accounts:read}';
@@ -0,0 +1,44 @@
+-- templates of transactions repeating on a schedule, the schedule follows a subset of RFC 5545 recurrence rules and
+-- the first occurrence is the start date
+drop type if exists recurrence_frequency;
+
+create type recurrence_frequency as ENUM (
+  'Daily','Weekly','Monthly','Yearly'
+  );
+
+create table if not exists recurring_transactions
+(
+    id                 uuid DEFAULT uuid_generate_v4 (),
+    amount             numeric(19, 4)       not null check (amount > 0),
+    currency           varchar(3)           not null,
+    tx_type            transaction_type     not null default 'Expense',
+    payee              varchar              not null default '',
+    note               varchar,
+    category_id        uuid                 references categories (id) on delete set null,
+    account_id         uuid                 not null references accounts (id) on delete cascade,
+    frequency          recurrence_frequency not null,
+    interval           integer              not null default 1 check (interval > 0),
+    starts_on          date                 not null,
+    until              date,
+    count              integer check (count > 0),
+    -- the next occurrence still to be recorded, empty once the schedule has ended
+    next_date          date,
+    materialized_count integer              not null default 0,
+    user_id            uuid                 not null references users (id) on delete cascade,
+    created_at         timestamptz          not null default current_timestamp,
+    updated_at         timestamptz          not null default current_timestamp,
+    check (until is null or count is null)
+);
+
+alter table recurring_transactions
+    add constraint recurring_transactions_id_pk primary key (id);
+
+create index if not exists recurring_transactions_user_id_idx on recurring_transactions (user_id);
+
+create index if not exists recurring_transactions_next_date_idx on recurring_transactions (next_date) where next_date is not null;
+
+-- an occurrence is recorded at most once, so catching up after downtime or racing instances never duplicate it
+alter table transactions
+    add column if not exists recurring_id uuid references recurring_transactions (id) on delete set null;
+
+create unique index if not exists transactions_recurring_id_date_idx on transactions (recurring_id, date);
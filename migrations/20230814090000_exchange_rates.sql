@@ -0,0 +1,60 @@
+-- reports are converted into the base currency of each user
+alter table users
+    add column if not exists base_currency varchar(3) not null default 'USD';
+
+-- one unit of the base currency is worth `rate` units of the quote currency from the effective date onwards, until a
+-- later rate of the same pair takes over
+create table if not exists exchange_rates
+(
+    base_currency  varchar(3)      not null,
+    quote_currency varchar(3)      not null,
+    rate           numeric(24, 10) not null check (rate > 0),
+    effective_on   date            not null,
+    created_at     timestamptz     not null default current_timestamp,
+    updated_at     timestamptz     not null default current_timestamp,
+    check (base_currency <> quote_currency)
+);
+
+alter table exchange_rates
+    add constraint exchange_rates_pk primary key (base_currency, quote_currency, effective_on);
+
+-- converts an amount with the rate effective on the date, looking the pair up either way round and preferring the
+-- most recent rate. Converted amounts are rounded to the four decimal places amounts are stored with, amounts without
+-- a rate convert to null.
+create or replace function convert_amount(amount numeric, from_currency varchar, to_currency varchar, on_date date)
+    returns numeric
+    language sql
+    stable
+as
+$$
+select case
+           when from_currency = to_currency then amount
+           else (select round(converted, 4)
+                 from (select amount * rate as converted, effective_on, 0 as priority
+                       from exchange_rates
+                       where base_currency = from_currency
+                         and quote_currency = to_currency
+                         and effective_on <= on_date
+                       union all
+                       select amount / rate, effective_on, 1
+                       from exchange_rates
+                       where base_currency = to_currency
+                         and quote_currency = from_currency
+                         and effective_on <= on_date) rates
+                 order by effective_on desc, priority
+                 limit 1)
+           end
+$$;
+
+-- budgets carry the currency their limit is set in, existing ones are in the base currency of their owner
+alter table budgets
+    add column if not exists currency varchar(3);
+
+update budgets
+set currency = users.base_currency
+from users
+where users.id = budgets.user_id
+  and budgets.currency is null;
+
+alter table budgets
+    alter column currency set not null;
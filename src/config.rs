@@ -1,9 +1,77 @@
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, ensure};
+use ipnet::IpNet;
+use time::OffsetDateTime;
+
 #[derive(clap::ValueEnum, Clone, Debug, Copy)]
 pub enum CargoEnv {
     Development,
     Production,
 }
 
+#[derive(clap::ValueEnum, Clone, Debug, Copy)]
+pub enum MailerTransport {
+    Smtp,
+    File,
+    Memory,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    Hs256,
+    Rs256,
+    #[value(name = "eddsa")]
+    EdDsa,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Copy)]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Copy)]
+pub enum RevocationStore {
+    Postgres,
+    Memory,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+pub enum ExchangeRatesFormat {
+    Csv,
+    Json,
+}
+
+impl ExchangeRatesFormat {
+    /// Guesses the format of a file from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// One-off tasks run instead of the server.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Loads exchange rates from a file and exits, rates already stored for the same currency pair and date are
+    /// replaced. CSV files need a `base_currency,quote_currency,rate,effective_on` header, JSON files an array of
+    /// objects with the same fields and the rate as a string.
+    ImportExchangeRates {
+        path: PathBuf,
+
+        /// Format of the file, guessed from its extension when left out.
+        #[clap(long, value_enum)]
+        format: Option<ExchangeRatesFormat>,
+    },
+}
+
 #[derive(clap::Parser)]
 pub struct AppConfig {
     #[clap(long, env, value_enum)]
@@ -18,18 +86,332 @@ pub struct AppConfig {
     #[clap(long, env)]
     pub run_migrations: bool,
 
+    /// Salt every password used to be hashed with, hashes still using it are replaced on the next sign in.
     #[clap(long, env)]
     pub argon_salt: String,
 
+    /// Memory used to hash a password, in KiB.
+    #[clap(long, env, default_value = "19456")]
+    pub argon_memory_cost: u32,
+
+    /// Number of passes over the memory when hashing a password.
+    #[clap(long, env, default_value = "2")]
+    pub argon_time_cost: u32,
+
+    /// Number of lanes used when hashing a password.
+    #[clap(long, env, default_value = "1")]
+    pub argon_parallelism: u32,
+
+    /// Passwords hashed at once, further sign ins queue up. Defaults to the number of CPUs.
+    #[clap(long, env)]
+    pub argon_max_concurrency: Option<usize>,
+
     #[clap(long, env)]
     pub access_token_secret: String,
 
     #[clap(long, env)]
     pub refresh_token_secret: String,
 
+    /// How long an access token is valid for, it cannot be longer than a session may sit idle.
+    #[clap(long, env, default_value = "900")]
+    pub access_token_ttl_seconds: u64,
+
+    /// How long an admin may act as another user with a single impersonation token, they cannot be refreshed.
+    #[clap(long, env, default_value = "600")]
+    pub impersonation_ttl_seconds: u64,
+
+    /// How long the password step of a sign in stays valid while waiting for the MFA code, it has to be well below the
+    /// lifetime of an access token.
+    #[clap(long, env, default_value = "300")]
+    pub mfa_token_ttl_seconds: u64,
+
+    /// How long a session lasts without being refreshed, every refresh extends it by this much again.
+    #[clap(long, env, default_value = "10080")]
+    pub session_idle_timeout_minutes: u64,
+
+    /// Age after which a session ends no matter how often it is refreshed.
+    #[clap(long, env, default_value = "30")]
+    pub session_max_age_days: u64,
+
+    /// Algorithm access tokens are signed with. `hs256` signs with the access token secret, the asymmetric
+    /// algorithms sign with rotating keys published at `/.well-known/jwks.json`.
+    #[clap(long, env, value_enum, default_value = "eddsa")]
+    pub jwt_algorithm: JwtAlgorithm,
+
+    /// How long a signing key is used before it is replaced by a new one.
+    #[clap(long, env, default_value = "720")]
+    pub jwt_key_rotation_hours: u64,
+
+    /// How long a replaced signing key is still accepted, it should outlive the access tokens it signed.
+    #[clap(long, env, default_value = "24")]
+    pub jwt_key_grace_hours: u64,
+
+    /// Where revoked access tokens are kept, the in-memory store only suits a single instance.
+    #[clap(long, env, value_enum, default_value = "postgres")]
+    pub revocation_store: RevocationStore,
+
     #[clap(long, env)]
     pub cors_origin: String,
 
+    /// Only send the session cookies over HTTPS, only disable this for local development.
+    #[clap(long, env, default_value_t = true, action = clap::ArgAction::Set)]
+    pub cookie_secure: bool,
+
+    #[clap(long, env, value_enum, default_value = "strict")]
+    pub cookie_same_site: CookieSameSite,
+
+    /// Path the refresh token cookie is scoped to, it only needs to reach the session routes.
+    #[clap(long, env, default_value = "/api/v1/users")]
+    pub cookie_path: String,
+
+    /// Domain the session cookies are set for, defaults to the host of the API.
+    #[clap(long, env)]
+    pub cookie_domain: Option<String>,
+
+    /// Proxies allowed to report the client IP, as comma separated addresses or CIDR ranges. Requests
+    /// from anywhere else are attributed to the address they connect from.
+    #[clap(long, env, value_delimiter = ',', value_parser = parse_trusted_proxy)]
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// Header trusted proxies report the client IP in, either a list of hops like `X-Forwarded-For` or a single address.
+    #[clap(long, env, default_value = "x-forwarded-for")]
+    pub client_ip_header: String,
+
     #[clap(long, env)]
     pub seed: bool,
+
+    /// Base URL of the client application, used to build links sent by email.
+    #[clap(long, env, default_value = "http://localhost:3000")]
+    pub app_url: String,
+
+    #[clap(long, env, value_enum, default_value = "file")]
+    pub mailer_transport: MailerTransport,
+
+    #[clap(long, env, default_value = "no-reply@localhost")]
+    pub mail_from: String,
+
+    /// Directory the file mailer writes `.eml` messages to.
+    #[clap(long, env, default_value = "mail")]
+    pub mail_dir: String,
+
+    #[clap(long, env)]
+    pub smtp_host: Option<String>,
+
+    #[clap(long, env, default_value = "587")]
+    pub smtp_port: u16,
+
+    #[clap(long, env)]
+    pub smtp_username: Option<String>,
+
+    #[clap(long, env)]
+    pub smtp_password: Option<String>,
+
+    #[clap(long, env, default_value = "30")]
+    pub password_reset_ttl_minutes: u64,
+
+    #[clap(long, env, default_value = "1440")]
+    pub email_verification_ttl_minutes: u64,
+
+    #[clap(long, env, default_value = "15")]
+    pub magic_link_ttl_minutes: u64,
+
+    /// How many sign in links may be sent to the same email within `MAGIC_LINK_WINDOW_MINUTES`.
+    #[clap(long, env, default_value = "3")]
+    pub magic_link_max_requests: u32,
+
+    #[clap(long, env, default_value = "60")]
+    pub magic_link_window_minutes: u64,
+
+    /// Rejects sign in attempts from users who have not verified their email address.
+    #[clap(long, env)]
+    pub require_email_verification: bool,
+
+    /// Issuer shown by authenticator apps for TOTP enrollments.
+    #[clap(long, env, default_value = "rest_api")]
+    pub mfa_issuer: String,
+
+    /// Lifetime of API keys created without an explicit expiry.
+    #[clap(long, env, default_value = "90")]
+    pub api_key_ttl_days: u64,
+
+    /// Days a deleted account can still be restored by signing in before it is permanently deleted.
+    #[clap(long, env, default_value = "30")]
+    pub account_deletion_grace_days: u64,
+
+    /// Name the OpenID Connect provider is stored under when linking identities, e.g. `google`.
+    #[clap(long, env, default_value = "oidc")]
+    pub oidc_provider: String,
+
+    /// Issuer URL of the OpenID Connect provider, OIDC sign in is disabled when unset.
+    #[clap(long, env)]
+    pub oidc_issuer_url: Option<String>,
+
+    #[clap(long, env)]
+    pub oidc_client_id: Option<String>,
+
+    #[clap(long, env)]
+    pub oidc_client_secret: Option<String>,
+
+    /// Page of the client application the provider redirects back to with the authorization code.
+    #[clap(long, env, default_value = "http://localhost:3000/oidc/callback")]
+    pub oidc_redirect_url: String,
+
+    #[clap(long, env, default_value = "openid email profile")]
+    pub oidc_scopes: String,
+
+    #[clap(long, env, default_value = "10")]
+    pub oidc_state_ttl_minutes: u64,
+
+    /// Domain passkeys are bound to, it has to be the host of `CORS_ORIGIN` or a parent domain of it.
+    /// Ceremonies are only accepted from `CORS_ORIGIN`.
+    #[clap(long, env, default_value = "localhost")]
+    pub webauthn_rp_id: String,
+
+    /// Name shown by browsers when creating a passkey.
+    #[clap(long, env, default_value = "rest_api")]
+    pub webauthn_rp_name: String,
+
+    #[clap(long, env, default_value = "300")]
+    pub webauthn_challenge_ttl_seconds: u64,
+
+    /// Failed sign in attempts allowed for an account before it is temporarily locked.
+    #[clap(long, env, default_value = "5")]
+    pub login_max_failed_attempts: u32,
+
+    /// Failed sign in attempts allowed from a single client IP, across all accounts, before it is locked.
+    #[clap(long, env, default_value = "20")]
+    pub login_max_failed_attempts_per_ip: u32,
+
+    /// Length of the first lockout, doubled with every further failed attempt.
+    #[clap(long, env, default_value = "30")]
+    pub login_lockout_seconds: u64,
+
+    #[clap(long, env, default_value = "3600")]
+    pub login_max_lockout_seconds: u64,
+
+    /// Failed attempts are forgotten after this long without any further failures.
+    #[clap(long, env, default_value = "15")]
+    pub login_failure_window_minutes: u64,
+
+    /// Runs a one-off task instead of the server.
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl AppConfig {
+    /// Rejects combinations of settings that contradict each other, along with lifetimes too long to be added to the
+    /// current time so they fail at startup rather than on every request using them.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let access_token_ttl =
+            lifetime("ACCESS_TOKEN_TTL_SECONDS", self.access_token_ttl_seconds, 1)?;
+        let session_idle_timeout = lifetime(
+            "SESSION_IDLE_TIMEOUT_MINUTES",
+            self.session_idle_timeout_minutes,
+            60,
+        )?;
+        let session_max_age = lifetime("SESSION_MAX_AGE_DAYS", self.session_max_age_days, 86400)?;
+        let jwt_key_grace = lifetime("JWT_KEY_GRACE_HOURS", self.jwt_key_grace_hours, 3600)?;
+        let mfa_token_ttl = lifetime("MFA_TOKEN_TTL_SECONDS", self.mfa_token_ttl_seconds, 1)?;
+
+        for (name, value, unit_seconds) in [
+            (
+                "IMPERSONATION_TTL_SECONDS",
+                self.impersonation_ttl_seconds,
+                1,
+            ),
+            ("JWT_KEY_ROTATION_HOURS", self.jwt_key_rotation_hours, 3600),
+            (
+                "PASSWORD_RESET_TTL_MINUTES",
+                self.password_reset_ttl_minutes,
+                60,
+            ),
+            (
+                "EMAIL_VERIFICATION_TTL_MINUTES",
+                self.email_verification_ttl_minutes,
+                60,
+            ),
+            ("MAGIC_LINK_TTL_MINUTES", self.magic_link_ttl_minutes, 60),
+            (
+                "MAGIC_LINK_WINDOW_MINUTES",
+                self.magic_link_window_minutes,
+                60,
+            ),
+            ("API_KEY_TTL_DAYS", self.api_key_ttl_days, 86400),
+            (
+                "ACCOUNT_DELETION_GRACE_DAYS",
+                self.account_deletion_grace_days,
+                86400,
+            ),
+            ("OIDC_STATE_TTL_MINUTES", self.oidc_state_ttl_minutes, 60),
+            (
+                "WEBAUTHN_CHALLENGE_TTL_SECONDS",
+                self.webauthn_challenge_ttl_seconds,
+                1,
+            ),
+            ("LOGIN_LOCKOUT_SECONDS", self.login_lockout_seconds, 1),
+            (
+                "LOGIN_MAX_LOCKOUT_SECONDS",
+                self.login_max_lockout_seconds,
+                1,
+            ),
+            (
+                "LOGIN_FAILURE_WINDOW_MINUTES",
+                self.login_failure_window_minutes,
+                60,
+            ),
+        ] {
+            lifetime(name, value, unit_seconds)?;
+        }
+
+        ensure!(
+            !access_token_ttl.is_zero(),
+            "ACCESS_TOKEN_TTL_SECONDS must be greater than zero"
+        );
+        ensure!(
+            !mfa_token_ttl.is_zero(),
+            "MFA_TOKEN_TTL_SECONDS must be greater than zero"
+        );
+        ensure!(
+            mfa_token_ttl <= access_token_ttl / 2,
+            "MFA_TOKEN_TTL_SECONDS cannot exceed half of ACCESS_TOKEN_TTL_SECONDS"
+        );
+        ensure!(
+            access_token_ttl <= session_idle_timeout,
+            "ACCESS_TOKEN_TTL_SECONDS cannot exceed SESSION_IDLE_TIMEOUT_MINUTES"
+        );
+        ensure!(
+            session_idle_timeout <= session_max_age,
+            "SESSION_IDLE_TIMEOUT_MINUTES cannot exceed SESSION_MAX_AGE_DAYS"
+        );
+        ensure!(
+            self.jwt_algorithm == JwtAlgorithm::Hs256 || access_token_ttl <= jwt_key_grace,
+            "JWT_KEY_GRACE_HOURS must outlive ACCESS_TOKEN_TTL_SECONDS"
+        );
+
+        Ok(())
+    }
+}
+
+/// Converts a setting counted in `unit_seconds` into a duration, rejecting values that overflow or cannot be added to
+/// the current time.
+fn lifetime(name: &str, value: u64, unit_seconds: u64) -> anyhow::Result<Duration> {
+    let duration = value.checked_mul(unit_seconds).map(Duration::from_secs);
+    let expires_at = duration
+        .and_then(|duration| time::Duration::try_from(duration).ok())
+        .and_then(|duration| OffsetDateTime::now_utc().checked_add(duration));
+
+    match (duration, expires_at) {
+        (Some(duration), Some(_)) => Ok(duration),
+        _ => bail!("{} is too large", name),
+    }
+}
+
+/// Accepts single addresses as well as CIDR ranges, an address is a range of its own.
+fn parse_trusted_proxy(value: &str) -> anyhow::Result<IpNet> {
+    let value = value.trim();
+
+    match value.parse::<IpAddr>() {
+        Ok(address) => Ok(IpNet::from(address)),
+        Err(_) => Ok(value.parse::<IpNet>()?),
+    }
 }
@@ -0,0 +1,98 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::{Date, OffsetDateTime};
+use sqlx::FromRow;
+use uuid::{uuid, Uuid};
+
+use crate::database::transaction::TransactionType;
+
+/// An account along with its current balance, the opening balance plus every transaction recorded against it.
+#[derive(FromRow, Debug)]
+pub struct Account {
+    pub id: Uuid,
+    pub name: String,
+    pub acc_type: AccountType,
+    pub currency: String,
+    pub opening_balance: Decimal,
+    pub balance: Decimal,
+    pub user_id: Uuid,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl Default for Account {
+    fn default() -> Self {
+        Self {
+            id: uuid!("8e3f1a5c-7b9d-4c2e-a6f8-1d3b5e7a9c2f"),
+            name: String::from("stub account"),
+            acc_type: AccountType::default(),
+            currency: String::from("USD"),
+            opening_balance: Decimal::ZERO,
+            balance: Decimal::ZERO,
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+            updated_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// A transaction of an account along with the balance of the account right after it.
+#[derive(FromRow, Debug)]
+pub struct LedgerEntry {
+    pub id: Uuid,
+    pub amount: Decimal,
+    pub tx_type: TransactionType,
+    pub date: Date,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    pub balance: Decimal,
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynAccountsRepository = Arc<dyn AccountsRepository + Send + Sync>;
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[sqlx(type_name = "account_type")]
+pub enum AccountType {
+    #[default]
+    Checking,
+    Savings,
+    Cash,
+    CreditCard,
+}
+
+#[automock]
+#[async_trait]
+pub trait AccountsRepository {
+    async fn create_account(
+        &self,
+        user_id: Uuid,
+        name: String,
+        acc_type: AccountType,
+        currency: String,
+        opening_balance: Decimal,
+    ) -> anyhow::Result<Account>;
+
+    async fn get_account_by_id(&self, id: Uuid) -> anyhow::Result<Option<Account>>;
+
+    async fn get_accounts(&self, user_id: Uuid) -> anyhow::Result<Vec<Account>>;
+
+    async fn update_account(
+        &self,
+        id: Uuid,
+        name: String,
+        acc_type: AccountType,
+        opening_balance: Decimal,
+    ) -> anyhow::Result<Account>;
+
+    async fn delete_account(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Lists the transactions of an account, newest first, with the running balance after each of them.
+    async fn get_ledger(&self, id: Uuid) -> anyhow::Result<Vec<LedgerEntry>>;
+}
@@ -0,0 +1,155 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::{transaction::TransactionType, Database};
+
+use super::model::{Account, AccountType, AccountsRepository, LedgerEntry};
+
+#[async_trait]
+impl AccountsRepository for Database {
+    async fn create_account(
+        &self,
+        user_id: Uuid,
+        name: String,
+        acc_type: AccountType,
+        currency: String,
+        opening_balance: Decimal,
+    ) -> anyhow::Result<Account> {
+        query_as!(
+            Account,
+            r#"
+        insert into accounts (name, acc_type, currency, opening_balance, user_id)
+        values ($1::varchar, $2, $3::varchar, $4, $5)
+        returning id, name, acc_type as "acc_type: AccountType", currency, opening_balance,
+        opening_balance as "balance!", user_id, created_at, updated_at
+            "#,
+            name,
+            acc_type as _,
+            currency,
+            opening_balance,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the account")
+    }
+
+    async fn get_account_by_id(&self, id: Uuid) -> anyhow::Result<Option<Account>> {
+        query_as!(
+            Account,
+            r#"
+        select accounts.id, accounts.name, accounts.acc_type as "acc_type: AccountType",
+        accounts.currency, accounts.opening_balance,
+        accounts.opening_balance + coalesce(account_movements.net, 0) as "balance!",
+        accounts.user_id, accounts.created_at, accounts.updated_at
+        from accounts
+        left join account_movements on account_movements.account_id = accounts.id
+        where accounts.id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("account was not found")
+    }
+
+    async fn get_accounts(&self, user_id: Uuid) -> anyhow::Result<Vec<Account>> {
+        query_as!(
+            Account,
+            r#"
+        select accounts.id, accounts.name, accounts.acc_type as "acc_type: AccountType",
+        accounts.currency, accounts.opening_balance,
+        accounts.opening_balance + coalesce(account_movements.net, 0) as "balance!",
+        accounts.user_id, accounts.created_at, accounts.updated_at
+        from accounts
+        left join account_movements on account_movements.account_id = accounts.id
+        where accounts.user_id = $1
+        order by accounts.created_at
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for accounts")
+    }
+
+    async fn update_account(
+        &self,
+        id: Uuid,
+        name: String,
+        acc_type: AccountType,
+        opening_balance: Decimal,
+    ) -> anyhow::Result<Account> {
+        query_as!(
+            Account,
+            r#"
+        with updated as (
+            update accounts
+            set
+                name = $1::varchar,
+                acc_type = $2,
+                opening_balance = $3,
+                updated_at = current_timestamp
+            where id = $4
+            returning *
+        )
+        select updated.id as "id!", updated.name as "name!",
+        updated.acc_type as "acc_type!: AccountType", updated.currency as "currency!",
+        updated.opening_balance as "opening_balance!",
+        updated.opening_balance + coalesce(account_movements.net, 0) as "balance!",
+        updated.user_id as "user_id!", updated.created_at as "created_at!",
+        updated.updated_at as "updated_at!"
+        from updated
+        left join account_movements on account_movements.account_id = updated.id
+            "#,
+            name,
+            acc_type as _,
+            opening_balance,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not update the account")
+    }
+
+    async fn delete_account(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from accounts
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting account")?;
+
+        Ok(())
+    }
+
+    async fn get_ledger(&self, id: Uuid) -> anyhow::Result<Vec<LedgerEntry>> {
+        query_as!(
+            LedgerEntry,
+            r#"
+        select transactions.id, transactions.amount,
+        transactions.tx_type as "tx_type: TransactionType", transactions.date,
+        transactions.payee, transactions.note, transactions.category_id, transactions.transfer_id,
+        accounts.opening_balance + sum(
+            case when transactions.tx_type in ('Income', 'TransferIn')
+            then transactions.amount else -transactions.amount end
+        ) over (order by transactions.date, transactions.created_at, transactions.id) as "balance!"
+        from transactions
+        inner join accounts on accounts.id = transactions.account_id
+        where transactions.account_id = $1
+        order by transactions.date desc, transactions.created_at desc, transactions.id desc
+            "#,
+            id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for the account ledger")
+    }
+}
@@ -0,0 +1,66 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub exp: OffsetDateTime,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for ApiKey {
+    fn default() -> Self {
+        Self {
+            id: uuid!("6c1f8e3a-2b7d-4e95-9a0c-4d3b7f1e8a62"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            name: String::from("stub name"),
+            prefix: String::from("stub prefix"),
+            key_hash: String::from("stub key hash"),
+            scopes: vec![String::from("categories:read")],
+            exp: OffsetDateTime::from(SystemTime::now()),
+            last_used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynApiKeysRepository = Arc<dyn ApiKeysRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ApiKeysRepository {
+    async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        prefix: &str,
+        key_hash: &str,
+        scopes: Vec<String>,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<ApiKey>;
+
+    async fn get_api_key_by_prefix(&self, prefix: &str) -> anyhow::Result<Option<ApiKey>>;
+
+    async fn get_api_key_by_id(&self, id: Uuid) -> anyhow::Result<Option<ApiKey>>;
+
+    /// Returns every key of the user, newest first, including expired keys.
+    async fn get_api_keys_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<ApiKey>>;
+
+    async fn touch_api_key(&self, id: Uuid) -> anyhow::Result<()>;
+
+    async fn delete_api_key(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Deletes every key of the user, returning how many were deleted.
+    async fn delete_user_api_keys(&self, user_id: Uuid) -> anyhow::Result<u64>;
+}
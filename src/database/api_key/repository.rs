@@ -0,0 +1,132 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{ApiKey, ApiKeysRepository};
+
+#[async_trait]
+impl ApiKeysRepository for Database {
+    async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        prefix: &str,
+        key_hash: &str,
+        scopes: Vec<String>,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<ApiKey> {
+        query_as!(
+            ApiKey,
+            r#"
+        insert into api_keys (user_id, name, prefix, key_hash, scopes, exp)
+        values ($1, $2::varchar, $3::varchar, $4::varchar, $5::varchar[], $6)
+        returning *
+            "#,
+            user_id,
+            name,
+            prefix,
+            key_hash,
+            &scopes,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the API key")
+    }
+
+    async fn get_api_key_by_prefix(&self, prefix: &str) -> anyhow::Result<Option<ApiKey>> {
+        query_as!(
+            ApiKey,
+            r#"
+        select *
+        from api_keys
+        where prefix = $1::varchar
+            "#,
+            prefix,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the API key")
+    }
+
+    async fn get_api_key_by_id(&self, id: Uuid) -> anyhow::Result<Option<ApiKey>> {
+        query_as!(
+            ApiKey,
+            r#"
+        select *
+        from api_keys
+        where id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the API key")
+    }
+
+    async fn get_api_keys_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<ApiKey>> {
+        query_as!(
+            ApiKey,
+            r#"
+        select *
+        from api_keys
+        where user_id = $1
+        order by created_at desc
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while retrieving API keys")
+    }
+
+    async fn touch_api_key(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update api_keys
+        set last_used_at = current_timestamp
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred updating the API key")?;
+
+        Ok(())
+    }
+
+    async fn delete_api_key(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from api_keys
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting the API key")?;
+
+        Ok(())
+    }
+
+    async fn delete_user_api_keys(&self, user_id: Uuid) -> anyhow::Result<u64> {
+        let result = query!(
+            r#"
+        delete from api_keys
+        where user_id = $1
+        "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting the API keys of the user")?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,116 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use sqlx::types::time::{Date, OffsetDateTime};
+use sqlx::FromRow;
+use time::macros::date;
+use uuid::{uuid, Uuid};
+
+use crate::database::category::CategoryType;
+
+#[derive(FromRow, Debug)]
+pub struct Budget {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub category_id: Uuid,
+    pub month: Date,
+    pub amount: Decimal,
+    pub currency: String,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            id: uuid!("2a7c9e1b-4d6f-4b8a-9c3e-5f7a9b1d3e6c"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            category_id: uuid!("b7f9ddc7-c80d-4bf6-8573-f06e94addfb3"),
+            month: date!(2023 - 07 - 01),
+            amount: Decimal::new(50000, 2),
+            currency: String::from("USD"),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+            updated_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// The spending of a category in a month against its budget, categories without a budget have no limit. Amounts are
+/// converted into the base currency of the user, figures depending on an amount without an exchange rate are left
+/// empty.
+#[derive(FromRow, Debug, Default)]
+pub struct BudgetLine {
+    pub category_id: Uuid,
+    pub name: String,
+    pub cat_type: CategoryType,
+    pub budget_limit: Option<Decimal>,
+    pub spent: Option<Decimal>,
+    pub remaining: Option<Decimal>,
+    pub percent_used: Option<Decimal>,
+}
+
+/// The spending of every category of a type in a month against their combined budgets, figures depending on an amount
+/// without an exchange rate are left empty.
+#[derive(FromRow, Debug, Default)]
+pub struct BudgetTotal {
+    pub cat_type: CategoryType,
+    pub budget_limit: Option<Decimal>,
+    pub spent: Option<Decimal>,
+    pub remaining: Option<Decimal>,
+    pub percent_used: Option<Decimal>,
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynBudgetsRepository = Arc<dyn BudgetsRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait BudgetsRepository {
+    /// Sets the budget of a category for a month, replacing the one already set.
+    async fn upsert_budget(
+        &self,
+        user_id: Uuid,
+        category_id: Uuid,
+        month: Date,
+        amount: Decimal,
+        currency: String,
+    ) -> anyhow::Result<Budget>;
+
+    async fn get_budget(&self, category_id: Uuid, month: Date) -> anyhow::Result<Option<Budget>>;
+
+    async fn get_budgets(&self, user_id: Uuid, month: Date) -> anyhow::Result<Vec<Budget>>;
+
+    /// Returns the budgets of every month, oldest first.
+    async fn get_all_budgets(&self, user_id: Uuid) -> anyhow::Result<Vec<Budget>>;
+
+    /// Copies the budgets of one month to another, budgets already set for the target month are kept.
+    async fn copy_budgets(
+        &self,
+        user_id: Uuid,
+        from_month: Date,
+        to_month: Date,
+    ) -> anyhow::Result<u64>;
+
+    async fn delete_budget(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Sums the expenses of each category in a month, alongside its budget.
+    async fn get_budget_lines(&self, user_id: Uuid, month: Date)
+        -> anyhow::Result<Vec<BudgetLine>>;
+
+    /// Sums the expenses and budgets of a month by category type.
+    async fn get_budget_totals(
+        &self,
+        user_id: Uuid,
+        month: Date,
+    ) -> anyhow::Result<Vec<BudgetTotal>>;
+
+    /// Lists the currencies of the expenses and budgets of a month without an exchange rate into the base currency of
+    /// the user, the figures depending on those are left empty.
+    async fn get_unconverted_currencies(
+        &self,
+        user_id: Uuid,
+        month: Date,
+    ) -> anyhow::Result<Vec<String>>;
+}
@@ -0,0 +1,298 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use sqlx::types::time::Date;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::{category::CategoryType, Database};
+
+use super::model::{Budget, BudgetLine, BudgetTotal, BudgetsRepository};
+
+#[async_trait]
+impl BudgetsRepository for Database {
+    async fn upsert_budget(
+        &self,
+        user_id: Uuid,
+        category_id: Uuid,
+        month: Date,
+        amount: Decimal,
+        currency: String,
+    ) -> anyhow::Result<Budget> {
+        query_as!(
+            Budget,
+            r#"
+        insert into budgets (user_id, category_id, month, amount, currency)
+        values ($1, $2, $3, $4, $5::varchar)
+        on conflict (category_id, month) do update
+        set
+            amount = excluded.amount,
+            currency = excluded.currency,
+            updated_at = current_timestamp
+        returning *
+            "#,
+            user_id,
+            category_id,
+            month,
+            amount,
+            currency
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while setting the budget")
+    }
+
+    async fn get_budget(&self, category_id: Uuid, month: Date) -> anyhow::Result<Option<Budget>> {
+        query_as!(
+            Budget,
+            r#"
+        select *
+        from budgets
+        where category_id = $1
+        and month = $2
+            "#,
+            category_id,
+            month
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("budget was not found")
+    }
+
+    async fn get_budgets(&self, user_id: Uuid, month: Date) -> anyhow::Result<Vec<Budget>> {
+        query_as!(
+            Budget,
+            r#"
+        select *
+        from budgets
+        where user_id = $1
+        and month = $2
+            "#,
+            user_id,
+            month
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for budgets")
+    }
+
+    async fn get_all_budgets(&self, user_id: Uuid) -> anyhow::Result<Vec<Budget>> {
+        query_as!(
+            Budget,
+            r#"
+        select *
+        from budgets
+        where user_id = $1
+        order by month, created_at
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for budgets")
+    }
+
+    async fn copy_budgets(
+        &self,
+        user_id: Uuid,
+        from_month: Date,
+        to_month: Date,
+    ) -> anyhow::Result<u64> {
+        let result = query!(
+            r#"
+        insert into budgets (user_id, category_id, month, amount, currency)
+        select user_id, category_id, $3, amount, currency
+        from budgets
+        where user_id = $1
+        and month = $2
+        on conflict (category_id, month) do nothing
+            "#,
+            user_id,
+            from_month,
+            to_month
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while copying budgets")?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_budget(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from budgets
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting budget")?;
+
+        Ok(())
+    }
+
+    async fn get_budget_lines(
+        &self,
+        user_id: Uuid,
+        month: Date,
+    ) -> anyhow::Result<Vec<BudgetLine>> {
+        query_as!(
+            BudgetLine,
+            r#"
+        with spending as (
+            select category_id, sum(converted) as spent, bool_or(converted is null) as unconverted
+            from (
+                select category_id, convert_amount(amount, currency, users.base_currency, date) as converted
+                from transactions
+                join users on users.id = transactions.user_id
+                where transactions.user_id = $1
+                and tx_type = 'Expense'
+                and date >= $2
+                and date < ($2 + interval '1 month')
+            ) expenses
+            group by category_id
+        ),
+        limits as (
+            select budgets.id, budgets.category_id,
+            convert_amount(budgets.amount, budgets.currency, users.base_currency, budgets.month) as budget_limit
+            from budgets
+            join users on users.id = budgets.user_id
+            where budgets.user_id = $1
+            and budgets.month = $2
+        ),
+        lines as (
+            select
+                categories.id,
+                categories.name,
+                categories.cat_type,
+                limits.budget_limit,
+                case when spending.unconverted then null else coalesce(spending.spent, 0) end as spent
+            from categories
+            left join limits on limits.category_id = categories.id
+            left join spending on spending.category_id = categories.id
+            where categories.user_id = $1
+            and (limits.id is not null or spending.category_id is not null)
+        )
+        select
+            id as "category_id!",
+            name as "name!",
+            cat_type as "cat_type!: CategoryType",
+            budget_limit as "budget_limit?",
+            spent as "spent?",
+            budget_limit - spent as "remaining?",
+            round(spent * 100 / nullif(budget_limit, 0), 2) as "percent_used?"
+        from lines
+        order by cat_type, name
+            "#,
+            user_id,
+            month
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while summarizing budgets")
+    }
+
+    async fn get_budget_totals(
+        &self,
+        user_id: Uuid,
+        month: Date,
+    ) -> anyhow::Result<Vec<BudgetTotal>> {
+        query_as!(
+            BudgetTotal,
+            r#"
+        with spending as (
+            select category_id, sum(converted) as spent, bool_or(converted is null) as unconverted
+            from (
+                select category_id, convert_amount(amount, currency, users.base_currency, date) as converted
+                from transactions
+                join users on users.id = transactions.user_id
+                where transactions.user_id = $1
+                and tx_type = 'Expense'
+                and date >= $2
+                and date < ($2 + interval '1 month')
+            ) expenses
+            group by category_id
+        ),
+        limits as (
+            select budgets.id, budgets.category_id,
+            convert_amount(budgets.amount, budgets.currency, users.base_currency, budgets.month) as budget_limit
+            from budgets
+            join users on users.id = budgets.user_id
+            where budgets.user_id = $1
+            and budgets.month = $2
+        ),
+        lines as (
+            select
+                categories.cat_type,
+                limits.id as budget_id,
+                limits.budget_limit,
+                case when spending.unconverted then null else coalesce(spending.spent, 0) end as spent
+            from categories
+            left join limits on limits.category_id = categories.id
+            left join spending on spending.category_id = categories.id
+            where categories.user_id = $1
+            and (limits.id is not null or spending.category_id is not null)
+        ),
+        totals as (
+            select
+                cat_type,
+                case when bool_or(budget_id is not null and budget_limit is null) then null
+                else coalesce(sum(budget_limit), 0) end as budget_limit,
+                case when bool_or(spent is null) then null else sum(spent) end as spent
+            from lines
+            group by cat_type
+        )
+        select
+            cat_type as "cat_type!: CategoryType",
+            budget_limit as "budget_limit?",
+            spent as "spent?",
+            budget_limit - spent as "remaining?",
+            round(spent * 100 / nullif(budget_limit, 0), 2) as "percent_used?"
+        from totals
+        order by cat_type
+            "#,
+            user_id,
+            month
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while summarizing budgets")
+    }
+
+    async fn get_unconverted_currencies(
+        &self,
+        user_id: Uuid,
+        month: Date,
+    ) -> anyhow::Result<Vec<String>> {
+        let currencies = query!(
+            r#"
+        select distinct amounts.currency as "currency!"
+        from (
+            select amount, currency, date
+            from transactions
+            where user_id = $1
+            and tx_type = 'Expense'
+            and date >= $2
+            and date < ($2 + interval '1 month')
+            union all
+            select amount, currency, month
+            from budgets
+            where user_id = $1
+            and month = $2
+        ) amounts
+        join users on users.id = $1
+        where convert_amount(amounts.amount, amounts.currency, users.base_currency, amounts.date) is null
+        order by 1
+            "#,
+            user_id,
+            month
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while looking for missing exchange rates")?;
+
+        Ok(currencies.into_iter().map(|row| row.currency).collect())
+    }
+}
@@ -33,19 +33,14 @@ impl Default for Category {
 /// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
 pub type DynCategoriesRepository = Arc<dyn CategoriesRepository + Send + Sync>;
 
-#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 #[sqlx(type_name = "category_type")]
 pub enum CategoryType {
     Essential,
+    #[default]
     NonEssential,
 }
 
-impl Default for CategoryType {
-    fn default() -> Self {
-        Self::NonEssential
-    }
-}
-
 #[automock]
 #[async_trait]
 pub trait CategoriesRepository {
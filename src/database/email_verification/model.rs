@@ -0,0 +1,52 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct EmailVerificationToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub token_hash: String,
+    pub exp: OffsetDateTime,
+    pub used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for EmailVerificationToken {
+    fn default() -> Self {
+        Self {
+            id: uuid!("9c1d7e3a-4b6f-4f0a-8e2d-1a7b3c5d9e04"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            email: String::from("stub email"),
+            token_hash: String::from("stub token hash"),
+            exp: OffsetDateTime::from(SystemTime::now()),
+            used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynEmailVerificationsRepository = Arc<dyn EmailVerificationsRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait EmailVerificationsRepository {
+    async fn create_email_verification(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<EmailVerificationToken>;
+
+    /// Marks an unused, unexpired token as used, returning it only if this call consumed it.
+    async fn consume_email_verification(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<EmailVerificationToken>>;
+}
@@ -0,0 +1,55 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{EmailVerificationToken, EmailVerificationsRepository};
+
+#[async_trait]
+impl EmailVerificationsRepository for Database {
+    async fn create_email_verification(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<EmailVerificationToken> {
+        query_as!(
+            EmailVerificationToken,
+            r#"
+        insert into email_verification_tokens (user_id, email, token_hash, exp)
+        values ($1, $2::varchar, $3::varchar, $4)
+        returning *
+            "#,
+            user_id,
+            email,
+            token_hash,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the email verification token")
+    }
+
+    async fn consume_email_verification(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<EmailVerificationToken>> {
+        query_as!(
+            EmailVerificationToken,
+            r#"
+        update email_verification_tokens
+        set used_at = current_timestamp
+        where token_hash = $1::varchar and used_at is null and exp >= now()
+        returning *
+            "#,
+            token_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the email verification token")
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use sqlx::types::time::Date;
+
+/// One unit of the base currency is worth `rate` units of the quote currency from the effective date onwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRateFields {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: Decimal,
+    pub effective_on: Date,
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynExchangeRatesRepository = Arc<dyn ExchangeRatesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ExchangeRatesRepository {
+    /// Stores the rates atomically, replacing the rates of a currency pair already stored for the same date. Returns
+    /// the number of rates stored.
+    async fn upsert_exchange_rates(&self, rates: Vec<ExchangeRateFields>) -> anyhow::Result<u64>;
+}
@@ -0,0 +1,53 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query;
+
+use crate::database::Database;
+
+use super::model::{ExchangeRateFields, ExchangeRatesRepository};
+
+#[async_trait]
+impl ExchangeRatesRepository for Database {
+    async fn upsert_exchange_rates(&self, rates: Vec<ExchangeRateFields>) -> anyhow::Result<u64> {
+        let mut db_transaction = self
+            .pool
+            .begin()
+            .await
+            .context("could not start a transaction for the exchange rates")?;
+
+        let mut stored = 0;
+
+        for rate in rates.iter() {
+            stored += query!(
+                r#"
+        insert into exchange_rates (base_currency, quote_currency, rate, effective_on)
+        values ($1::varchar, $2::varchar, $3, $4)
+        on conflict (base_currency, quote_currency, effective_on) do update
+        set
+            rate = excluded.rate,
+            updated_at = current_timestamp
+            "#,
+                rate.base_currency,
+                rate.quote_currency,
+                rate.rate,
+                rate.effective_on
+            )
+            .execute(&mut db_transaction)
+            .await
+            .with_context(|| {
+                format!(
+                    "an unexpected error occured while storing the {}/{} rate of {}",
+                    rate.base_currency, rate.quote_currency, rate.effective_on
+                )
+            })?
+            .rows_affected();
+        }
+
+        db_transaction
+            .commit()
+            .await
+            .context("could not commit the exchange rates")?;
+
+        Ok(stored)
+    }
+}
@@ -0,0 +1,55 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct ImpersonationEvent {
+    pub id: Uuid,
+    pub actor_id: Uuid,
+    pub user_id: Uuid,
+    pub event: String,
+    pub access_token_id: Option<Uuid>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for ImpersonationEvent {
+    fn default() -> Self {
+        Self {
+            id: uuid!("3b8e1f5a-7c2d-4e9b-a6f0-1d4c8b2e7a95"),
+            actor_id: uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            event: String::from("started"),
+            access_token_id: None,
+            method: None,
+            path: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynImpersonationEventsRepository = Arc<dyn ImpersonationEventsRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ImpersonationEventsRepository {
+    async fn create_impersonation_event(
+        &self,
+        actor_id: Uuid,
+        user_id: Uuid,
+        event: &str,
+        access_token_id: Option<Uuid>,
+        method: Option<String>,
+        path: Option<String>,
+    ) -> anyhow::Result<ImpersonationEvent>;
+
+    async fn get_impersonation_events_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<ImpersonationEvent>>;
+}
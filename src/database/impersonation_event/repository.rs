@@ -0,0 +1,58 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{ImpersonationEvent, ImpersonationEventsRepository};
+
+#[async_trait]
+impl ImpersonationEventsRepository for Database {
+    async fn create_impersonation_event(
+        &self,
+        actor_id: Uuid,
+        user_id: Uuid,
+        event: &str,
+        access_token_id: Option<Uuid>,
+        method: Option<String>,
+        path: Option<String>,
+    ) -> anyhow::Result<ImpersonationEvent> {
+        query_as!(
+            ImpersonationEvent,
+            r#"
+        insert into impersonation_events (actor_id, user_id, event, access_token_id, method, path)
+        values ($1, $2, $3::varchar, $4, $5, $6)
+        returning *
+            "#,
+            actor_id,
+            user_id,
+            event,
+            access_token_id,
+            method,
+            path
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while recording the impersonation event")
+    }
+
+    async fn get_impersonation_events_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<ImpersonationEvent>> {
+        query_as!(
+            ImpersonationEvent,
+            r#"
+        select *
+        from impersonation_events
+        where user_id = $1
+        order by created_at desc
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for impersonation events")
+    }
+}
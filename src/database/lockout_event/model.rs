@@ -0,0 +1,47 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct LockoutEvent {
+    pub id: Uuid,
+    pub scope: String,
+    pub subject: String,
+    pub event: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for LockoutEvent {
+    fn default() -> Self {
+        Self {
+            id: uuid!("5a7c9e1b-3d5f-4a8c-b2e4-6f8a0c2e4b6d"),
+            scope: String::from("account"),
+            subject: String::from("stub email"),
+            event: String::from("locked"),
+            failed_attempts: 5,
+            locked_until: Some(OffsetDateTime::from(SystemTime::now())),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynLockoutEventsRepository = Arc<dyn LockoutEventsRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait LockoutEventsRepository {
+    async fn create_lockout_event(
+        &self,
+        scope: &str,
+        subject: &str,
+        event: &str,
+        failed_attempts: i32,
+        locked_until: Option<OffsetDateTime>,
+    ) -> anyhow::Result<LockoutEvent>;
+}
@@ -0,0 +1,37 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use sqlx::types::time::OffsetDateTime;
+
+use crate::database::Database;
+
+use super::{LockoutEvent, LockoutEventsRepository};
+
+#[async_trait]
+impl LockoutEventsRepository for Database {
+    async fn create_lockout_event(
+        &self,
+        scope: &str,
+        subject: &str,
+        event: &str,
+        failed_attempts: i32,
+        locked_until: Option<OffsetDateTime>,
+    ) -> anyhow::Result<LockoutEvent> {
+        query_as!(
+            LockoutEvent,
+            r#"
+        insert into lockout_events (scope, subject, event, failed_attempts, locked_until)
+        values ($1::varchar, $2::varchar, $3::varchar, $4, $5)
+        returning *
+            "#,
+            scope,
+            subject,
+            event,
+            failed_attempts,
+            locked_until
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while recording the lockout event")
+    }
+}
@@ -0,0 +1,61 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+
+#[derive(FromRow, Debug, Clone)]
+pub struct LoginThrottle {
+    pub scope: String,
+    pub subject: String,
+    pub failed_attempts: i32,
+    pub last_failed_at: OffsetDateTime,
+    pub locked_until: Option<OffsetDateTime>,
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self {
+            scope: String::from("account"),
+            subject: String::from("stub email"),
+            failed_attempts: 1,
+            last_failed_at: OffsetDateTime::from(SystemTime::now()),
+            locked_until: None,
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynLoginThrottlesRepository = Arc<dyn LoginThrottlesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait LoginThrottlesRepository {
+    async fn get_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<LoginThrottle>>;
+
+    /// Counts a failed attempt, restarting the count when nothing happened since `window_start`.
+    async fn record_failed_login(
+        &self,
+        scope: &str,
+        subject: &str,
+        window_start: &OffsetDateTime,
+    ) -> anyhow::Result<LoginThrottle>;
+
+    async fn lock_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+        locked_until: &OffsetDateTime,
+    ) -> anyhow::Result<()>;
+
+    /// Clears the failed attempts, returning the throttle that was removed if there was one.
+    async fn delete_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<LoginThrottle>>;
+}
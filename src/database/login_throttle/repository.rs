@@ -0,0 +1,104 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
+
+use crate::database::Database;
+
+use super::{LoginThrottle, LoginThrottlesRepository};
+
+#[async_trait]
+impl LoginThrottlesRepository for Database {
+    async fn get_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<LoginThrottle>> {
+        query_as!(
+            LoginThrottle,
+            r#"
+        select *
+        from login_throttles
+        where scope = $1::varchar and subject = $2::varchar
+            "#,
+            scope,
+            subject
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for the login throttle")
+    }
+
+    async fn record_failed_login(
+        &self,
+        scope: &str,
+        subject: &str,
+        window_start: &OffsetDateTime,
+    ) -> anyhow::Result<LoginThrottle> {
+        // lockouts count as activity, otherwise the backoff would start over once a long lockout expires
+        query_as!(
+            LoginThrottle,
+            r#"
+        insert into login_throttles (scope, subject, failed_attempts, last_failed_at)
+        values ($1::varchar, $2::varchar, 1, current_timestamp)
+        on conflict (scope, subject) do update
+        set
+            failed_attempts = case
+                when greatest(login_throttles.last_failed_at, login_throttles.locked_until) < $3 then 1
+                else login_throttles.failed_attempts + 1
+            end,
+            last_failed_at = current_timestamp
+        returning *
+            "#,
+            scope,
+            subject,
+            window_start
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while recording the failed login")
+    }
+
+    async fn lock_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+        locked_until: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update login_throttles
+        set locked_until = $3
+        where scope = $1::varchar and subject = $2::varchar
+        "#,
+            scope,
+            subject,
+            locked_until
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while locking the login throttle")?;
+
+        Ok(())
+    }
+
+    async fn delete_login_throttle(
+        &self,
+        scope: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<LoginThrottle>> {
+        query_as!(
+            LoginThrottle,
+            r#"
+        delete from login_throttles
+        where scope = $1::varchar and subject = $2::varchar
+        returning *
+            "#,
+            scope,
+            subject
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while clearing the login throttle")
+    }
+}
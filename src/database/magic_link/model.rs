@@ -0,0 +1,53 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct MagicLinkToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub exp: OffsetDateTime,
+    pub used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for MagicLinkToken {
+    fn default() -> Self {
+        Self {
+            id: uuid!("8c2e4b7a-3f1d-4e9a-b6c5-0d7f2a9e1b34"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            token_hash: String::from("stub token hash"),
+            exp: OffsetDateTime::from(SystemTime::now()),
+            used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynMagicLinksRepository = Arc<dyn MagicLinksRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait MagicLinksRepository {
+    async fn create_magic_link(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<MagicLinkToken>;
+
+    /// Counts the links sent to a user since `since`, used or not.
+    async fn count_magic_links_since(
+        &self,
+        user_id: Uuid,
+        since: &OffsetDateTime,
+    ) -> anyhow::Result<i64>;
+
+    /// Marks an unused, unexpired token as used, returning it only if this call consumed it.
+    async fn consume_magic_link(&self, token_hash: &str) -> anyhow::Result<Option<MagicLinkToken>>;
+}
@@ -0,0 +1,72 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{MagicLinkToken, MagicLinksRepository};
+
+#[async_trait]
+impl MagicLinksRepository for Database {
+    async fn create_magic_link(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<MagicLinkToken> {
+        query_as!(
+            MagicLinkToken,
+            r#"
+        insert into magic_link_tokens (user_id, token_hash, exp)
+        values ($1, $2::varchar, $3)
+        returning *
+            "#,
+            user_id,
+            token_hash,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the magic link token")
+    }
+
+    async fn count_magic_links_since(
+        &self,
+        user_id: Uuid,
+        since: &OffsetDateTime,
+    ) -> anyhow::Result<i64> {
+        let count = query!(
+            r#"
+        select count(*) as "count!"
+        from magic_link_tokens
+        where user_id = $1 and created_at >= $2
+            "#,
+            user_id,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while counting magic link tokens")?
+        .count;
+
+        Ok(count)
+    }
+
+    async fn consume_magic_link(&self, token_hash: &str) -> anyhow::Result<Option<MagicLinkToken>> {
+        query_as!(
+            MagicLinkToken,
+            r#"
+        update magic_link_tokens
+        set used_at = current_timestamp
+        where token_hash = $1::varchar and used_at is null and exp >= now()
+        returning *
+            "#,
+            token_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the magic link token")
+    }
+}
@@ -1,7 +1,27 @@
 mod connection;
 
+pub mod account;
+pub mod api_key;
+pub mod budget;
 pub mod category;
+pub mod email_verification;
+pub mod exchange_rate;
+pub mod impersonation_event;
+pub mod lockout_event;
+pub mod login_throttle;
+pub mod magic_link;
+pub mod oidc_login_state;
+pub mod passkey;
+pub mod password_reset;
+pub mod recovery_code;
+pub mod recurring_transaction;
+pub mod revoked_access_token;
+pub mod role;
 pub mod session;
+pub mod signing_key;
+pub mod transaction;
 pub mod user;
+pub mod user_identity;
+pub mod webauthn_challenge;
 
 pub use connection::*;
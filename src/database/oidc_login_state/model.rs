@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct OidcLoginState {
+    pub id: Uuid,
+    pub state_hash: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub exp: OffsetDateTime,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for OidcLoginState {
+    fn default() -> Self {
+        Self {
+            id: uuid!("2e6d9b41-8c3a-4f7e-a5d1-7c0b3e9f4a28"),
+            state_hash: String::from("stub state hash"),
+            code_verifier: String::from("stub code verifier"),
+            nonce: String::from("stub nonce"),
+            exp: OffsetDateTime::from(SystemTime::now()),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynOidcLoginStatesRepository = Arc<dyn OidcLoginStatesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait OidcLoginStatesRepository {
+    async fn create_oidc_login_state(
+        &self,
+        state_hash: &str,
+        code_verifier: &str,
+        nonce: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<OidcLoginState>;
+
+    /// Removes an unexpired login state, returning it only if this call consumed it.
+    async fn consume_oidc_login_state(
+        &self,
+        state_hash: &str,
+    ) -> anyhow::Result<Option<OidcLoginState>>;
+}
@@ -0,0 +1,53 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use sqlx::types::time::OffsetDateTime;
+
+use crate::database::Database;
+
+use super::{OidcLoginState, OidcLoginStatesRepository};
+
+#[async_trait]
+impl OidcLoginStatesRepository for Database {
+    async fn create_oidc_login_state(
+        &self,
+        state_hash: &str,
+        code_verifier: &str,
+        nonce: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<OidcLoginState> {
+        query_as!(
+            OidcLoginState,
+            r#"
+        insert into oidc_login_states (state_hash, code_verifier, nonce, exp)
+        values ($1::varchar, $2::varchar, $3::varchar, $4)
+        returning *
+            "#,
+            state_hash,
+            code_verifier,
+            nonce,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the OIDC login state")
+    }
+
+    async fn consume_oidc_login_state(
+        &self,
+        state_hash: &str,
+    ) -> anyhow::Result<Option<OidcLoginState>> {
+        query_as!(
+            OidcLoginState,
+            r#"
+        delete from oidc_login_states
+        where state_hash = $1::varchar and exp >= now()
+        returning *
+            "#,
+            state_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the OIDC login state")
+    }
+}
@@ -0,0 +1,64 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct Passkey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub credential_id: String,
+    pub public_key: Vec<u8>,
+    pub sign_count: i64,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for Passkey {
+    fn default() -> Self {
+        Self {
+            id: uuid!("9d4b2c7e-5a13-4f86-b0e2-1c8f6a3d7b95"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            name: String::from("stub name"),
+            credential_id: String::from("stub credential id"),
+            public_key: Vec::new(),
+            sign_count: 0,
+            last_used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynPasskeysRepository = Arc<dyn PasskeysRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait PasskeysRepository {
+    async fn create_passkey(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        credential_id: &str,
+        public_key: &[u8],
+        sign_count: i64,
+    ) -> anyhow::Result<Passkey>;
+
+    async fn get_passkey_by_credential_id(
+        &self,
+        credential_id: &str,
+    ) -> anyhow::Result<Option<Passkey>>;
+
+    async fn get_passkey_by_id(&self, id: Uuid) -> anyhow::Result<Option<Passkey>>;
+
+    async fn get_passkeys_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Passkey>>;
+
+    /// Records a successful assertion, returning `false` if the signature counter did not move forward,
+    /// which hints at a cloned authenticator. Authenticators that do not count always report zero.
+    async fn use_passkey(&self, id: Uuid, sign_count: i64) -> anyhow::Result<bool>;
+
+    async fn delete_passkey(&self, id: Uuid) -> anyhow::Result<()>;
+}
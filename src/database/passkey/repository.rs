@@ -0,0 +1,120 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{Passkey, PasskeysRepository};
+
+#[async_trait]
+impl PasskeysRepository for Database {
+    async fn create_passkey(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        credential_id: &str,
+        public_key: &[u8],
+        sign_count: i64,
+    ) -> anyhow::Result<Passkey> {
+        query_as!(
+            Passkey,
+            r#"
+        insert into passkeys (user_id, name, credential_id, public_key, sign_count)
+        values ($1, $2::varchar, $3::varchar, $4, $5)
+        returning *
+            "#,
+            user_id,
+            name,
+            credential_id,
+            public_key,
+            sign_count
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the passkey")
+    }
+
+    async fn get_passkey_by_credential_id(
+        &self,
+        credential_id: &str,
+    ) -> anyhow::Result<Option<Passkey>> {
+        query_as!(
+            Passkey,
+            r#"
+        select *
+        from passkeys
+        where credential_id = $1::varchar
+            "#,
+            credential_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the passkey")
+    }
+
+    async fn get_passkey_by_id(&self, id: Uuid) -> anyhow::Result<Option<Passkey>> {
+        query_as!(
+            Passkey,
+            r#"
+        select *
+        from passkeys
+        where id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the passkey")
+    }
+
+    async fn get_passkeys_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Passkey>> {
+        query_as!(
+            Passkey,
+            r#"
+        select *
+        from passkeys
+        where user_id = $1
+        order by created_at desc
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while retrieving the passkeys")
+    }
+
+    async fn use_passkey(&self, id: Uuid, sign_count: i64) -> anyhow::Result<bool> {
+        let result = query!(
+            r#"
+        update passkeys
+        set
+            sign_count = $1,
+            last_used_at = current_timestamp
+        where id = $2 and (sign_count < $1 or (sign_count = 0 and $1 = 0))
+        "#,
+            sign_count,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not record the passkey use")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_passkey(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from passkeys
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while deleting the passkey")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub exp: OffsetDateTime,
+    pub used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for PasswordResetToken {
+    fn default() -> Self {
+        Self {
+            id: uuid!("5d0f5cf6-7cfa-4d36-a4d3-3f5b8a1f2e90"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            token_hash: String::from("stub token hash"),
+            exp: OffsetDateTime::from(SystemTime::now()),
+            used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynPasswordResetsRepository = Arc<dyn PasswordResetsRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait PasswordResetsRepository {
+    async fn create_password_reset(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<PasswordResetToken>;
+
+    /// Marks an unused, unexpired token as used, returning it only if this call consumed it.
+    async fn consume_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<PasswordResetToken>>;
+
+    async fn delete_password_resets_by_user_id(&self, user_id: Uuid) -> anyhow::Result<()>;
+}
@@ -0,0 +1,68 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{PasswordResetToken, PasswordResetsRepository};
+
+#[async_trait]
+impl PasswordResetsRepository for Database {
+    async fn create_password_reset(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<PasswordResetToken> {
+        query_as!(
+            PasswordResetToken,
+            r#"
+        insert into password_reset_tokens (user_id, token_hash, exp)
+        values ($1, $2::varchar, $3)
+        returning *
+            "#,
+            user_id,
+            token_hash,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the password reset token")
+    }
+
+    async fn consume_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<PasswordResetToken>> {
+        query_as!(
+            PasswordResetToken,
+            r#"
+        update password_reset_tokens
+        set used_at = current_timestamp
+        where token_hash = $1::varchar and used_at is null and exp >= now()
+        returning *
+            "#,
+            token_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the password reset token")
+    }
+
+    async fn delete_password_resets_by_user_id(&self, user_id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from password_reset_tokens
+        where user_id = $1
+        "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting password reset tokens")?;
+
+        Ok(())
+    }
+}
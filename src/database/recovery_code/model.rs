@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct RecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for RecoveryCode {
+    fn default() -> Self {
+        Self {
+            id: uuid!("3b8e2f1c-5a7d-4c9e-b6f0-2d4a8c1e7f35"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            code_hash: String::from("stub code hash"),
+            used_at: None,
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynRecoveryCodesRepository = Arc<dyn RecoveryCodesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait RecoveryCodesRepository {
+    /// Replaces every recovery code of the user with the given hashes.
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> anyhow::Result<()>;
+
+    /// Marks an unused recovery code as used, returning it only if this call consumed it.
+    async fn consume_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> anyhow::Result<Option<RecoveryCode>>;
+
+    async fn delete_recovery_codes_by_user_id(&self, user_id: Uuid) -> anyhow::Result<()>;
+}
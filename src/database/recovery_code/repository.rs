@@ -0,0 +1,87 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{RecoveryCode, RecoveryCodesRepository};
+
+#[async_trait]
+impl RecoveryCodesRepository for Database {
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .context("could not start a transaction for recovery codes")?;
+
+        query!(
+            r#"
+        delete from recovery_codes
+        where user_id = $1
+        "#,
+            user_id
+        )
+        .execute(&mut transaction)
+        .await
+        .context("an unexpected error occurred deleting recovery codes")?;
+
+        query!(
+            r#"
+        insert into recovery_codes (user_id, code_hash)
+        select $1, unnest($2::varchar[])
+        "#,
+            user_id,
+            &code_hashes
+        )
+        .execute(&mut transaction)
+        .await
+        .context("an unexpected error occured while creating recovery codes")?;
+
+        transaction
+            .commit()
+            .await
+            .context("could not commit recovery codes")
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> anyhow::Result<Option<RecoveryCode>> {
+        query_as!(
+            RecoveryCode,
+            r#"
+        update recovery_codes
+        set used_at = current_timestamp
+        where user_id = $1 and code_hash = $2::varchar and used_at is null
+        returning *
+            "#,
+            user_id,
+            code_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the recovery code")
+    }
+
+    async fn delete_recovery_codes_by_user_id(&self, user_id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from recovery_codes
+        where user_id = $1
+        "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting recovery codes")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::{Date, OffsetDateTime};
+use sqlx::FromRow;
+use time::macros::date;
+use uuid::{uuid, Uuid};
+
+use crate::database::transaction::{TransactionFields, TransactionType};
+
+/// A template of a transaction along with the schedule it repeats on.
+#[derive(FromRow, Debug, Clone)]
+pub struct RecurringTransaction {
+    pub id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub tx_type: TransactionType,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Uuid,
+    pub frequency: RecurrenceFrequency,
+    pub interval: i32,
+    pub starts_on: Date,
+    pub until: Option<Date>,
+    pub count: Option<i32>,
+    pub next_date: Option<Date>,
+    pub materialized_count: i32,
+    pub user_id: Uuid,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl Default for RecurringTransaction {
+    fn default() -> Self {
+        Self {
+            id: uuid!("3f5b7d9e-1a2c-4e4f-8a6b-c8d0e2f4a6b8"),
+            amount: Decimal::new(120000, 2),
+            currency: String::from("USD"),
+            tx_type: TransactionType::default(),
+            payee: String::from("stub landlord"),
+            note: None,
+            category_id: Some(uuid!("b7f9ddc7-c80d-4bf6-8573-f06e94addfb3")),
+            account_id: uuid!("8e3f1a5c-7b9d-4c2e-a6f8-1d3b5e7a9c2f"),
+            frequency: RecurrenceFrequency::Monthly,
+            interval: 1,
+            starts_on: date!(2023 - 01 - 31),
+            until: None,
+            count: None,
+            next_date: Some(date!(2023 - 01 - 31)),
+            materialized_count: 0,
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+            updated_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+impl RecurringTransaction {
+    pub fn rule(&self) -> RecurrenceRule {
+        RecurrenceRule {
+            frequency: self.frequency,
+            interval: self.interval as u32,
+            until: self.until,
+            count: self.count.map(|count| count as u32),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynRecurringTransactionsRepository =
+    Arc<dyn RecurringTransactionsRepository + Send + Sync>;
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[sqlx(type_name = "recurrence_frequency")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// When a recurring transaction repeats, mirroring the `FREQ`, `INTERVAL`, `UNTIL` and `COUNT` parts of an RFC 5545
+/// recurrence rule. A rule ends on a date or after a number of occurrences, never both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub until: Option<Date>,
+    pub count: Option<u32>,
+}
+
+#[automock]
+#[async_trait]
+pub trait RecurringTransactionsRepository {
+    /// Creates a recurring transaction whose first occurrence is the date of the fields.
+    async fn create_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        fields: TransactionFields,
+        rule: RecurrenceRule,
+    ) -> anyhow::Result<RecurringTransaction>;
+
+    async fn get_recurring_transaction_by_id(
+        &self,
+        id: Uuid,
+    ) -> anyhow::Result<Option<RecurringTransaction>>;
+
+    async fn get_recurring_transactions(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<RecurringTransaction>>;
+
+    /// Updates the template of a recurring transaction, its schedule is left untouched.
+    async fn update_recurring_transaction(
+        &self,
+        id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<RecurringTransaction>;
+
+    async fn delete_recurring_transaction(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Lists the recurring transactions of every user with an occurrence due on or before the date.
+    async fn get_due_recurring_transactions(
+        &self,
+        date: Date,
+    ) -> anyhow::Result<Vec<RecurringTransaction>>;
+
+    /// Records the occurrences of a recurring transaction and moves its schedule forward atomically, occurrences
+    /// already recorded are skipped. Returns the number of transactions created.
+    async fn create_occurrences(
+        &self,
+        recurring_transaction: &RecurringTransaction,
+        dates: Vec<Date>,
+        next_date: Option<Date>,
+    ) -> anyhow::Result<u64>;
+}
@@ -0,0 +1,229 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::Date;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::transaction::{TransactionFields, TransactionType};
+use crate::database::Database;
+
+use super::model::{
+    RecurrenceFrequency, RecurrenceRule, RecurringTransaction, RecurringTransactionsRepository,
+};
+
+#[async_trait]
+impl RecurringTransactionsRepository for Database {
+    async fn create_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        fields: TransactionFields,
+        rule: RecurrenceRule,
+    ) -> anyhow::Result<RecurringTransaction> {
+        query_as!(
+            RecurringTransaction,
+            r#"
+        insert into recurring_transactions (amount, currency, tx_type, payee, note, category_id, account_id,
+        frequency, interval, starts_on, until, count, next_date, user_id)
+        values ($1, $2::varchar, $3, $4::varchar, $5, $6, $7, $8, $9, $10, $11, $12, $10, $13)
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", payee, note, category_id,
+        account_id, frequency as "frequency: RecurrenceFrequency", interval, starts_on, until, count,
+        next_date, materialized_count, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            fields.tx_type as _,
+            fields.payee,
+            fields.note,
+            fields.category_id,
+            fields.account_id,
+            rule.frequency as _,
+            rule.interval as i32,
+            fields.date,
+            rule.until,
+            rule.count.map(|count| count as i32),
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the recurring transaction")
+    }
+
+    async fn get_recurring_transaction_by_id(
+        &self,
+        id: Uuid,
+    ) -> anyhow::Result<Option<RecurringTransaction>> {
+        query_as!(
+            RecurringTransaction,
+            r#"
+        select id, amount, currency, tx_type as "tx_type: TransactionType", payee, note, category_id,
+        account_id, frequency as "frequency: RecurrenceFrequency", interval, starts_on, until, count,
+        next_date, materialized_count, user_id, created_at, updated_at
+        from recurring_transactions
+        where id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("recurring transaction was not found")
+    }
+
+    async fn get_recurring_transactions(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<RecurringTransaction>> {
+        query_as!(
+            RecurringTransaction,
+            r#"
+        select id, amount, currency, tx_type as "tx_type: TransactionType", payee, note, category_id,
+        account_id, frequency as "frequency: RecurrenceFrequency", interval, starts_on, until, count,
+        next_date, materialized_count, user_id, created_at, updated_at
+        from recurring_transactions
+        where user_id = $1
+        order by next_date nulls last, created_at
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for recurring transactions")
+    }
+
+    async fn update_recurring_transaction(
+        &self,
+        id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<RecurringTransaction> {
+        query_as!(
+            RecurringTransaction,
+            r#"
+        update recurring_transactions
+        set
+            amount = $1,
+            currency = $2::varchar,
+            tx_type = $3,
+            payee = $4::varchar,
+            note = $5,
+            category_id = $6,
+            account_id = $7,
+            updated_at = current_timestamp
+        where id = $8
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", payee, note, category_id,
+        account_id, frequency as "frequency: RecurrenceFrequency", interval, starts_on, until, count,
+        next_date, materialized_count, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            fields.tx_type as _,
+            fields.payee,
+            fields.note,
+            fields.category_id,
+            fields.account_id,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not update the recurring transaction")
+    }
+
+    async fn delete_recurring_transaction(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from recurring_transactions
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting recurring transaction")?;
+
+        Ok(())
+    }
+
+    async fn get_due_recurring_transactions(
+        &self,
+        date: Date,
+    ) -> anyhow::Result<Vec<RecurringTransaction>> {
+        query_as!(
+            RecurringTransaction,
+            r#"
+        select id, amount, currency, tx_type as "tx_type: TransactionType", payee, note, category_id,
+        account_id, frequency as "frequency: RecurrenceFrequency", interval, starts_on, until, count,
+        next_date, materialized_count, user_id, created_at, updated_at
+        from recurring_transactions
+        where next_date <= $1
+        order by next_date
+            "#,
+            date,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for due recurring transactions")
+    }
+
+    async fn create_occurrences(
+        &self,
+        recurring_transaction: &RecurringTransaction,
+        dates: Vec<Date>,
+        next_date: Option<Date>,
+    ) -> anyhow::Result<u64> {
+        let mut db_transaction = self
+            .pool
+            .begin()
+            .await
+            .context("could not start a transaction for the recurring transaction")?;
+
+        let mut created = 0;
+
+        for date in dates.iter() {
+            created += query!(
+                r#"
+        insert into transactions (amount, currency, tx_type, date, payee, note, category_id, account_id,
+        recurring_id, user_id)
+        values ($1, $2::varchar, $3, $4, $5::varchar, $6, $7, $8, $9, $10)
+        on conflict (recurring_id, date) do nothing
+            "#,
+                recurring_transaction.amount,
+                recurring_transaction.currency,
+                recurring_transaction.tx_type.clone() as _,
+                date,
+                recurring_transaction.payee,
+                recurring_transaction.note,
+                recurring_transaction.category_id,
+                recurring_transaction.account_id,
+                recurring_transaction.id,
+                recurring_transaction.user_id
+            )
+            .execute(&mut db_transaction)
+            .await
+            .context("an unexpected error occured while recording an occurrence")?
+            .rows_affected();
+        }
+
+        // the schedule is set rather than incremented, so instances recording the same occurrences agree on it
+        query!(
+            r#"
+        update recurring_transactions
+        set
+            next_date = $1,
+            materialized_count = $2,
+            updated_at = current_timestamp
+        where id = $3
+        "#,
+            next_date,
+            recurring_transaction.materialized_count + dates.len() as i32,
+            recurring_transaction.id
+        )
+        .execute(&mut db_transaction)
+        .await
+        .context("an unexpected error occured while moving the schedule forward")?;
+
+        db_transaction
+            .commit()
+            .await
+            .context("could not commit the occurrences")?;
+
+        Ok(created)
+    }
+}
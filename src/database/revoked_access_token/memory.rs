@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::RevokedAccessTokensRepository;
+
+/// Keeps revoked access tokens in memory, entries are evicted once the tokens they deny have expired.
+/// Revocations are neither shared between instances nor kept across restarts.
+#[derive(Default)]
+pub struct InMemoryRevokedAccessTokensRepository {
+    /// Expiry of each revoked token, keyed by `jti`.
+    tokens: Mutex<HashMap<Uuid, OffsetDateTime>>,
+    /// The `issued_before` cutoff and expiry of the revocations, keyed by user.
+    users: Mutex<HashMap<Uuid, (OffsetDateTime, OffsetDateTime)>>,
+}
+
+impl InMemoryRevokedAccessTokensRepository {
+    fn evict_expired(&self) {
+        let now = OffsetDateTime::now_utc();
+
+        self.tokens
+            .lock()
+            .unwrap()
+            .retain(|_, expires_at| *expires_at > now);
+        self.users
+            .lock()
+            .unwrap()
+            .retain(|_, (_, expires_at)| *expires_at > now);
+    }
+}
+
+#[async_trait]
+impl RevokedAccessTokensRepository for InMemoryRevokedAccessTokensRepository {
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        self.evict_expired();
+
+        self.tokens.lock().unwrap().insert(jti, *expires_at);
+
+        Ok(())
+    }
+
+    async fn consume_token(&self, jti: Uuid, expires_at: &OffsetDateTime) -> anyhow::Result<bool> {
+        self.evict_expired();
+
+        let mut tokens = self.tokens.lock().unwrap();
+
+        if tokens.contains_key(&jti) {
+            return Ok(false);
+        }
+
+        tokens.insert(jti, *expires_at);
+
+        Ok(true)
+    }
+
+    async fn revoke_user_access_tokens(
+        &self,
+        user_id: Uuid,
+        issued_before: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        self.evict_expired();
+
+        self.users
+            .lock()
+            .unwrap()
+            .entry(user_id)
+            .and_modify(|(existing_issued_before, existing_expires_at)| {
+                *existing_issued_before = (*existing_issued_before).max(*issued_before);
+                *existing_expires_at = (*existing_expires_at).max(*expires_at);
+            })
+            .or_insert((*issued_before, *expires_at));
+
+        Ok(())
+    }
+
+    async fn is_access_token_revoked(
+        &self,
+        jti: Uuid,
+        user_id: Uuid,
+        issued_at: &OffsetDateTime,
+    ) -> anyhow::Result<bool> {
+        let now = OffsetDateTime::now_utc();
+
+        let token_revoked = self
+            .tokens
+            .lock()
+            .unwrap()
+            .get(&jti)
+            .map_or(false, |expires_at| *expires_at > now);
+
+        let user_revoked = self
+            .users
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .map_or(false, |(issued_before, expires_at)| {
+                *expires_at > now && issued_before > issued_at
+            });
+
+        Ok(token_revoked || user_revoked)
+    }
+
+    async fn delete_expired_revoked_access_tokens(&self) -> anyhow::Result<()> {
+        self.evict_expired();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,6 @@
+mod memory;
+mod model;
+mod repository;
+
+pub use memory::*;
+pub use model::*;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynRevokedAccessTokensRepository = Arc<dyn RevokedAccessTokensRepository + Send + Sync>;
+
+/// Denylist of access tokens revoked before they expired. Entries only need to outlive the tokens they deny.
+#[automock]
+#[async_trait]
+pub trait RevokedAccessTokensRepository {
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()>;
+
+    /// Revokes a single-use token, returning whether it was still unused. Racing attempts are settled by the store so
+    /// only one of them wins.
+    async fn consume_token(&self, jti: Uuid, expires_at: &OffsetDateTime) -> anyhow::Result<bool>;
+
+    /// Revokes every access token issued to the user before `issued_before`, `expires_at` is when the last of them expires.
+    async fn revoke_user_access_tokens(
+        &self,
+        user_id: Uuid,
+        issued_before: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()>;
+
+    async fn is_access_token_revoked(
+        &self,
+        jti: Uuid,
+        user_id: Uuid,
+        issued_at: &OffsetDateTime,
+    ) -> anyhow::Result<bool>;
+
+    async fn delete_expired_revoked_access_tokens(&self) -> anyhow::Result<()>;
+}
@@ -0,0 +1,124 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::RevokedAccessTokensRepository;
+
+#[async_trait]
+impl RevokedAccessTokensRepository for Database {
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        query!(
+            r#"
+        insert into revoked_access_tokens (jti, expires_at)
+        values ($1, $2)
+        on conflict (jti) do nothing
+        "#,
+            jti,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while revoking the access token")?;
+
+        Ok(())
+    }
+
+    async fn consume_token(&self, jti: Uuid, expires_at: &OffsetDateTime) -> anyhow::Result<bool> {
+        let result = query!(
+            r#"
+        insert into revoked_access_tokens (jti, expires_at)
+        values ($1, $2)
+        on conflict (jti) do nothing
+        "#,
+            jti,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the token")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_user_access_tokens(
+        &self,
+        user_id: Uuid,
+        issued_before: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        query!(
+            r#"
+        insert into revoked_user_access_tokens (user_id, issued_before, expires_at)
+        values ($1, $2, $3)
+        on conflict (user_id) do update
+        set
+            issued_before = greatest(revoked_user_access_tokens.issued_before, excluded.issued_before),
+            expires_at = greatest(revoked_user_access_tokens.expires_at, excluded.expires_at)
+        "#,
+            user_id,
+            issued_before,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while revoking the access tokens of the user")?;
+
+        Ok(())
+    }
+
+    async fn is_access_token_revoked(
+        &self,
+        jti: Uuid,
+        user_id: Uuid,
+        issued_at: &OffsetDateTime,
+    ) -> anyhow::Result<bool> {
+        let result = query!(
+            r#"
+        select
+            exists (select 1 from revoked_access_tokens where jti = $1)
+            or exists (select 1 from revoked_user_access_tokens where user_id = $2 and issued_before > $3)
+            as "revoked!"
+        "#,
+            jti,
+            user_id,
+            issued_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while checking if the access token is revoked")?;
+
+        Ok(result.revoked)
+    }
+
+    async fn delete_expired_revoked_access_tokens(&self) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from revoked_access_tokens
+        where expires_at <= now()
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while removing expired revoked access tokens")?;
+
+        query!(
+            r#"
+        delete from revoked_user_access_tokens
+        where expires_at <= now()
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while removing expired revoked access tokens")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug, Clone)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Self {
+            id: uuid!("4f2a9c6e-1d8b-4b3f-a7e5-0c9d2e6b8f14"),
+            name: String::from("user"),
+            permissions: vec![
+                String::from("categories:read"),
+                String::from("categories:write"),
+            ],
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// The roles of a user along with every permission they grant, as embedded in access tokens.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessGrants {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+impl AccessGrants {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|held_role| held_role == role)
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions
+            .iter()
+            .any(|held_permission| held_permission == permission)
+    }
+}
+
+impl From<Vec<Role>> for AccessGrants {
+    fn from(roles: Vec<Role>) -> Self {
+        let mut permissions: Vec<String> = roles
+            .iter()
+            .flat_map(|role| role.permissions.iter().cloned())
+            .collect();
+
+        permissions.sort();
+        permissions.dedup();
+
+        Self {
+            roles: roles.into_iter().map(|role| role.name).collect(),
+            permissions,
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynRolesRepository = Arc<dyn RolesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait RolesRepository {
+    async fn get_role_by_name(&self, name: &str) -> anyhow::Result<Option<Role>>;
+
+    async fn get_roles_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Role>>;
+
+    async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> anyhow::Result<()>;
+
+    async fn revoke_role(&self, user_id: Uuid, role_id: Uuid) -> anyhow::Result<()>;
+}
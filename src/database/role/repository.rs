@@ -0,0 +1,77 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{Role, RolesRepository};
+
+#[async_trait]
+impl RolesRepository for Database {
+    async fn get_role_by_name(&self, name: &str) -> anyhow::Result<Option<Role>> {
+        query_as!(
+            Role,
+            r#"
+        select *
+        from roles
+        where name = $1::varchar
+            "#,
+            name,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the role")
+    }
+
+    async fn get_roles_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Role>> {
+        query_as!(
+            Role,
+            r#"
+        select roles.*
+        from roles
+        inner join user_roles
+        on roles.id = user_roles.role_id
+        where user_roles.user_id = $1
+        order by roles.name
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while retrieving user roles")
+    }
+
+    async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        insert into user_roles (user_id, role_id)
+        values ($1, $2)
+        on conflict do nothing
+        "#,
+            user_id,
+            role_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred assigning the role")?;
+
+        Ok(())
+    }
+
+    async fn revoke_role(&self, user_id: Uuid, role_id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from user_roles
+        where user_id = $1 and role_id = $2
+        "#,
+            user_id,
+            role_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred revoking the role")?;
+
+        Ok(())
+    }
+}
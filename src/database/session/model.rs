@@ -1,4 +1,7 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
 use mockall::automock;
@@ -13,6 +16,13 @@ pub struct Session {
     pub user_id: Uuid,
     pub exp: OffsetDateTime,
     pub user_agent: String,
+    pub created_at: OffsetDateTime,
+    pub refresh_token_id: Uuid,
+    pub ip_address: Option<String>,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device: Option<String>,
+    pub last_seen_at: OffsetDateTime,
 }
 
 impl Default for Session {
@@ -22,10 +32,27 @@ impl Default for Session {
             user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
             exp: OffsetDateTime::from(SystemTime::now()),
             user_agent: String::from("stub user agent"),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+            refresh_token_id: uuid!("2e3d5d1e-6f0f-4b0e-9d54-5b1a3f3f7c11"),
+            ip_address: None,
+            browser: None,
+            os: None,
+            device: None,
+            last_seen_at: OffsetDateTime::from(SystemTime::now()),
         }
     }
 }
 
+/// What we know about the client a session is started from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionMetadata {
+    pub user_agent: String,
+    pub ip_address: Option<String>,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device: Option<String>,
+}
+
 /// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
 pub type DynSessionsRepository = Arc<dyn SessionsRepository + Send + Sync>;
 
@@ -35,9 +62,29 @@ pub trait SessionsRepository {
     async fn new_session(
         &self,
         user_id: Uuid,
-        user_agent: &str,
+        metadata: &SessionMetadata,
         exp: &OffsetDateTime,
     ) -> anyhow::Result<Session>;
 
     async fn get_user_by_session_id(&self, id: Uuid) -> anyhow::Result<Option<User>>;
+
+    async fn get_session_by_id(&self, id: Uuid) -> anyhow::Result<Option<Session>>;
+
+    /// Swaps the session's current refresh token for a new one, only if `refresh_token_id` is still current.
+    /// The session is extended until `exp`, but never beyond `max_age` after it was created, and marked as seen.
+    async fn rotate_refresh_token(
+        &self,
+        id: Uuid,
+        refresh_token_id: Uuid,
+        exp: &OffsetDateTime,
+        max_age: Duration,
+    ) -> anyhow::Result<Option<Session>>;
+
+    async fn get_sessions_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Session>>;
+
+    async fn delete_session(&self, id: Uuid) -> anyhow::Result<()>;
+
+    async fn delete_other_sessions(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<u64>;
+
+    async fn delete_sessions_by_user_id(&self, user_id: Uuid) -> anyhow::Result<u64>;
 }
@@ -1,31 +1,38 @@
-use anyhow::Context;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use sqlx::query_as;
+use sqlx::postgres::types::PgInterval;
 use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
 use uuid::Uuid;
 
 use crate::database::user::User;
 use crate::database::Database;
 
-use super::{Session, SessionsRepository};
+use super::{Session, SessionMetadata, SessionsRepository};
 
 #[async_trait]
 impl SessionsRepository for Database {
     async fn new_session(
         &self,
         user_id: Uuid,
-        user_agent: &str,
+        metadata: &SessionMetadata,
         exp: &OffsetDateTime,
     ) -> anyhow::Result<Session> {
         query_as!(
             Session,
             r#"
-        insert into sessions (user_id,user_agent,exp)
-        values ($1,$2,$3)
+        insert into sessions (user_id,user_agent,ip_address,browser,os,device,exp)
+        values ($1,$2,$3,$4,$5,$6,$7)
         returning *
             "#,
             user_id,
-            user_agent,
+            metadata.user_agent,
+            metadata.ip_address,
+            metadata.browser,
+            metadata.os,
+            metadata.device,
             exp
         )
         .fetch_one(&self.pool)
@@ -48,4 +55,113 @@ impl SessionsRepository for Database {
         .await
         .context("user was not found")
     }
+
+    async fn get_session_by_id(&self, id: Uuid) -> anyhow::Result<Option<Session>> {
+        query_as!(
+            Session,
+            r#"
+        select *
+        from sessions
+        where id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("session was not found")
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        id: Uuid,
+        refresh_token_id: Uuid,
+        exp: &OffsetDateTime,
+        max_age: Duration,
+    ) -> anyhow::Result<Option<Session>> {
+        let max_age = PgInterval::try_from(max_age)
+            .map_err(|err| anyhow!(err))
+            .context("the maximum session age is out of range")?;
+
+        query_as!(
+            Session,
+            r#"
+        update sessions
+        set
+            refresh_token_id = uuid_generate_v4(),
+            exp = least($3, created_at + $4),
+            last_seen_at = current_timestamp
+        where id = $1 and refresh_token_id = $2 and exp >= now() and created_at + $4 >= now()
+        returning *
+            "#,
+            id,
+            refresh_token_id,
+            exp,
+            max_age
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while rotating the refresh token")
+    }
+
+    async fn get_sessions_by_user_id(&self, user_id: Uuid) -> anyhow::Result<Vec<Session>> {
+        query_as!(
+            Session,
+            r#"
+        select *
+        from sessions
+        where user_id = $1 and exp >= now()
+        order by created_at desc
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for user sessions")
+    }
+
+    async fn delete_session(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from sessions
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting session")?;
+
+        Ok(())
+    }
+
+    async fn delete_other_sessions(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<u64> {
+        let result = query!(
+            r#"
+        delete from sessions
+        where user_id = $1 and id <> $2
+        "#,
+            user_id,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting sessions")?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_sessions_by_user_id(&self, user_id: Uuid) -> anyhow::Result<u64> {
+        let result = query!(
+            r#"
+        delete from sessions
+        where user_id = $1
+        "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting sessions")?;
+
+        Ok(result.rows_affected())
+    }
 }
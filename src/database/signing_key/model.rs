@@ -0,0 +1,57 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug, Clone)]
+pub struct SigningKey {
+    pub id: Uuid,
+    pub kid: String,
+    pub algorithm: String,
+    /// Base64 encoded DER of the private key, PKCS#8 for EdDSA and PKCS#1 for RSA keys.
+    pub private_key: String,
+    /// Public half of the key as a JSON Web Key.
+    pub public_jwk: String,
+    pub activates_at: OffsetDateTime,
+    pub expires_at: OffsetDateTime,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for SigningKey {
+    fn default() -> Self {
+        Self {
+            id: uuid!("7d3f5b9e-1a4c-4e8b-9f2d-6c0a8e4b2d71"),
+            kid: String::from("stub kid"),
+            algorithm: String::from("EdDSA"),
+            private_key: String::from("stub private key"),
+            public_jwk: String::from("stub public jwk"),
+            activates_at: OffsetDateTime::from(SystemTime::now()),
+            expires_at: OffsetDateTime::from(SystemTime::now()),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynSigningKeysRepository = Arc<dyn SigningKeysRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait SigningKeysRepository {
+    async fn create_signing_key(
+        &self,
+        kid: &str,
+        algorithm: &str,
+        private_key: &str,
+        public_jwk: &str,
+        activates_at: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<SigningKey>;
+
+    /// Returns the unexpired keys, the most recently activated first.
+    async fn get_signing_keys(&self) -> anyhow::Result<Vec<SigningKey>>;
+
+    async fn delete_expired_signing_keys(&self) -> anyhow::Result<()>;
+}
@@ -0,0 +1,68 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
+
+use crate::database::Database;
+
+use super::{SigningKey, SigningKeysRepository};
+
+#[async_trait]
+impl SigningKeysRepository for Database {
+    async fn create_signing_key(
+        &self,
+        kid: &str,
+        algorithm: &str,
+        private_key: &str,
+        public_jwk: &str,
+        activates_at: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> anyhow::Result<SigningKey> {
+        query_as!(
+            SigningKey,
+            r#"
+        insert into signing_keys (kid, algorithm, private_key, public_jwk, activates_at, expires_at)
+        values ($1::varchar, $2::varchar, $3::varchar, $4::varchar, $5, $6)
+        returning *
+            "#,
+            kid,
+            algorithm,
+            private_key,
+            public_jwk,
+            activates_at,
+            expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the signing key")
+    }
+
+    async fn get_signing_keys(&self) -> anyhow::Result<Vec<SigningKey>> {
+        query_as!(
+            SigningKey,
+            r#"
+        select *
+        from signing_keys
+        where expires_at > now()
+        order by activates_at desc
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while retrieving the signing keys")
+    }
+
+    async fn delete_expired_signing_keys(&self) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from signing_keys
+        where expires_at <= now()
+        "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while removing expired signing keys")?;
+
+        Ok(())
+    }
+}
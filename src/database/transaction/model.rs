@@ -0,0 +1,139 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::{Date, OffsetDateTime};
+use sqlx::FromRow;
+use time::macros::date;
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct Transaction {
+    pub id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub tx_type: TransactionType,
+    pub date: Date,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    pub recurring_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self {
+            id: uuid!("6d2c8e4a-1f3b-4a5d-9c7e-0b2d4f6a8c1e"),
+            amount: Decimal::new(1250, 2),
+            currency: String::from("USD"),
+            tx_type: TransactionType::default(),
+            date: date!(2023 - 07 - 17),
+            payee: String::from("stub payee"),
+            note: None,
+            category_id: Some(uuid!("b7f9ddc7-c80d-4bf6-8573-f06e94addfb3")),
+            account_id: Some(uuid!("8e3f1a5c-7b9d-4c2e-a6f8-1d3b5e7a9c2f")),
+            transfer_id: None,
+            recurring_id: None,
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+            updated_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynTransactionsRepository = Arc<dyn TransactionsRepository + Send + Sync>;
+
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[sqlx(type_name = "transaction_type")]
+pub enum TransactionType {
+    #[default]
+    Expense,
+    Income,
+    /// The outgoing leg of a transfer between two accounts.
+    TransferOut,
+    /// The incoming leg of a transfer between two accounts.
+    TransferIn,
+}
+
+impl TransactionType {
+    pub fn is_transfer(&self) -> bool {
+        matches!(self, Self::TransferOut | Self::TransferIn)
+    }
+}
+
+/// The fields of a transaction as they are written, shared by creates and updates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionFields {
+    pub amount: Decimal,
+    pub currency: String,
+    pub tx_type: TransactionType,
+    pub date: Date,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+}
+
+/// Money moved between two accounts of a user, recorded as a pair of linked transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferFields {
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub date: Date,
+    pub note: Option<String>,
+}
+
+/// Narrows down the transactions of a user, every filter left empty is ignored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TransactionFilter {
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+}
+
+#[automock]
+#[async_trait]
+pub trait TransactionsRepository {
+    async fn create_transaction(
+        &self,
+        user_id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<Transaction>;
+
+    async fn get_transaction_by_id(&self, id: Uuid) -> anyhow::Result<Option<Transaction>>;
+
+    async fn get_transactions(
+        &self,
+        user_id: Uuid,
+        filter: TransactionFilter,
+    ) -> anyhow::Result<Vec<Transaction>>;
+
+    async fn update_transaction(
+        &self,
+        id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<Transaction>;
+
+    async fn delete_transaction(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Records both legs of a transfer atomically, returning the outgoing leg first.
+    async fn create_transfer(
+        &self,
+        user_id: Uuid,
+        fields: TransferFields,
+    ) -> anyhow::Result<(Transaction, Transaction)>;
+
+    /// Removes both legs of a transfer.
+    async fn delete_transfer(&self, transfer_id: Uuid) -> anyhow::Result<()>;
+}
@@ -0,0 +1,224 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::model::{
+    Transaction, TransactionFields, TransactionFilter, TransactionType, TransactionsRepository,
+    TransferFields,
+};
+
+#[async_trait]
+impl TransactionsRepository for Database {
+    async fn create_transaction(
+        &self,
+        user_id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<Transaction> {
+        query_as!(
+            Transaction,
+            r#"
+        insert into transactions (amount, currency, tx_type, date, payee, note, category_id, account_id, user_id)
+        values ($1, $2::varchar, $3, $4, $5::varchar, $6, $7, $8, $9)
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            fields.tx_type as _,
+            fields.date,
+            fields.payee,
+            fields.note,
+            fields.category_id,
+            fields.account_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the transaction")
+    }
+
+    async fn get_transaction_by_id(&self, id: Uuid) -> anyhow::Result<Option<Transaction>> {
+        query_as!(
+            Transaction,
+            r#"
+        select id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+        from transactions
+        where id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("transaction was not found")
+    }
+
+    async fn get_transactions(
+        &self,
+        user_id: Uuid,
+        filter: TransactionFilter,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        query_as!(
+            Transaction,
+            r#"
+        select id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+        from transactions
+        where user_id = $1
+        and ($2::date is null or date >= $2)
+        and ($3::date is null or date <= $3)
+        and ($4::uuid is null or category_id = $4)
+        and ($5::uuid is null or account_id = $5)
+        order by date desc, created_at desc
+            "#,
+            user_id,
+            filter.from,
+            filter.to,
+            filter.category_id,
+            filter.account_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while querying for transactions")
+    }
+
+    async fn update_transaction(
+        &self,
+        id: Uuid,
+        fields: TransactionFields,
+    ) -> anyhow::Result<Transaction> {
+        query_as!(
+            Transaction,
+            r#"
+        update transactions
+        set
+            amount = $1,
+            currency = $2::varchar,
+            tx_type = $3,
+            date = $4,
+            payee = $5::varchar,
+            note = $6,
+            category_id = $7,
+            account_id = $8,
+            updated_at = current_timestamp
+        where id = $9
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            fields.tx_type as _,
+            fields.date,
+            fields.payee,
+            fields.note,
+            fields.category_id,
+            fields.account_id,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not update the transaction")
+    }
+
+    async fn delete_transaction(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from transactions
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting transaction")?;
+
+        Ok(())
+    }
+
+    async fn create_transfer(
+        &self,
+        user_id: Uuid,
+        fields: TransferFields,
+    ) -> anyhow::Result<(Transaction, Transaction)> {
+        let transfer_id = Uuid::new_v4();
+
+        let mut db_transaction = self
+            .pool
+            .begin()
+            .await
+            .context("could not start a transaction for the transfer")?;
+
+        let outgoing = query_as!(
+            Transaction,
+            r#"
+        insert into transactions (amount, currency, tx_type, date, payee, note, account_id, transfer_id, user_id)
+        select $1, $2::varchar, $3, $4, name, $5, $6, $7, $8
+        from accounts
+        where id = $9
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            TransactionType::TransferOut as _,
+            fields.date,
+            fields.note,
+            fields.from_account_id,
+            transfer_id,
+            user_id,
+            fields.to_account_id
+        )
+        .fetch_one(&mut db_transaction)
+        .await
+        .context("an unexpected error occured while recording the outgoing transfer")?;
+
+        let incoming = query_as!(
+            Transaction,
+            r#"
+        insert into transactions (amount, currency, tx_type, date, payee, note, account_id, transfer_id, user_id)
+        select $1, $2::varchar, $3, $4, name, $5, $6, $7, $8
+        from accounts
+        where id = $9
+        returning id, amount, currency, tx_type as "tx_type: TransactionType", date, payee, note,
+        category_id, account_id, transfer_id, recurring_id, user_id, created_at, updated_at
+            "#,
+            fields.amount,
+            fields.currency,
+            TransactionType::TransferIn as _,
+            fields.date,
+            fields.note,
+            fields.to_account_id,
+            transfer_id,
+            user_id,
+            fields.from_account_id
+        )
+        .fetch_one(&mut db_transaction)
+        .await
+        .context("an unexpected error occured while recording the incoming transfer")?;
+
+        db_transaction
+            .commit()
+            .await
+            .context("could not commit the transfer")?;
+
+        Ok((outgoing, incoming))
+    }
+
+    async fn delete_transfer(&self, transfer_id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        delete from transactions
+        where transfer_id = $1
+        "#,
+            transfer_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occurred deleting transfer")?;
+
+        Ok(())
+    }
+}
@@ -2,4 +2,3 @@ mod model;
 mod repository;
 
 pub use model::*;
-pub use repository::*;
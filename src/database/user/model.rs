@@ -14,6 +14,13 @@ pub struct User {
     pub password: String,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    pub verified_at: Option<OffsetDateTime>,
+    pub mfa_secret: Option<String>,
+    pub mfa_enabled_at: Option<OffsetDateTime>,
+    pub mfa_last_used_step: Option<i64>,
+    pub delete_after: Option<OffsetDateTime>,
+    /// The currency reports of the user are converted into.
+    pub base_currency: String,
 }
 
 impl Default for User {
@@ -25,6 +32,12 @@ impl Default for User {
             password: String::from("hashed password"),
             created_at: OffsetDateTime::from(SystemTime::now()),
             updated_at: OffsetDateTime::from(SystemTime::now()),
+            verified_at: None,
+            mfa_secret: None,
+            mfa_enabled_at: None,
+            mfa_last_used_step: None,
+            delete_after: None,
+            base_currency: String::from("USD"),
         }
     }
 }
@@ -52,5 +65,33 @@ pub trait UsersRepository {
         email: String,
         name: String,
         password: String,
+        base_currency: String,
     ) -> anyhow::Result<User>;
+
+    async fn update_password(&self, id: Uuid, password: &str) -> anyhow::Result<()>;
+
+    /// Applies a verified email address to the user and marks the account as verified.
+    async fn verify_email(&self, id: Uuid, email: &str) -> anyhow::Result<User>;
+
+    /// Stores a pending TOTP secret, MFA is only enforced once `enable_mfa` confirms it.
+    async fn set_mfa_secret(&self, id: Uuid, secret: &str) -> anyhow::Result<()>;
+
+    async fn enable_mfa(&self, id: Uuid) -> anyhow::Result<()>;
+
+    async fn disable_mfa(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Records the TOTP step that was just used, returning `false` if it, or a later one, was already used.
+    async fn use_mfa_step(&self, id: Uuid, step: i64) -> anyhow::Result<bool>;
+
+    /// Marks the user for deletion, the account is hard deleted by `delete_scheduled_users` once `delete_after` passes.
+    async fn schedule_user_deletion(
+        &self,
+        id: Uuid,
+        delete_after: &OffsetDateTime,
+    ) -> anyhow::Result<()>;
+
+    async fn cancel_user_deletion(&self, id: Uuid) -> anyhow::Result<()>;
+
+    /// Deletes the users whose grace period has passed, returning how many were deleted.
+    async fn delete_scheduled_users(&self) -> anyhow::Result<u64>;
 }
@@ -1,6 +1,7 @@
 use anyhow::Context;
 use async_trait::async_trait;
-use sqlx::query_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{query, query_as};
 use uuid::Uuid;
 
 use crate::database::Database;
@@ -15,7 +16,13 @@ impl UsersRepository for Database {
         name: &str,
         hash_password: &str,
     ) -> anyhow::Result<User> {
-        query_as!(
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .context("could not start a transaction for the new user")?;
+
+        let created_user = query_as!(
             User,
             r#"
         insert into users (created_at, updated_at, name, email, password)
@@ -26,9 +33,28 @@ impl UsersRepository for Database {
             email,
             hash_password
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut transaction)
+        .await
+        .context("an unexpected error occured while creating the user")?;
+
+        // every user starts out with the default role
+        query!(
+            r#"
+        insert into user_roles (user_id, role_id)
+        select $1, id from roles where name = 'user'
+        "#,
+            created_user.id
+        )
+        .execute(&mut transaction)
         .await
-        .context("an unexpected error occured while creating the user")
+        .context("an unexpected error occured while assigning the default role")?;
+
+        transaction
+            .commit()
+            .await
+            .context("could not commit the new user")?;
+
+        Ok(created_user)
     }
 
     async fn get_user_by_email(&self, email: &str) -> anyhow::Result<Option<User>> {
@@ -67,6 +93,7 @@ impl UsersRepository for Database {
         email: String,
         name: String,
         password: String,
+        base_currency: String,
     ) -> anyhow::Result<User> {
         query_as!(
             User,
@@ -76,17 +103,189 @@ impl UsersRepository for Database {
             name = $1::varchar,
             email = $2::varchar,
             password = $3::varchar,
+            base_currency = $4::varchar,
             updated_at = current_timestamp
-        where id = $4
+        where id = $5
         returning *
             "#,
             name,
             email,
             password,
+            base_currency,
             id
         )
         .fetch_one(&self.pool)
         .await
         .context("could not update the user")
     }
+
+    async fn update_password(&self, id: Uuid, password: &str) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            password = $1::varchar,
+            updated_at = current_timestamp
+        where id = $2
+        "#,
+            password,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not update the user password")?;
+
+        Ok(())
+    }
+
+    async fn verify_email(&self, id: Uuid, email: &str) -> anyhow::Result<User> {
+        query_as!(
+            User,
+            r#"
+        update users
+        set
+            email = $1::varchar,
+            verified_at = current_timestamp,
+            updated_at = current_timestamp
+        where id = $2
+        returning *
+            "#,
+            email,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not verify the user email")
+    }
+
+    async fn set_mfa_secret(&self, id: Uuid, secret: &str) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            mfa_secret = $1::varchar,
+            mfa_enabled_at = null,
+            mfa_last_used_step = null,
+            updated_at = current_timestamp
+        where id = $2
+        "#,
+            secret,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not store the user MFA secret")?;
+
+        Ok(())
+    }
+
+    async fn enable_mfa(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            mfa_enabled_at = current_timestamp,
+            updated_at = current_timestamp
+        where id = $1 and mfa_secret is not null
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not enable MFA for the user")?;
+
+        Ok(())
+    }
+
+    async fn disable_mfa(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            mfa_secret = null,
+            mfa_enabled_at = null,
+            mfa_last_used_step = null,
+            updated_at = current_timestamp
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not disable MFA for the user")?;
+
+        Ok(())
+    }
+
+    async fn use_mfa_step(&self, id: Uuid, step: i64) -> anyhow::Result<bool> {
+        let result = query!(
+            r#"
+        update users
+        set mfa_last_used_step = $1
+        where id = $2 and (mfa_last_used_step is null or mfa_last_used_step < $1)
+        "#,
+            step,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not record the used MFA code")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn schedule_user_deletion(
+        &self,
+        id: Uuid,
+        delete_after: &OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            delete_after = $1,
+            updated_at = current_timestamp
+        where id = $2
+        "#,
+            delete_after,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not schedule the user for deletion")?;
+
+        Ok(())
+    }
+
+    async fn cancel_user_deletion(&self, id: Uuid) -> anyhow::Result<()> {
+        query!(
+            r#"
+        update users
+        set
+            delete_after = null,
+            updated_at = current_timestamp
+        where id = $1
+        "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not cancel the user deletion")?;
+
+        Ok(())
+    }
+
+    async fn delete_scheduled_users(&self) -> anyhow::Result<u64> {
+        let result = query!(
+            r#"
+        delete from users
+        where delete_after <= current_timestamp
+        "#
+        )
+        .execute(&self.pool)
+        .await
+        .context("an unexpected error occured while deleting scheduled users")?;
+
+        Ok(result.rows_affected())
+    }
 }
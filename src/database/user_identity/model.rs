@@ -0,0 +1,55 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for UserIdentity {
+    fn default() -> Self {
+        Self {
+            id: uuid!("9a4c7e21-3f6b-4d8a-8e2c-5b1f0d7a6c43"),
+            user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            provider: String::from("stub provider"),
+            subject: String::from("stub subject"),
+            email: Some(String::from("stub email")),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynUserIdentitiesRepository = Arc<dyn UserIdentitiesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait UserIdentitiesRepository {
+    async fn get_user_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<UserIdentity>>;
+
+    async fn get_user_identities_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<UserIdentity>>;
+
+    async fn create_user_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        email: Option<String>,
+    ) -> anyhow::Result<UserIdentity>;
+}
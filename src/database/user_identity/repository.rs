@@ -0,0 +1,74 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{UserIdentitiesRepository, UserIdentity};
+
+#[async_trait]
+impl UserIdentitiesRepository for Database {
+    async fn get_user_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<UserIdentity>> {
+        query_as!(
+            UserIdentity,
+            r#"
+        select *
+        from user_identities
+        where provider = $1::varchar and subject = $2::varchar
+            "#,
+            provider,
+            subject,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while searching for the user identity")
+    }
+
+    async fn get_user_identities_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<Vec<UserIdentity>> {
+        query_as!(
+            UserIdentity,
+            r#"
+        select *
+        from user_identities
+        where user_id = $1
+        order by created_at
+            "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("an unexpected error occured while retrieving the identities of the user")
+    }
+
+    async fn create_user_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        email: Option<String>,
+    ) -> anyhow::Result<UserIdentity> {
+        query_as!(
+            UserIdentity,
+            r#"
+        insert into user_identities (user_id, provider, subject, email)
+        values ($1, $2::varchar, $3::varchar, $4::varchar)
+        returning *
+            "#,
+            user_id,
+            provider,
+            subject,
+            email
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while linking the user identity")
+    }
+}
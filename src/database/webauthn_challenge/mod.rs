@@ -0,0 +1,4 @@
+mod model;
+mod repository;
+
+pub use model::*;
@@ -0,0 +1,52 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{types::time::OffsetDateTime, FromRow};
+use uuid::{uuid, Uuid};
+
+#[derive(FromRow, Debug)]
+pub struct WebauthnChallenge {
+    pub id: Uuid,
+    pub challenge_hash: String,
+    pub ceremony: String,
+    pub user_id: Option<Uuid>,
+    pub exp: OffsetDateTime,
+    pub created_at: OffsetDateTime,
+}
+
+impl Default for WebauthnChallenge {
+    fn default() -> Self {
+        Self {
+            id: uuid!("4f2a7c91-3e6b-4d58-8a1f-b7c0d2e5f936"),
+            challenge_hash: String::from("stub challenge hash"),
+            ceremony: String::from("authentication"),
+            user_id: None,
+            exp: OffsetDateTime::from(SystemTime::now()),
+            created_at: OffsetDateTime::from(SystemTime::now()),
+        }
+    }
+}
+
+/// Similar to above, we want to keep a reference count across threads so we can manage our connection pool.
+pub type DynWebauthnChallengesRepository = Arc<dyn WebauthnChallengesRepository + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait WebauthnChallengesRepository {
+    /// Stores the challenge of a ceremony, registrations are bound to the user adding the passkey.
+    async fn create_webauthn_challenge(
+        &self,
+        challenge_hash: &str,
+        ceremony: &str,
+        user_id: Option<Uuid>,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<WebauthnChallenge>;
+
+    /// Removes an unexpired challenge of the given ceremony, returning it only if this call consumed it.
+    async fn consume_webauthn_challenge(
+        &self,
+        challenge_hash: &str,
+        ceremony: &str,
+    ) -> anyhow::Result<Option<WebauthnChallenge>>;
+}
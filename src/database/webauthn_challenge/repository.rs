@@ -0,0 +1,56 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::query_as;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+use super::{WebauthnChallenge, WebauthnChallengesRepository};
+
+#[async_trait]
+impl WebauthnChallengesRepository for Database {
+    async fn create_webauthn_challenge(
+        &self,
+        challenge_hash: &str,
+        ceremony: &str,
+        user_id: Option<Uuid>,
+        exp: &OffsetDateTime,
+    ) -> anyhow::Result<WebauthnChallenge> {
+        query_as!(
+            WebauthnChallenge,
+            r#"
+        insert into webauthn_challenges (challenge_hash, ceremony, user_id, exp)
+        values ($1::varchar, $2::varchar, $3, $4)
+        returning *
+            "#,
+            challenge_hash,
+            ceremony,
+            user_id,
+            exp
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("an unexpected error occured while creating the WebAuthn challenge")
+    }
+
+    async fn consume_webauthn_challenge(
+        &self,
+        challenge_hash: &str,
+        ceremony: &str,
+    ) -> anyhow::Result<Option<WebauthnChallenge>> {
+        query_as!(
+            WebauthnChallenge,
+            r#"
+        delete from webauthn_challenges
+        where challenge_hash = $1::varchar and ceremony = $2::varchar and exp >= now()
+        returning *
+            "#,
+            challenge_hash,
+            ceremony,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("an unexpected error occured while consuming the WebAuthn challenge")
+    }
+}
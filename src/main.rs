@@ -6,12 +6,16 @@ use dotenvy::dotenv;
 
 use tracing::info;
 
-use rest_api::{AppConfig, ApplicationServer, Database, Logger};
+use rest_api::exchange_rate::DynExchangeRatesRepository;
+use rest_api::services::exchange_rate_services::{ExchangeRatesService, ExchangeRatesServiceTrait};
+use rest_api::{AppConfig, ApplicationServer, Command, Database, ExchangeRatesFormat, Logger};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    let config = Arc::new(AppConfig::parse());
+    let config = AppConfig::parse();
+    config.validate().context("invalid configuration")?;
+    let config = Arc::new(config);
 
     let _guard = Logger::init(config.cargo_env);
 
@@ -20,6 +24,25 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("could not initialize the database connection pool");
 
+    if let Some(Command::ImportExchangeRates { path, format }) = &config.command {
+        let format = format
+            .or_else(|| ExchangeRatesFormat::from_path(path))
+            .context("could not tell the format of the exchange rates file, pass --format")?;
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read exchange rates from {}", path.display()))?;
+
+        info!("importing exchange rates from {}...", path.display());
+        let service = ExchangeRatesService::new(Arc::new(db) as DynExchangeRatesRepository);
+        let imported = service
+            .import_exchange_rates(&contents, format)
+            .await
+            .map_err(|err| anyhow::anyhow!("{}", err))
+            .context("could not import exchange rates")?;
+        info!("imported {} exchange rates", imported);
+
+        return Ok(());
+    }
+
     ApplicationServer::serve(config, db)
         .await
         .context("could not initialize application routes")?;
@@ -1,8 +1,85 @@
+use std::sync::Arc;
+
+use ciborium::value::Value;
+use clap::Parser;
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+use sha2::{Digest, Sha256};
+
+use crate::config::AppConfig;
+use crate::database::account::MockAccountsRepository;
+use crate::database::api_key::MockApiKeysRepository;
+use crate::database::budget::MockBudgetsRepository;
 use crate::database::category::MockCategoriesRepository;
+use crate::database::email_verification::MockEmailVerificationsRepository;
+use crate::database::exchange_rate::MockExchangeRatesRepository;
+use crate::database::impersonation_event::MockImpersonationEventsRepository;
+use crate::database::lockout_event::MockLockoutEventsRepository;
+use crate::database::login_throttle::MockLoginThrottlesRepository;
+use crate::database::magic_link::MockMagicLinksRepository;
+use crate::database::oidc_login_state::MockOidcLoginStatesRepository;
+use crate::database::passkey::MockPasskeysRepository;
+use crate::database::password_reset::MockPasswordResetsRepository;
+use crate::database::recovery_code::MockRecoveryCodesRepository;
+use crate::database::recurring_transaction::MockRecurringTransactionsRepository;
+use crate::database::revoked_access_token::MockRevokedAccessTokensRepository;
+use crate::database::role::MockRolesRepository;
+use crate::database::session::MockSessionsRepository;
+use crate::database::signing_key::MockSigningKeysRepository;
+use crate::database::transaction::MockTransactionsRepository;
 use crate::database::user::MockUsersRepository;
+use crate::database::user_identity::MockUserIdentitiesRepository;
+use crate::database::webauthn_challenge::MockWebauthnChallengesRepository;
+use crate::server::dtos::passkey_dto::{
+    AssertionResponseDto, AttestationResponseDto, PasskeySignInDto, RegistrationCredentialDto,
+};
+use crate::server::services::account_services::MockAccountsServiceTrait;
+use crate::server::services::api_key_services::MockApiKeysServiceTrait;
+use crate::server::services::budget_services::MockBudgetsServiceTrait;
+use crate::server::services::category_services::MockCategoriesServiceTrait;
+use crate::server::services::impersonation_services::MockImpersonationServiceTrait;
+use crate::server::services::login_throttle_services::MockLoginThrottlesServiceTrait;
+use crate::server::services::magic_link_services::MockMagicLinksServiceTrait;
+use crate::server::services::oidc_services::MockOidcServiceTrait;
+use crate::server::services::passkey_services::MockPasskeysServiceTrait;
+use crate::server::services::personal_data_services::MockPersonalDataServiceTrait;
+use crate::server::services::recurring_transaction_services::MockRecurringTransactionsServiceTrait;
+use crate::server::services::role_services::MockRolesServiceTrait;
 use crate::server::services::session_services::MockSessionsServiceTrait;
+use crate::server::services::signing_key_services::MockSigningKeysServiceTrait;
+use crate::server::services::transaction_services::MockTransactionsServiceTrait;
+use crate::server::services::user_services::MockUsersServiceTrait;
+use crate::server::services::Services;
 use crate::server::utils::argon_utils::MockArgonUtil;
+use crate::server::utils::client_ip_utils::ClientIpUtil;
+use crate::server::utils::cookie_utils::CookieUtil;
 use crate::server::utils::jwt_utils::MockJwtUtil;
+use crate::server::utils::mailer_utils::MockMailer;
+use crate::server::utils::oidc_utils::MockOidcClient;
+use crate::server::utils::webauthn_utils;
+
+/// Builds a configuration with stub secrets, suitable for services under test.
+pub fn stub_config() -> Arc<AppConfig> {
+    stub_config_with(&[])
+}
+
+/// Builds a stub configuration, overriding or extending it with additional command line arguments.
+pub fn stub_config_with(args: &[&str]) -> Arc<AppConfig> {
+    let defaults = [
+        "rest_api",
+        "--cargo-env=development",
+        "--database-url=postgresql://localhost/stub",
+        "--argon-salt=stubsaltstubsalt",
+        "--access-token-secret=stub access secret",
+        "--refresh-token-secret=stub refresh secret",
+        "--cors-origin=http://localhost:3000",
+        "--mailer-transport=memory",
+    ];
+
+    Arc::new(AppConfig::parse_from(
+        defaults.iter().chain(args.iter()).copied(),
+    ))
+}
 
 pub struct CategoriesServiceTestFixture {
     pub mock_repository: MockCategoriesRepository,
@@ -22,11 +99,121 @@ impl Default for CategoriesServiceTestFixture {
     }
 }
 
+pub struct TransactionsServiceTestFixture {
+    pub mock_repository: MockTransactionsRepository,
+    pub mock_categories_repository: MockCategoriesRepository,
+    pub mock_accounts_repository: MockAccountsRepository,
+}
+
+impl TransactionsServiceTestFixture {
+    pub fn new() -> Self {
+        TransactionsServiceTestFixture {
+            mock_repository: MockTransactionsRepository::new(),
+            mock_categories_repository: MockCategoriesRepository::new(),
+            mock_accounts_repository: MockAccountsRepository::new(),
+        }
+    }
+}
+
+impl Default for TransactionsServiceTestFixture {
+    fn default() -> Self {
+        TransactionsServiceTestFixture::new()
+    }
+}
+
+pub struct RecurringTransactionsServiceTestFixture {
+    pub mock_repository: MockRecurringTransactionsRepository,
+    pub mock_categories_repository: MockCategoriesRepository,
+    pub mock_accounts_repository: MockAccountsRepository,
+}
+
+impl RecurringTransactionsServiceTestFixture {
+    pub fn new() -> Self {
+        RecurringTransactionsServiceTestFixture {
+            mock_repository: MockRecurringTransactionsRepository::new(),
+            mock_categories_repository: MockCategoriesRepository::new(),
+            mock_accounts_repository: MockAccountsRepository::new(),
+        }
+    }
+}
+
+impl Default for RecurringTransactionsServiceTestFixture {
+    fn default() -> Self {
+        RecurringTransactionsServiceTestFixture::new()
+    }
+}
+
+pub struct AccountsServiceTestFixture {
+    pub mock_repository: MockAccountsRepository,
+    pub mock_transactions_repository: MockTransactionsRepository,
+}
+
+impl AccountsServiceTestFixture {
+    pub fn new() -> Self {
+        AccountsServiceTestFixture {
+            mock_repository: MockAccountsRepository::new(),
+            mock_transactions_repository: MockTransactionsRepository::new(),
+        }
+    }
+}
+
+impl Default for AccountsServiceTestFixture {
+    fn default() -> Self {
+        AccountsServiceTestFixture::new()
+    }
+}
+
+pub struct BudgetsServiceTestFixture {
+    pub mock_repository: MockBudgetsRepository,
+    pub mock_categories_repository: MockCategoriesRepository,
+    pub mock_users_repository: MockUsersRepository,
+}
+
+impl BudgetsServiceTestFixture {
+    pub fn new() -> Self {
+        BudgetsServiceTestFixture {
+            mock_repository: MockBudgetsRepository::new(),
+            mock_categories_repository: MockCategoriesRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+        }
+    }
+}
+
+impl Default for BudgetsServiceTestFixture {
+    fn default() -> Self {
+        BudgetsServiceTestFixture::new()
+    }
+}
+
+pub struct ExchangeRatesServiceTestFixture {
+    pub mock_repository: MockExchangeRatesRepository,
+}
+
+impl ExchangeRatesServiceTestFixture {
+    pub fn new() -> Self {
+        ExchangeRatesServiceTestFixture {
+            mock_repository: MockExchangeRatesRepository::new(),
+        }
+    }
+}
+
+impl Default for ExchangeRatesServiceTestFixture {
+    fn default() -> Self {
+        ExchangeRatesServiceTestFixture::new()
+    }
+}
+
 pub struct UsersServiceTestFixture {
     pub mock_repository: MockUsersRepository,
+    pub mock_password_resets_repository: MockPasswordResetsRepository,
+    pub mock_email_verifications_repository: MockEmailVerificationsRepository,
+    pub mock_recovery_codes_repository: MockRecoveryCodesRepository,
     pub mock_jwt_util: MockJwtUtil,
     pub mock_argon_util: MockArgonUtil,
     pub mock_sessions_services: MockSessionsServiceTrait,
+    pub mock_login_throttles_services: MockLoginThrottlesServiceTrait,
+    pub mock_mailer: MockMailer,
+    pub config: Arc<AppConfig>,
 }
 
 impl Default for UsersServiceTestFixture {
@@ -39,9 +226,489 @@ impl UsersServiceTestFixture {
     pub fn new() -> Self {
         Self {
             mock_repository: MockUsersRepository::new(),
+            mock_password_resets_repository: MockPasswordResetsRepository::new(),
+            mock_email_verifications_repository: MockEmailVerificationsRepository::new(),
+            mock_recovery_codes_repository: MockRecoveryCodesRepository::new(),
+            mock_jwt_util: MockJwtUtil::new(),
+            mock_argon_util: MockArgonUtil::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+            mock_login_throttles_services: MockLoginThrottlesServiceTrait::new(),
+            mock_mailer: MockMailer::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct LoginThrottlesServiceTestFixture {
+    pub mock_repository: MockLoginThrottlesRepository,
+    pub mock_lockout_events_repository: MockLockoutEventsRepository,
+    pub mock_users_repository: MockUsersRepository,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for LoginThrottlesServiceTestFixture {
+    fn default() -> Self {
+        LoginThrottlesServiceTestFixture::new()
+    }
+}
+
+impl LoginThrottlesServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockLoginThrottlesRepository::new(),
+            mock_lockout_events_repository: MockLockoutEventsRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct SigningKeysServiceTestFixture {
+    pub mock_repository: MockSigningKeysRepository,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for SigningKeysServiceTestFixture {
+    fn default() -> Self {
+        SigningKeysServiceTestFixture::new()
+    }
+}
+
+impl SigningKeysServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockSigningKeysRepository::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct SessionsServiceTestFixture {
+    pub mock_repository: MockSessionsRepository,
+    pub mock_roles_repository: MockRolesRepository,
+    pub mock_revoked_access_tokens_repository: MockRevokedAccessTokensRepository,
+    pub mock_jwt_util: MockJwtUtil,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for SessionsServiceTestFixture {
+    fn default() -> Self {
+        SessionsServiceTestFixture::new()
+    }
+}
+
+impl SessionsServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockSessionsRepository::new(),
+            mock_roles_repository: MockRolesRepository::new(),
+            mock_revoked_access_tokens_repository: MockRevokedAccessTokensRepository::new(),
             mock_jwt_util: MockJwtUtil::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct OidcServiceTestFixture {
+    pub mock_repository: MockUsersRepository,
+    pub mock_identities_repository: MockUserIdentitiesRepository,
+    pub mock_login_states_repository: MockOidcLoginStatesRepository,
+    pub mock_oidc_client: MockOidcClient,
+    pub mock_argon_util: MockArgonUtil,
+    pub mock_jwt_util: MockJwtUtil,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for OidcServiceTestFixture {
+    fn default() -> Self {
+        OidcServiceTestFixture::new()
+    }
+}
+
+impl OidcServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockUsersRepository::new(),
+            mock_identities_repository: MockUserIdentitiesRepository::new(),
+            mock_login_states_repository: MockOidcLoginStatesRepository::new(),
+            mock_oidc_client: MockOidcClient::new(),
             mock_argon_util: MockArgonUtil::new(),
+            mock_jwt_util: MockJwtUtil::new(),
             mock_sessions_services: MockSessionsServiceTrait::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct ApiKeysServiceTestFixture {
+    pub mock_repository: MockApiKeysRepository,
+    pub mock_roles_repository: MockRolesRepository,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for ApiKeysServiceTestFixture {
+    fn default() -> Self {
+        ApiKeysServiceTestFixture::new()
+    }
+}
+
+impl ApiKeysServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockApiKeysRepository::new(),
+            mock_roles_repository: MockRolesRepository::new(),
+            config: stub_config(),
         }
     }
 }
+
+pub struct RolesServiceTestFixture {
+    pub mock_repository: MockRolesRepository,
+    pub mock_users_repository: MockUsersRepository,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+}
+
+impl Default for RolesServiceTestFixture {
+    fn default() -> Self {
+        RolesServiceTestFixture::new()
+    }
+}
+
+impl RolesServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockRolesRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+        }
+    }
+}
+
+pub struct ImpersonationServiceTestFixture {
+    pub mock_repository: MockImpersonationEventsRepository,
+    pub mock_users_repository: MockUsersRepository,
+    pub mock_roles_repository: MockRolesRepository,
+    pub mock_jwt_util: MockJwtUtil,
+}
+
+impl Default for ImpersonationServiceTestFixture {
+    fn default() -> Self {
+        ImpersonationServiceTestFixture::new()
+    }
+}
+
+impl ImpersonationServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockImpersonationEventsRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+            mock_roles_repository: MockRolesRepository::new(),
+            mock_jwt_util: MockJwtUtil::new(),
+        }
+    }
+}
+
+/// Every feature service mocked, for exercising routes along with their extractors and middleware.
+pub struct ServicesTestFixture {
+    pub mock_jwt_util: MockJwtUtil,
+    pub mock_signing_keys_services: MockSigningKeysServiceTrait,
+    pub mock_users_services: MockUsersServiceTrait,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+    pub mock_login_throttles_services: MockLoginThrottlesServiceTrait,
+    pub mock_magic_links_services: MockMagicLinksServiceTrait,
+    pub mock_oidc_services: MockOidcServiceTrait,
+    pub mock_passkeys_services: MockPasskeysServiceTrait,
+    pub mock_api_keys_services: MockApiKeysServiceTrait,
+    pub mock_roles_services: MockRolesServiceTrait,
+    pub mock_impersonation_services: MockImpersonationServiceTrait,
+    pub mock_categories_services: MockCategoriesServiceTrait,
+    pub mock_transactions_services: MockTransactionsServiceTrait,
+    pub mock_accounts_services: MockAccountsServiceTrait,
+    pub mock_recurring_transactions_services: MockRecurringTransactionsServiceTrait,
+    pub mock_budgets_services: MockBudgetsServiceTrait,
+    pub mock_personal_data_services: MockPersonalDataServiceTrait,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for ServicesTestFixture {
+    fn default() -> Self {
+        ServicesTestFixture::new()
+    }
+}
+
+impl ServicesTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_jwt_util: MockJwtUtil::new(),
+            mock_signing_keys_services: MockSigningKeysServiceTrait::new(),
+            mock_users_services: MockUsersServiceTrait::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+            mock_login_throttles_services: MockLoginThrottlesServiceTrait::new(),
+            mock_magic_links_services: MockMagicLinksServiceTrait::new(),
+            mock_oidc_services: MockOidcServiceTrait::new(),
+            mock_passkeys_services: MockPasskeysServiceTrait::new(),
+            mock_api_keys_services: MockApiKeysServiceTrait::new(),
+            mock_roles_services: MockRolesServiceTrait::new(),
+            mock_impersonation_services: MockImpersonationServiceTrait::new(),
+            mock_categories_services: MockCategoriesServiceTrait::new(),
+            mock_transactions_services: MockTransactionsServiceTrait::new(),
+            mock_accounts_services: MockAccountsServiceTrait::new(),
+            mock_recurring_transactions_services: MockRecurringTransactionsServiceTrait::new(),
+            mock_budgets_services: MockBudgetsServiceTrait::new(),
+            mock_personal_data_services: MockPersonalDataServiceTrait::new(),
+            config: stub_config(),
+        }
+    }
+
+    pub fn into_services(self) -> Services {
+        Services {
+            jwt_util: Arc::new(self.mock_jwt_util),
+            cookie_util: Arc::new(CookieUtil::new(self.config.clone())),
+            client_ip_util: Arc::new(ClientIpUtil::new(self.config)),
+            signing_keys: Arc::new(self.mock_signing_keys_services),
+            users: Arc::new(self.mock_users_services),
+            sessions: Arc::new(self.mock_sessions_services),
+            login_throttles: Arc::new(self.mock_login_throttles_services),
+            magic_links: Arc::new(self.mock_magic_links_services),
+            oidc: Arc::new(self.mock_oidc_services),
+            passkeys: Arc::new(self.mock_passkeys_services),
+            api_keys: Arc::new(self.mock_api_keys_services),
+            roles: Arc::new(self.mock_roles_services),
+            impersonation: Arc::new(self.mock_impersonation_services),
+            categories: Arc::new(self.mock_categories_services),
+            transactions: Arc::new(self.mock_transactions_services),
+            accounts: Arc::new(self.mock_accounts_services),
+            recurring_transactions: Arc::new(self.mock_recurring_transactions_services),
+            budgets: Arc::new(self.mock_budgets_services),
+            personal_data: Arc::new(self.mock_personal_data_services),
+        }
+    }
+}
+
+pub struct PersonalDataServiceTestFixture {
+    pub mock_users_repository: MockUsersRepository,
+    pub mock_categories_repository: MockCategoriesRepository,
+    pub mock_api_keys_repository: MockApiKeysRepository,
+    pub mock_identities_repository: MockUserIdentitiesRepository,
+    pub mock_passkeys_repository: MockPasskeysRepository,
+    pub mock_transactions_repository: MockTransactionsRepository,
+    pub mock_budgets_repository: MockBudgetsRepository,
+    pub mock_accounts_repository: MockAccountsRepository,
+    pub mock_recurring_transactions_repository: MockRecurringTransactionsRepository,
+    pub mock_argon_util: MockArgonUtil,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+    pub mock_login_throttles_services: MockLoginThrottlesServiceTrait,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for PersonalDataServiceTestFixture {
+    fn default() -> Self {
+        PersonalDataServiceTestFixture::new()
+    }
+}
+
+impl PersonalDataServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_users_repository: MockUsersRepository::new(),
+            mock_categories_repository: MockCategoriesRepository::new(),
+            mock_api_keys_repository: MockApiKeysRepository::new(),
+            mock_identities_repository: MockUserIdentitiesRepository::new(),
+            mock_passkeys_repository: MockPasskeysRepository::new(),
+            mock_transactions_repository: MockTransactionsRepository::new(),
+            mock_budgets_repository: MockBudgetsRepository::new(),
+            mock_accounts_repository: MockAccountsRepository::new(),
+            mock_recurring_transactions_repository: MockRecurringTransactionsRepository::new(),
+            mock_argon_util: MockArgonUtil::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+            mock_login_throttles_services: MockLoginThrottlesServiceTrait::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct MagicLinksServiceTestFixture {
+    pub mock_repository: MockMagicLinksRepository,
+    pub mock_users_repository: MockUsersRepository,
+    pub mock_argon_util: MockArgonUtil,
+    pub mock_jwt_util: MockJwtUtil,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+    pub mock_mailer: MockMailer,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for MagicLinksServiceTestFixture {
+    fn default() -> Self {
+        MagicLinksServiceTestFixture::new()
+    }
+}
+
+impl MagicLinksServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockMagicLinksRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+            mock_argon_util: MockArgonUtil::new(),
+            mock_jwt_util: MockJwtUtil::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+            mock_mailer: MockMailer::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+pub struct PasskeysServiceTestFixture {
+    pub mock_repository: MockPasskeysRepository,
+    pub mock_challenges_repository: MockWebauthnChallengesRepository,
+    pub mock_users_repository: MockUsersRepository,
+    pub mock_sessions_services: MockSessionsServiceTrait,
+    pub config: Arc<AppConfig>,
+}
+
+impl Default for PasskeysServiceTestFixture {
+    fn default() -> Self {
+        PasskeysServiceTestFixture::new()
+    }
+}
+
+impl PasskeysServiceTestFixture {
+    pub fn new() -> Self {
+        Self {
+            mock_repository: MockPasskeysRepository::new(),
+            mock_challenges_repository: MockWebauthnChallengesRepository::new(),
+            mock_users_repository: MockUsersRepository::new(),
+            mock_sessions_services: MockSessionsServiceTrait::new(),
+            config: stub_config(),
+        }
+    }
+}
+
+/// A WebAuthn authenticator holding a single ES256 passkey in memory, standing in for hardware in tests.
+pub struct SoftwareAuthenticator {
+    key_pair: EcdsaKeyPair,
+    pub credential_id: Vec<u8>,
+    pub sign_count: u32,
+    pub rp_id: String,
+    pub origin: String,
+    /// Whether the authenticator claims to have verified the user, e.g. with a PIN or biometrics.
+    pub user_verified: bool,
+}
+
+impl Default for SoftwareAuthenticator {
+    fn default() -> Self {
+        let config = stub_config();
+
+        SoftwareAuthenticator::new(&config.webauthn_rp_id, &config.cors_origin)
+    }
+}
+
+impl SoftwareAuthenticator {
+    pub fn new(rp_id: &str, origin: &str) -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+
+        Self {
+            key_pair: EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref())
+                .unwrap(),
+            credential_id: b"stub credential id".to_vec(),
+            sign_count: 0,
+            rp_id: String::from(rp_id),
+            origin: String::from(origin),
+            user_verified: true,
+        }
+    }
+
+    pub fn encoded_credential_id(&self) -> String {
+        webauthn_utils::encode(&self.credential_id)
+    }
+
+    /// The credential public key as stored by the relying party after registration.
+    pub fn cose_public_key(&self) -> Vec<u8> {
+        let point = self.key_pair.public_key().as_ref();
+
+        let key = Value::Map(vec![
+            (Value::from(1), Value::from(2)),
+            (Value::from(3), Value::from(-7)),
+            (Value::from(-1), Value::from(1)),
+            (Value::from(-2), Value::Bytes(point[1..33].to_vec())),
+            (Value::from(-3), Value::Bytes(point[33..65].to_vec())),
+        ]);
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&key, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Answers `navigator.credentials.create()` for the given challenge.
+    pub fn create(&self, challenge: &str) -> RegistrationCredentialDto {
+        let mut auth_data = self.authenticator_data(0x40);
+        auth_data.extend_from_slice(&[0u8; 16]);
+        auth_data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        auth_data.extend_from_slice(&self.credential_id);
+        auth_data.extend_from_slice(&self.cose_public_key());
+
+        let attestation = Value::Map(vec![
+            (Value::from("fmt"), Value::from("none")),
+            (Value::from("attStmt"), Value::Map(Vec::new())),
+            (Value::from("authData"), Value::Bytes(auth_data)),
+        ]);
+
+        let mut attestation_object = Vec::new();
+        ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+
+        RegistrationCredentialDto {
+            raw_id: self.encoded_credential_id(),
+            response: AttestationResponseDto {
+                client_data_json: webauthn_utils::encode(
+                    &self.client_data_json("webauthn.create", challenge),
+                ),
+                attestation_object: webauthn_utils::encode(&attestation_object),
+            },
+        }
+    }
+
+    /// Answers `navigator.credentials.get()` for the given challenge, moving the signature counter forward.
+    pub fn get(&mut self, challenge: &str, user_handle: Option<String>) -> PasskeySignInDto {
+        self.sign_count += 1;
+
+        let auth_data = self.authenticator_data(0);
+        let client_data_json = self.client_data_json("webauthn.get", challenge);
+
+        let mut signed_data = auth_data.clone();
+        signed_data.extend_from_slice(&Sha256::digest(&client_data_json));
+        let signature = self
+            .key_pair
+            .sign(&SystemRandom::new(), &signed_data)
+            .unwrap();
+
+        PasskeySignInDto {
+            raw_id: self.encoded_credential_id(),
+            response: AssertionResponseDto {
+                client_data_json: webauthn_utils::encode(&client_data_json),
+                authenticator_data: webauthn_utils::encode(&auth_data),
+                signature: webauthn_utils::encode(signature.as_ref()),
+                user_handle,
+            },
+        }
+    }
+
+    fn client_data_json(&self, ceremony_type: &str, challenge: &str) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "type": ceremony_type,
+            "challenge": challenge,
+            "origin": self.origin,
+            "crossOrigin": false,
+        }))
+        .unwrap()
+    }
+
+    fn authenticator_data(&self, extra_flags: u8) -> Vec<u8> {
+        let user_verified_flag = if self.user_verified { 0x04 } else { 0 };
+
+        let mut auth_data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        auth_data.push(0x01 | user_verified_flag | extra_flags);
+        auth_data.extend_from_slice(&self.sign_count.to_be_bytes());
+        auth_data
+    }
+}
@@ -0,0 +1,114 @@
+use axum::extract::{Json, Path};
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::server::dtos::account_dto::{
+    AccountCreateDto, AccountResponseDto, AccountUpdateDto, LedgerEntryDto, TransferCreateDto,
+    TransferResponseDto,
+};
+use crate::server::error::AppResult;
+use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::utils::permission_utils::{RoutePermissions, ACCOUNTS_READ, ACCOUNTS_WRITE};
+
+pub struct AccountController;
+
+impl AccountController {
+    pub fn app() -> Router {
+        Router::new()
+            .route("/", get(Self::get_user_accounts).post(Self::create_account))
+            .route("/transfers", post(Self::create_transfer))
+            .route(
+                "/:id",
+                get(Self::get_account)
+                    .put(Self::update_account)
+                    .delete(Self::delete_account),
+            )
+            .route("/:id/ledger", get(Self::get_ledger))
+            .route_layer(Extension(RoutePermissions::new(
+                ACCOUNTS_READ,
+                ACCOUNTS_WRITE,
+            )))
+    }
+
+    pub async fn get_user_accounts(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<AccountResponseDto>>> {
+        info!("received request to get current user accounts");
+
+        let accounts = services.accounts.get_accounts(user_id).await?;
+
+        Ok(Json(accounts))
+    }
+
+    pub async fn get_account(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<AccountResponseDto>> {
+        info!("recieved request to get account {:?}", id);
+
+        let account = services.accounts.get_account_by_id(id, user_id).await?;
+
+        Ok(Json(account))
+    }
+
+    pub async fn create_account(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<AccountCreateDto>,
+    ) -> AppResult<Json<AccountResponseDto>> {
+        info!("received request to create account");
+
+        let new_account = services.accounts.create_account(user_id, request).await?;
+
+        Ok(Json(new_account))
+    }
+
+    pub async fn update_account(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<AccountUpdateDto>,
+    ) -> AppResult<Json<AccountResponseDto>> {
+        info!("recieved request to update account {:?}", id);
+
+        let updated_account = services
+            .accounts
+            .updated_account(id, user_id, request)
+            .await?;
+
+        Ok(Json(updated_account))
+    }
+
+    pub async fn delete_account(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<()> {
+        info!("recieved request to remove account {:?}", id);
+
+        services.accounts.delete_account(user_id, id).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_ledger(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<LedgerEntryDto>>> {
+        info!("recieved request to get the ledger of account {:?}", id);
+
+        let entries = services.accounts.get_ledger(id, user_id).await?;
+
+        Ok(Json(entries))
+    }
+
+    pub async fn create_transfer(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<TransferCreateDto>,
+    ) -> AppResult<Json<TransferResponseDto>> {
+        info!("received request to transfer between accounts");
+
+        let transfer = services.accounts.create_transfer(user_id, request).await?;
+
+        Ok(Json(transfer))
+    }
+}
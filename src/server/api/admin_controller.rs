@@ -0,0 +1,119 @@
+use axum::extract::{Json, Path};
+use axum::routing::{delete, get, post, put};
+use axum::Router;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::server::dtos::role_dto::RoleDto;
+use crate::server::dtos::user_dto::UserAuthenicationResponse;
+use crate::server::error::AppResult;
+use crate::server::extractors::{Admin, RequiredRole};
+
+pub struct AdminController;
+
+impl AdminController {
+    pub fn app() -> Router {
+        Router::new()
+            .route("/users/:id/roles", get(Self::get_user_roles_endpoint))
+            .route(
+                "/users/:id/roles/:role",
+                put(Self::assign_role_endpoint).delete(Self::revoke_role_endpoint),
+            )
+            .route("/users/:id/lockout", delete(Self::unlock_user_endpoint))
+            .route(
+                "/users/:id/sessions",
+                delete(Self::revoke_user_sessions_endpoint),
+            )
+            .route(
+                "/users/:id/impersonate",
+                post(Self::impersonate_user_endpoint),
+            )
+    }
+
+    pub async fn get_user_roles_endpoint(
+        Path(user_id): Path<Uuid>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<Json<Vec<RoleDto>>> {
+        info!(
+            "recieved request from admin {:?} to list roles of user {:?}",
+            admin_id, user_id
+        );
+
+        let roles = services.roles.get_user_roles(user_id).await?;
+
+        Ok(Json(roles))
+    }
+
+    pub async fn assign_role_endpoint(
+        Path((user_id, role)): Path<(Uuid, String)>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<Json<Vec<RoleDto>>> {
+        info!(
+            "recieved request from admin {:?} to assign role {:?} to user {:?}",
+            admin_id, role, user_id
+        );
+
+        let roles = services.roles.assign_role(user_id, role).await?;
+
+        Ok(Json(roles))
+    }
+
+    pub async fn revoke_role_endpoint(
+        Path((user_id, role)): Path<(Uuid, String)>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<Json<Vec<RoleDto>>> {
+        info!(
+            "recieved request from admin {:?} to revoke role {:?} from user {:?}",
+            admin_id, role, user_id
+        );
+
+        let roles = services.roles.revoke_role(admin_id, user_id, role).await?;
+
+        Ok(Json(roles))
+    }
+
+    pub async fn unlock_user_endpoint(
+        Path(user_id): Path<Uuid>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request from admin {:?} to unlock user {:?}",
+            admin_id, user_id
+        );
+
+        services.login_throttles.unlock_user(user_id).await?;
+
+        Ok(())
+    }
+
+    pub async fn revoke_user_sessions_endpoint(
+        Path(user_id): Path<Uuid>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request from admin {:?} to revoke every session and token of user {:?}",
+            admin_id, user_id
+        );
+
+        services.sessions.revoke_user_sessions(user_id).await?;
+
+        Ok(())
+    }
+
+    pub async fn impersonate_user_endpoint(
+        Path(user_id): Path<Uuid>,
+        RequiredRole(admin_id, services, _): RequiredRole<Admin>,
+    ) -> AppResult<Json<UserAuthenicationResponse>> {
+        info!(
+            "recieved request from admin {:?} to impersonate user {:?}",
+            admin_id, user_id
+        );
+
+        let user = services
+            .impersonation
+            .impersonate(admin_id, user_id)
+            .await?;
+
+        Ok(Json(UserAuthenicationResponse { user }))
+    }
+}
@@ -0,0 +1,91 @@
+use axum::extract::{Json, Path};
+use axum::routing::{get, post, put};
+use axum::{Extension, Router};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::server::dtos::budget_dto::{
+    BudgetCopyDto, BudgetResponseDto, BudgetSetDto, BudgetSummaryDto,
+};
+use crate::server::error::AppResult;
+use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::utils::permission_utils::{RoutePermissions, BUDGETS_READ, BUDGETS_WRITE};
+
+pub struct BudgetController;
+
+impl BudgetController {
+    pub fn app() -> Router {
+        Router::new()
+            .route("/:month", get(Self::get_budget_summary))
+            .route("/:month/copy", post(Self::copy_budgets))
+            .route(
+                "/:month/:category_id",
+                put(Self::set_budget).delete(Self::delete_budget),
+            )
+            .route_layer(Extension(RoutePermissions::new(
+                BUDGETS_READ,
+                BUDGETS_WRITE,
+            )))
+    }
+
+    pub async fn get_budget_summary(
+        Path(month): Path<String>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<BudgetSummaryDto>> {
+        info!("recieved request to summarize budgets of {:?}", month);
+
+        let summary = services.budgets.get_budget_summary(user_id, month).await?;
+
+        Ok(Json(summary))
+    }
+
+    pub async fn copy_budgets(
+        Path(month): Path<String>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<BudgetCopyDto>,
+    ) -> AppResult<Json<Vec<BudgetResponseDto>>> {
+        info!("recieved request to copy budgets into {:?}", month);
+
+        let budgets = services
+            .budgets
+            .copy_budgets(user_id, month, request)
+            .await?;
+
+        Ok(Json(budgets))
+    }
+
+    pub async fn set_budget(
+        Path((month, category_id)): Path<(String, Uuid)>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<BudgetSetDto>,
+    ) -> AppResult<Json<BudgetResponseDto>> {
+        info!(
+            "recieved request to set budget of category {:?} for {:?}",
+            category_id, month
+        );
+
+        let budget = services
+            .budgets
+            .set_budget(user_id, month, category_id, request)
+            .await?;
+
+        Ok(Json(budget))
+    }
+
+    pub async fn delete_budget(
+        Path((month, category_id)): Path<(String, Uuid)>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request to remove budget of category {:?} for {:?}",
+            category_id, month
+        );
+
+        services
+            .budgets
+            .delete_budget(user_id, month, category_id)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use axum::extract::{Json, Path, Query};
 use axum::routing::{delete, get, post, put};
-use axum::Router;
+use axum::{Extension, Router};
 use tracing::info;
 use uuid::Uuid;
 
@@ -9,6 +9,7 @@ use crate::server::dtos::category_dto::{
 };
 use crate::server::error::AppResult;
 use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::utils::permission_utils::{RoutePermissions, CATEGORIES_READ, CATEGORIES_WRITE};
 
 pub struct CategoryController;
 
@@ -19,6 +20,10 @@ impl CategoryController {
             .route("/", post(Self::create_category))
             .route("/:id", put(Self::update_category))
             .route("/:id", delete(Self::delete_category))
+            .route_layer(Extension(RoutePermissions::new(
+                CATEGORIES_READ,
+                CATEGORIES_WRITE,
+            )))
     }
 
     pub async fn get_user_categories(
@@ -1,17 +1,47 @@
+mod account_controller;
+mod admin_controller;
+mod budget_controller;
 mod category_controller;
+mod recurring_transaction_controller;
+mod transaction_controller;
 mod user_controller;
 
+use axum::middleware;
 use axum::routing::*;
+use axum::{Extension, Json};
+use jsonwebtoken::jwk::JwkSet;
 
-use self::{category_controller::CategoryController, user_controller::UserController};
+use super::extractors::mark_impersonated_responses;
+use super::services::Services;
+
+use self::{
+    account_controller::AccountController, admin_controller::AdminController,
+    budget_controller::BudgetController, category_controller::CategoryController,
+    recurring_transaction_controller::RecurringTransactionController,
+    transaction_controller::TransactionController, user_controller::UserController,
+};
 
 pub async fn health() -> &'static str {
     "🚀🚀🚀 Server Running"
 }
 
+/// Publishes the keys access tokens are signed with, letting other services verify tokens on their own.
+pub async fn jwks(Extension(services): Extension<Services>) -> Json<JwkSet> {
+    Json(services.signing_keys.get_jwks())
+}
+
 pub fn app() -> Router {
     Router::new()
         .nest("/users", UserController::app())
         .nest("/categories", CategoryController::app())
+        .nest("/transactions", TransactionController::app())
+        .nest(
+            "/recurring-transactions",
+            RecurringTransactionController::app(),
+        )
+        .nest("/accounts", AccountController::app())
+        .nest("/budgets", BudgetController::app())
+        .nest("/admin", AdminController::app())
         .route("/health", get(health))
+        .layer(middleware::from_fn(mark_impersonated_responses))
 }
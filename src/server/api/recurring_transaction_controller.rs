@@ -0,0 +1,121 @@
+use axum::extract::{Json, Path, Query};
+use axum::routing::{delete, get, post, put};
+use axum::{Extension, Router};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::server::dtos::recurring_transaction_dto::{
+    OccurrenceDto, OccurrencesQuery, RecurringTransactionCreateDto,
+    RecurringTransactionResponseDto, RecurringTransactionUpdateDto,
+};
+use crate::server::error::AppResult;
+use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::utils::permission_utils::{
+    RoutePermissions, TRANSACTIONS_READ, TRANSACTIONS_WRITE,
+};
+
+pub struct RecurringTransactionController;
+
+impl RecurringTransactionController {
+    pub fn app() -> Router {
+        Router::new()
+            .route("/", get(Self::get_user_recurring_transactions))
+            .route("/", post(Self::create_recurring_transaction))
+            .route("/:id", get(Self::get_recurring_transaction))
+            .route("/:id", put(Self::update_recurring_transaction))
+            .route("/:id", delete(Self::delete_recurring_transaction))
+            .route("/:id/occurrences", get(Self::preview_occurrences))
+            .route_layer(Extension(RoutePermissions::new(
+                TRANSACTIONS_READ,
+                TRANSACTIONS_WRITE,
+            )))
+    }
+
+    pub async fn get_user_recurring_transactions(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<RecurringTransactionResponseDto>>> {
+        info!("received request to get current user recurring transactions");
+
+        let recurring_transactions = services
+            .recurring_transactions
+            .get_recurring_transactions(user_id)
+            .await?;
+
+        Ok(Json(recurring_transactions))
+    }
+
+    pub async fn get_recurring_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<RecurringTransactionResponseDto>> {
+        info!("recieved request to get recurring transaction {:?}", id);
+
+        let recurring_transaction = services
+            .recurring_transactions
+            .get_recurring_transaction_by_id(id, user_id)
+            .await?;
+
+        Ok(Json(recurring_transaction))
+    }
+
+    pub async fn create_recurring_transaction(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<RecurringTransactionCreateDto>,
+    ) -> AppResult<Json<RecurringTransactionResponseDto>> {
+        info!("received request to create recurring transaction");
+
+        let new_recurring_transaction = services
+            .recurring_transactions
+            .create_recurring_transaction(user_id, request)
+            .await?;
+
+        Ok(Json(new_recurring_transaction))
+    }
+
+    pub async fn update_recurring_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<RecurringTransactionUpdateDto>,
+    ) -> AppResult<Json<RecurringTransactionResponseDto>> {
+        info!("recieved request to update recurring transaction {:?}", id);
+
+        let updated_recurring_transaction = services
+            .recurring_transactions
+            .updated_recurring_transaction(id, user_id, request)
+            .await?;
+
+        Ok(Json(updated_recurring_transaction))
+    }
+
+    pub async fn delete_recurring_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<()> {
+        info!("recieved request to remove recurring transaction {:?}", id);
+
+        services
+            .recurring_transactions
+            .delete_recurring_transaction(user_id, id)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn preview_occurrences(
+        Path(id): Path<Uuid>,
+        Query(query): Query<OccurrencesQuery>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<OccurrenceDto>>> {
+        info!(
+            "recieved request to preview occurrences of recurring transaction {:?}",
+            id
+        );
+
+        let occurrences = services
+            .recurring_transactions
+            .preview_occurrences(id, user_id, query.count)
+            .await?;
+
+        Ok(Json(occurrences))
+    }
+}
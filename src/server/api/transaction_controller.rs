@@ -0,0 +1,102 @@
+use axum::extract::{Json, Path, Query};
+use axum::routing::{delete, get, post, put};
+use axum::{Extension, Router};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::server::dtos::transaction_dto::{
+    TransactionCreateDto, TransactionQuery, TransactionResponseDto, TransactionUpdateDto,
+};
+use crate::server::error::AppResult;
+use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::utils::permission_utils::{
+    RoutePermissions, TRANSACTIONS_READ, TRANSACTIONS_WRITE,
+};
+
+pub struct TransactionController;
+
+impl TransactionController {
+    pub fn app() -> Router {
+        Router::new()
+            .route("/", get(Self::get_user_transactions))
+            .route("/", post(Self::create_transaction))
+            .route("/:id", get(Self::get_transaction))
+            .route("/:id", put(Self::update_transaction))
+            .route("/:id", delete(Self::delete_transaction))
+            .route_layer(Extension(RoutePermissions::new(
+                TRANSACTIONS_READ,
+                TRANSACTIONS_WRITE,
+            )))
+    }
+
+    pub async fn get_user_transactions(
+        Query(query): Query<TransactionQuery>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<TransactionResponseDto>>> {
+        info!("received request to get current user transactions");
+
+        let transactions = services
+            .transactions
+            .get_transactions(user_id, query)
+            .await?;
+
+        Ok(Json(transactions))
+    }
+
+    pub async fn get_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<TransactionResponseDto>> {
+        info!("recieved request to get transaction {:?}", id);
+
+        let transaction = services
+            .transactions
+            .get_transaction_by_id(id, user_id)
+            .await?;
+
+        Ok(Json(transaction))
+    }
+
+    pub async fn create_transaction(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<TransactionCreateDto>,
+    ) -> AppResult<Json<TransactionResponseDto>> {
+        info!("received request to create transaction");
+
+        let new_transaction = services
+            .transactions
+            .create_transaction(user_id, request)
+            .await?;
+
+        Ok(Json(new_transaction))
+    }
+
+    pub async fn update_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+        ValidationExtractor(request): ValidationExtractor<TransactionUpdateDto>,
+    ) -> AppResult<Json<TransactionResponseDto>> {
+        info!("recieved request to update transaction {:?}", id);
+
+        let updated_transaction = services
+            .transactions
+            .updated_transaction(id, user_id, request)
+            .await?;
+
+        Ok(Json(updated_transaction))
+    }
+
+    pub async fn delete_transaction(
+        Path(id): Path<Uuid>,
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<()> {
+        info!("recieved request to remove transaction {:?}", id);
+
+        services
+            .transactions
+            .delete_transaction(user_id, id)
+            .await?;
+
+        Ok(())
+    }
+}
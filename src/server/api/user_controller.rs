@@ -1,16 +1,35 @@
-use axum::extract::Json;
-use axum::routing::{get, post, put};
+use axum::body::boxed;
+use axum::extract::{Json, Path};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post, put};
 use axum::{Extension, Router};
-use axum_extra::extract::cookie::{Cookie, CookieJar};
+use axum_extra::extract::cookie::CookieJar;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::extractors::{SessionExtractor, UserAgentExtractor};
+use crate::server::dtos::api_key_dto::{ApiKeyDto, CreateApiKeyDto, CreatedApiKeyDto};
+use crate::server::dtos::oidc_dto::{OidcAuthorizationDto, OidcCallbackDto};
+use crate::server::dtos::passkey_dto::{
+    PasskeyAuthenticationOptionsDto, PasskeyDto, PasskeyRegistrationOptionsDto, PasskeySignInDto,
+    RegisterPasskeyDto,
+};
+use crate::server::dtos::personal_data_dto::{AccountDeletionDto, DeleteAccountDto};
+use crate::server::dtos::session_dto::SessionDto;
 use crate::server::dtos::user_dto::{
-    SignInUserDto, SignUpUserDto, UpdateUserDto, UserAuthenicationResponse,
+    ForgotPasswordDto, MagicLinkDto, MfaChallengeResponse, MfaCodeDto, MfaEnrollmentDto,
+    ResendVerificationDto, ResetPasswordDto, ResponseUserDto, SignInMagicLinkDto, SignInMfaDto,
+    SignInOutcome, SignInUserDto, SignUpUserDto, UpdateUserDto, UserAuthenicationResponse,
+    VerifyEmailDto,
 };
 use crate::server::error::AppResult;
-use crate::server::extractors::{RequiredAuthentication, ValidationExtractor};
+use crate::server::extractors::{
+    AccessTokenExtractor, ClientIpExtractor, CsrfExtractor, RequiredAccountHolder,
+    RequiredAuthentication, ValidationExtractor,
+};
 use crate::server::services::Services;
+use crate::server::utils::cookie_utils::CSRF_TOKEN_HEADER;
 
 pub struct UserController;
 
@@ -19,10 +38,45 @@ impl UserController {
         Router::new()
             .route("/signup", post(Self::signup_user_endpoint))
             .route("/signin", post(Self::signin_user_endpoint))
+            .route("/signin/mfa", post(Self::signin_user_mfa_endpoint))
+            .route("/signin/link", post(Self::send_magic_link_endpoint))
+            .route(
+                "/signin/link/redeem",
+                post(Self::signin_magic_link_endpoint),
+            )
+            .route(
+                "/signin/passkey/options",
+                post(Self::passkey_authentication_options_endpoint),
+            )
+            .route("/signin/passkey", post(Self::signin_passkey_endpoint))
+            .route("/oidc/authorize", get(Self::oidc_authorize_endpoint))
+            .route("/oidc/callback", post(Self::oidc_callback_endpoint))
             .route("/signout", post(Self::signout_user_endpoint))
             .route("/whoami", get(Self::get_current_user_endpoint))
             .route("/refresh", get(Self::refresh_user_endpoint))
+            .route("/sessions", get(Self::get_sessions_endpoint))
+            .route("/sessions", delete(Self::revoke_other_sessions_endpoint))
+            .route("/sessions/:id", delete(Self::revoke_session_endpoint))
+            .route("/passkeys", get(Self::get_passkeys_endpoint))
+            .route("/passkeys", post(Self::register_passkey_endpoint))
+            .route(
+                "/passkeys/options",
+                post(Self::passkey_registration_options_endpoint),
+            )
+            .route("/passkeys/:id", delete(Self::delete_passkey_endpoint))
+            .route("/api-keys", get(Self::get_api_keys_endpoint))
+            .route("/api-keys", post(Self::create_api_key_endpoint))
+            .route("/api-keys/:id", delete(Self::revoke_api_key_endpoint))
+            .route("/password/forgot", post(Self::forgot_password_endpoint))
+            .route("/password/reset", post(Self::reset_password_endpoint))
+            .route("/verify", post(Self::verify_email_endpoint))
+            .route("/verify/resend", post(Self::resend_verification_endpoint))
+            .route("/mfa", delete(Self::disable_mfa_endpoint))
+            .route("/mfa/enroll", post(Self::enroll_mfa_endpoint))
+            .route("/mfa/confirm", post(Self::confirm_mfa_endpoint))
+            .route("/export", get(Self::export_user_data_endpoint))
             .route("/", put(Self::update_user_endpoint))
+            .route("/", delete(Self::delete_user_endpoint))
     }
 
     pub async fn signup_user_endpoint(
@@ -43,33 +97,178 @@ impl UserController {
         jar: CookieJar,
         Extension(services): Extension<Services>,
         UserAgentExtractor(user_agent): UserAgentExtractor,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
         ValidationExtractor(request): ValidationExtractor<SignInUserDto>,
-    ) -> AppResult<(CookieJar, Json<UserAuthenicationResponse>)> {
+    ) -> AppResult<Response> {
         info!(
             "recieved request to login user {:?}",
             request.email.as_ref().unwrap()
         );
 
-        let (user, refresh_token) = services.users.signin_user(request, user_agent).await?;
+        let outcome = services
+            .users
+            .signin_user(request, user_agent, ip_address)
+            .await?;
+
+        Ok(Self::signin_response(&services, jar, outcome))
+    }
+
+    pub async fn signin_user_mfa_endpoint(
+        jar: CookieJar,
+        Extension(services): Extension<Services>,
+        UserAgentExtractor(user_agent): UserAgentExtractor,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
+        ValidationExtractor(request): ValidationExtractor<SignInMfaDto>,
+    ) -> AppResult<Response> {
+        info!("recieved request to complete an MFA login");
+
+        let (user, refresh_token) = services
+            .users
+            .signin_user_mfa(request, user_agent, ip_address)
+            .await?;
+
+        Ok(Self::session_response(&services, jar, refresh_token, user))
+    }
+
+    pub async fn send_magic_link_endpoint(
+        Extension(services): Extension<Services>,
+        ValidationExtractor(request): ValidationExtractor<MagicLinkDto>,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request to send a sign in link to {:?}",
+            request.email.as_ref().unwrap()
+        );
 
-        let cookie = jar.add(Cookie::new("refresh_token", refresh_token.to_string()));
+        services.magic_links.send_magic_link(request).await?;
 
-        Ok((cookie, Json(UserAuthenicationResponse { user })))
+        Ok(())
+    }
+
+    pub async fn signin_magic_link_endpoint(
+        jar: CookieJar,
+        Extension(services): Extension<Services>,
+        UserAgentExtractor(user_agent): UserAgentExtractor,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
+        ValidationExtractor(request): ValidationExtractor<SignInMagicLinkDto>,
+    ) -> AppResult<Response> {
+        info!("recieved request to login with a sign in link");
+
+        let outcome = services
+            .magic_links
+            .signin_magic_link(request, user_agent, ip_address)
+            .await?;
+
+        Ok(Self::signin_response(&services, jar, outcome))
+    }
+
+    pub async fn passkey_authentication_options_endpoint(
+        Extension(services): Extension<Services>,
+    ) -> AppResult<Json<PasskeyAuthenticationOptionsDto>> {
+        info!("recieved request to start a passkey login");
+
+        let options = services.passkeys.authentication_options().await?;
+
+        Ok(Json(options))
+    }
+
+    pub async fn signin_passkey_endpoint(
+        jar: CookieJar,
+        Extension(services): Extension<Services>,
+        UserAgentExtractor(user_agent): UserAgentExtractor,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
+        ValidationExtractor(request): ValidationExtractor<PasskeySignInDto>,
+    ) -> AppResult<Response> {
+        info!("recieved request to complete a passkey login");
+
+        let (user, refresh_token) = services
+            .passkeys
+            .signin_passkey(request, user_agent, ip_address)
+            .await?;
+
+        Ok(Self::session_response(&services, jar, refresh_token, user))
+    }
+
+    pub async fn oidc_authorize_endpoint(
+        Extension(services): Extension<Services>,
+    ) -> AppResult<Json<OidcAuthorizationDto>> {
+        info!("recieved request to start an OIDC login");
+
+        let authorization = services.oidc.authorize().await?;
+
+        Ok(Json(authorization))
+    }
+
+    pub async fn oidc_callback_endpoint(
+        jar: CookieJar,
+        Extension(services): Extension<Services>,
+        UserAgentExtractor(user_agent): UserAgentExtractor,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
+        ValidationExtractor(request): ValidationExtractor<OidcCallbackDto>,
+    ) -> AppResult<Response> {
+        info!("recieved request to complete an OIDC login");
+
+        let outcome = services
+            .oidc
+            .callback(request, user_agent, ip_address)
+            .await?;
+
+        Ok(Self::signin_response(&services, jar, outcome))
+    }
+
+    /// Sets the refresh cookie of a new session, or asks for an MFA code when the user has MFA enabled.
+    fn signin_response(services: &Services, jar: CookieJar, outcome: SignInOutcome) -> Response {
+        match outcome {
+            SignInOutcome::Authenticated(user, refresh_token) => {
+                Self::session_response(services, jar, refresh_token, user)
+            }
+            SignInOutcome::MfaRequired(mfa_token) => Json(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_token,
+            })
+            .into_response(),
+        }
+    }
+
+    /// Sets the session cookies, echoing the CSRF token in a header for clients that cannot read the cookie.
+    fn session_response(
+        services: &Services,
+        jar: CookieJar,
+        refresh_token: String,
+        user: ResponseUserDto,
+    ) -> Response {
+        let (jar, csrf_token) = services.cookie_util.add_session_cookies(jar, refresh_token);
+
+        (
+            jar,
+            [(CSRF_TOKEN_HEADER, csrf_token)],
+            Json(UserAuthenicationResponse { user }),
+        )
+            .into_response()
     }
 
     pub async fn get_current_user_endpoint(
         RequiredAuthentication(user_id, services): RequiredAuthentication,
+        AccessTokenExtractor(access_token): AccessTokenExtractor,
     ) -> AppResult<Json<UserAuthenicationResponse>> {
         info!("recieved request to retrieve current user");
 
-        let current_user = services.users.get_current_user(user_id).await?;
+        // an impersonating admin must not be handed a regular token of the user
+        let current_user = match access_token.and_then(|access_token| access_token.actor_id) {
+            Some(admin_id) => {
+                services
+                    .impersonation
+                    .get_impersonated_user(admin_id, user_id)
+                    .await?
+            }
+            None => services.users.get_current_user(user_id).await?,
+        };
 
         Ok(Json(UserAuthenicationResponse { user: current_user }))
     }
 
     pub async fn update_user_endpoint(
-        RequiredAuthentication(user_id, services): RequiredAuthentication,
-        Json(request): Json<UpdateUserDto>,
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ValidationExtractor(request): ValidationExtractor<UpdateUserDto>,
     ) -> AppResult<Json<UserAuthenicationResponse>> {
         info!("recieved request to update user {:?}", user_id);
 
@@ -78,31 +277,283 @@ impl UserController {
         Ok(Json(UserAuthenicationResponse { user: updated_user }))
     }
 
+    pub async fn export_user_data_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<Response> {
+        info!("recieved request to export data of user {:?}", user_id);
+
+        let export = services.personal_data.export_user_data(user_id).await?;
+
+        Ok((
+            [
+                (CONTENT_TYPE, "application/json"),
+                (
+                    CONTENT_DISPOSITION,
+                    "attachment; filename=\"personal-data.json\"",
+                ),
+            ],
+            boxed(export),
+        )
+            .into_response())
+    }
+
+    pub async fn delete_user_endpoint(
+        jar: CookieJar,
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ClientIpExtractor(ip_address): ClientIpExtractor,
+        ValidationExtractor(request): ValidationExtractor<DeleteAccountDto>,
+    ) -> AppResult<(CookieJar, Json<AccountDeletionDto>)> {
+        info!("recieved request to delete user {:?}", user_id);
+
+        let deletion = services
+            .personal_data
+            .delete_account(user_id, request, ip_address)
+            .await?;
+
+        Ok((
+            services.cookie_util.remove_session_cookies(jar),
+            Json(deletion),
+        ))
+    }
+
     pub async fn refresh_user_endpoint(
         jar: CookieJar,
         Extension(services): Extension<Services>,
-        SessionExtractor(session_id, refresh_token): SessionExtractor,
-    ) -> AppResult<(CookieJar, Json<UserAuthenicationResponse>)> {
+        _csrf: CsrfExtractor,
+        SessionExtractor(session_id, refresh_token_id): SessionExtractor,
+    ) -> AppResult<Response> {
         info!("recieved request to refresh access token {:?}", session_id);
 
-        let user = services.sessions.refresh_access_token(session_id).await?;
+        let (user, refresh_token) = services
+            .sessions
+            .refresh_access_token(session_id, refresh_token_id)
+            .await?;
 
-        let cookie = jar.add(Cookie::new("refresh_token", refresh_token));
-
-        Ok((cookie, Json(UserAuthenicationResponse { user })))
+        Ok(Self::session_response(&services, jar, refresh_token, user))
     }
 
     pub async fn signout_user_endpoint(
         jar: CookieJar,
         Extension(services): Extension<Services>,
-        SessionExtractor(session_id, _refresh_token): SessionExtractor,
+        _csrf: CsrfExtractor,
+        SessionExtractor(session_id, _refresh_token_id): SessionExtractor,
+        AccessTokenExtractor(access_token): AccessTokenExtractor,
     ) -> AppResult<CookieJar> {
         info!("recieved request to signout session {:?}", session_id);
 
-        services.sessions.refresh_access_token(session_id).await?;
+        services.sessions.signout_session(session_id).await?;
+
+        // the access token of the session would otherwise stay usable until it expires
+        if let Some(access_token) = access_token {
+            services.sessions.revoke_access_token(&access_token).await?;
+        }
+
+        Ok(services.cookie_util.remove_session_cookies(jar))
+    }
+
+    pub async fn get_sessions_endpoint(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<SessionDto>>> {
+        info!("recieved request to list sessions for user {:?}", user_id);
+
+        let sessions = services.sessions.get_sessions(user_id).await?;
+
+        Ok(Json(sessions))
+    }
+
+    pub async fn revoke_session_endpoint(
+        Path(id): Path<Uuid>,
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<()> {
+        info!("recieved request to revoke session {:?}", id);
+
+        services.sessions.revoke_session(user_id, id).await?;
+
+        Ok(())
+    }
+
+    pub async fn revoke_other_sessions_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        _csrf: CsrfExtractor,
+        SessionExtractor(session_id, _refresh_token_id): SessionExtractor,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request to revoke all sessions other than {:?}",
+            session_id
+        );
+
+        services
+            .sessions
+            .revoke_other_sessions(user_id, session_id)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn passkey_registration_options_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<Json<PasskeyRegistrationOptionsDto>> {
+        info!(
+            "recieved request to start a passkey registration for user {:?}",
+            user_id
+        );
+
+        let options = services.passkeys.registration_options(user_id).await?;
+
+        Ok(Json(options))
+    }
+
+    pub async fn register_passkey_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ValidationExtractor(request): ValidationExtractor<RegisterPasskeyDto>,
+    ) -> AppResult<Json<PasskeyDto>> {
+        info!(
+            "recieved request to register a passkey for user {:?}",
+            user_id
+        );
+
+        let passkey = services.passkeys.register_passkey(user_id, request).await?;
+
+        Ok(Json(passkey))
+    }
+
+    pub async fn get_passkeys_endpoint(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<PasskeyDto>>> {
+        info!("recieved request to list passkeys for user {:?}", user_id);
+
+        let passkeys = services.passkeys.get_passkeys(user_id).await?;
+
+        Ok(Json(passkeys))
+    }
+
+    pub async fn delete_passkey_endpoint(
+        Path(id): Path<Uuid>,
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<()> {
+        info!("recieved request to delete passkey {:?}", id);
+
+        services.passkeys.delete_passkey(user_id, id).await?;
+
+        Ok(())
+    }
+
+    pub async fn create_api_key_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ValidationExtractor(request): ValidationExtractor<CreateApiKeyDto>,
+    ) -> AppResult<Json<CreatedApiKeyDto>> {
+        info!(
+            "recieved request to create an API key for user {:?}",
+            user_id
+        );
+
+        let created_api_key = services.api_keys.create_api_key(user_id, request).await?;
+
+        Ok(Json(created_api_key))
+    }
+
+    pub async fn get_api_keys_endpoint(
+        RequiredAuthentication(user_id, services): RequiredAuthentication,
+    ) -> AppResult<Json<Vec<ApiKeyDto>>> {
+        info!("recieved request to list API keys for user {:?}", user_id);
+
+        let api_keys = services.api_keys.get_api_keys(user_id).await?;
+
+        Ok(Json(api_keys))
+    }
+
+    pub async fn revoke_api_key_endpoint(
+        Path(id): Path<Uuid>,
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<()> {
+        info!("recieved request to revoke API key {:?}", id);
+
+        services.api_keys.revoke_api_key(user_id, id).await?;
+
+        Ok(())
+    }
+
+    pub async fn forgot_password_endpoint(
+        Extension(services): Extension<Services>,
+        ValidationExtractor(request): ValidationExtractor<ForgotPasswordDto>,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request to reset password for {:?}",
+            request.email.as_ref().unwrap()
+        );
+
+        services.users.forgot_password(request).await?;
+
+        Ok(())
+    }
+
+    pub async fn reset_password_endpoint(
+        Extension(services): Extension<Services>,
+        ValidationExtractor(request): ValidationExtractor<ResetPasswordDto>,
+    ) -> AppResult<()> {
+        info!("recieved request to complete a password reset");
+
+        services.users.reset_password(request).await?;
+
+        Ok(())
+    }
+
+    pub async fn verify_email_endpoint(
+        Extension(services): Extension<Services>,
+        ValidationExtractor(request): ValidationExtractor<VerifyEmailDto>,
+    ) -> AppResult<Json<UserAuthenicationResponse>> {
+        info!("recieved request to verify an email address");
+
+        let verified_user = services.users.verify_email(request).await?;
+
+        Ok(Json(UserAuthenicationResponse {
+            user: verified_user,
+        }))
+    }
+
+    pub async fn resend_verification_endpoint(
+        Extension(services): Extension<Services>,
+        ValidationExtractor(request): ValidationExtractor<ResendVerificationDto>,
+    ) -> AppResult<()> {
+        info!(
+            "recieved request to resend verification email to {:?}",
+            request.email.as_ref().unwrap()
+        );
+
+        services.users.resend_verification(request).await?;
+
+        Ok(())
+    }
+
+    pub async fn enroll_mfa_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+    ) -> AppResult<Json<MfaEnrollmentDto>> {
+        info!("recieved request to enroll MFA for user {:?}", user_id);
+
+        let enrollment = services.users.enroll_mfa(user_id).await?;
+
+        Ok(Json(enrollment))
+    }
+
+    pub async fn confirm_mfa_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ValidationExtractor(request): ValidationExtractor<MfaCodeDto>,
+    ) -> AppResult<()> {
+        info!("recieved request to confirm MFA for user {:?}", user_id);
+
+        services.users.confirm_mfa(user_id, request).await?;
+
+        Ok(())
+    }
+
+    pub async fn disable_mfa_endpoint(
+        RequiredAccountHolder(user_id, services): RequiredAccountHolder,
+        ValidationExtractor(request): ValidationExtractor<MfaCodeDto>,
+    ) -> AppResult<()> {
+        info!("recieved request to disable MFA for user {:?}", user_id);
 
-        let cookie = jar.remove(Cookie::named("refresh_token"));
+        services.users.disable_mfa(user_id, request).await?;
 
-        Ok(cookie)
+        Ok(())
     }
 }
@@ -0,0 +1,115 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::Date;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::account::{Account, AccountType, LedgerEntry};
+use crate::database::transaction::TransactionType;
+use crate::server::dtos::transaction_dto::TransactionResponseDto;
+use crate::server::utils::date_utils::iso_date;
+
+impl Account {
+    pub fn into_dto(self) -> AccountResponseDto {
+        AccountResponseDto {
+            id: self.id,
+            name: self.name,
+            acc_type: self.acc_type,
+            currency: self.currency,
+            opening_balance: self.opening_balance.normalize(),
+            balance: self.balance.normalize(),
+        }
+    }
+}
+
+impl LedgerEntry {
+    pub fn into_dto(self) -> LedgerEntryDto {
+        LedgerEntryDto {
+            id: self.id,
+            amount: self.amount.normalize(),
+            tx_type: self.tx_type,
+            date: self.date,
+            payee: self.payee,
+            note: self.note,
+            category_id: self.category_id,
+            transfer_id: self.transfer_id,
+            balance: self.balance.normalize(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccountResponseDto {
+    pub id: Uuid,
+    pub name: String,
+    pub acc_type: AccountType,
+    pub currency: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub opening_balance: Decimal,
+    /// The opening balance plus every transaction recorded against the account.
+    #[serde(with = "rust_decimal::serde::str")]
+    pub balance: Decimal,
+}
+
+/// Amounts are sent as strings so they are never rounded through a float.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct AccountCreateDto {
+    #[validate(required, length(min = 1))]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub acc_type: AccountType,
+    #[validate(required, length(equal = 3))]
+    pub currency: Option<String>,
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub opening_balance: Option<Decimal>,
+}
+
+/// The currency of an account is fixed, its transactions are recorded in it.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct AccountUpdateDto {
+    #[validate(length(min = 1))]
+    pub name: Option<String>,
+    pub acc_type: Option<AccountType>,
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub opening_balance: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LedgerEntryDto {
+    pub id: Uuid,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Decimal,
+    pub tx_type: TransactionType,
+    #[serde(with = "iso_date")]
+    pub date: Date,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    /// The balance of the account right after the transaction.
+    #[serde(with = "rust_decimal::serde::str")]
+    pub balance: Decimal,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct TransferCreateDto {
+    #[validate(required)]
+    pub from_account_id: Option<Uuid>,
+    #[validate(required)]
+    pub to_account_id: Option<Uuid>,
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[validate(required)]
+    pub amount: Option<Decimal>,
+    #[serde(default, with = "iso_date::option")]
+    #[validate(required)]
+    pub date: Option<Date>,
+    pub note: Option<String>,
+}
+
+/// Both legs of a transfer, the outgoing one recorded against the source account.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransferResponseDto {
+    pub transfer_id: Uuid,
+    pub outgoing: TransactionResponseDto,
+    pub incoming: TransactionResponseDto,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::api_key::ApiKey;
+
+impl ApiKey {
+    pub fn into_dto(self) -> ApiKeyDto {
+        ApiKeyDto {
+            id: self.id,
+            name: self.name,
+            prefix: self.prefix,
+            scopes: self.scopes,
+            exp: self.exp,
+            last_used_at: self.last_used_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiKeyDto {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exp: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// A newly created key, the only time the key itself is returned.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatedApiKeyDto {
+    #[serde(flatten)]
+    pub api_key: ApiKeyDto,
+    pub key: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct CreateApiKeyDto {
+    #[validate(required, length(min = 1))]
+    pub name: Option<String>,
+    #[validate(required, length(min = 1))]
+    pub scopes: Option<Vec<String>>,
+    #[validate(range(min = 1, max = 365))]
+    pub expires_in_days: Option<u64>,
+}
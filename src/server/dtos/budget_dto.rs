@@ -0,0 +1,113 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::budget::{Budget, BudgetLine, BudgetTotal};
+use crate::database::category::CategoryType;
+use crate::server::utils::date_utils;
+
+impl Budget {
+    pub fn into_dto(self) -> BudgetResponseDto {
+        BudgetResponseDto {
+            id: self.id,
+            category_id: self.category_id,
+            month: date_utils::format_month(self.month),
+            amount: self.amount.normalize(),
+            currency: self.currency,
+        }
+    }
+}
+
+impl BudgetLine {
+    pub fn into_dto(self) -> BudgetLineDto {
+        BudgetLineDto {
+            category_id: self.category_id,
+            name: self.name,
+            cat_type: self.cat_type,
+            limit: self.budget_limit.map(|limit| limit.normalize()),
+            spent: self.spent.map(|spent| spent.normalize()),
+            remaining: self.remaining.map(|remaining| remaining.normalize()),
+            percent_used: self.percent_used.map(|percent| percent.normalize()),
+        }
+    }
+}
+
+impl BudgetTotal {
+    pub fn into_dto(self) -> BudgetTotalDto {
+        BudgetTotalDto {
+            cat_type: self.cat_type,
+            limit: self.budget_limit.map(|limit| limit.normalize()),
+            spent: self.spent.map(|spent| spent.normalize()),
+            remaining: self.remaining.map(|remaining| remaining.normalize()),
+            percent_used: self.percent_used.map(|percent| percent.normalize()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BudgetResponseDto {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub month: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Decimal,
+    pub currency: String,
+}
+
+/// Amounts are sent as strings so they are never rounded through a float.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct BudgetSetDto {
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[validate(required)]
+    pub amount: Option<Decimal>,
+    /// Defaults to the base currency of the user.
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct BudgetCopyDto {
+    #[validate(required, length(min = 1))]
+    pub from_month: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BudgetLineDto {
+    pub category_id: Uuid,
+    pub name: String,
+    pub cat_type: CategoryType,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub limit: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub spent: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub remaining: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub percent_used: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BudgetTotalDto {
+    pub cat_type: CategoryType,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub limit: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub spent: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub remaining: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub percent_used: Option<Decimal>,
+}
+
+/// Spending of a month against its budgets, per category and per category type, converted into the base currency of
+/// the user with the exchange rate effective on the date of each expense.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BudgetSummaryDto {
+    pub month: String,
+    pub currency: String,
+    pub categories: Vec<BudgetLineDto>,
+    pub totals: Vec<BudgetTotalDto>,
+    /// Currencies without an exchange rate into the base currency, the figures depending on their amounts are null.
+    pub unconverted_currencies: Vec<String>,
+}
@@ -0,0 +1,17 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::Date;
+
+use crate::server::utils::date_utils::iso_date;
+
+/// A rate as it appears in an exchange rates file. Rates are strings in JSON files so they are never rounded through a
+/// float.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExchangeRateRecordDto {
+    pub base_currency: String,
+    pub quote_currency: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub rate: Decimal,
+    #[serde(with = "iso_date")]
+    pub effective_on: Date,
+}
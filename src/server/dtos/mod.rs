@@ -1,3 +1,13 @@
+pub mod account_dto;
+pub mod api_key_dto;
+pub mod budget_dto;
 pub mod category_dto;
+pub mod exchange_rate_dto;
+pub mod oidc_dto;
+pub mod passkey_dto;
+pub mod personal_data_dto;
+pub mod recurring_transaction_dto;
+pub mod role_dto;
 pub mod session_dto;
+pub mod transaction_dto;
 pub mod user_dto;
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct OidcAuthorizationDto {
+    pub authorization_url: String,
+}
+
+/// Parameters the provider appended to the redirect URL, forwarded by the client application.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct OidcCallbackDto {
+    #[validate(required, length(min = 1))]
+    pub code: Option<String>,
+    #[validate(required, length(min = 1))]
+    pub state: Option<String>,
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::passkey::Passkey;
+
+impl Passkey {
+    pub fn into_dto(self) -> PasskeyDto {
+        PasskeyDto {
+            id: self.id,
+            name: self.name,
+            last_used_at: self.last_used_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PasskeyDto {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+// the ceremony payloads below follow the JSON encoding of the WebAuthn spec, so they can be handed
+// to `navigator.credentials` and taken from `PublicKeyCredential.toJSON()` without any mapping
+
+/// Options for `navigator.credentials.create()`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegistrationOptionsDto {
+    pub challenge: String,
+    pub rp: RelyingPartyDto,
+    pub user: PasskeyUserDto,
+    pub pub_key_cred_params: Vec<PublicKeyCredentialParametersDto>,
+    pub timeout: u64,
+    pub attestation: String,
+    pub authenticator_selection: AuthenticatorSelectionDto,
+    pub exclude_credentials: Vec<PublicKeyCredentialDescriptorDto>,
+}
+
+/// Options for `navigator.credentials.get()`, no credentials are listed so the browser offers any discoverable passkey.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticationOptionsDto {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: u64,
+    pub user_verification: String,
+    pub allow_credentials: Vec<PublicKeyCredentialDescriptorDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RelyingPartyDto {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUserDto {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublicKeyCredentialParametersDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub alg: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelectionDto {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublicKeyCredentialDescriptorDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub id: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate)]
+pub struct RegisterPasskeyDto {
+    #[validate(required, length(min = 1))]
+    pub name: Option<String>,
+    #[validate(required)]
+    pub credential: Option<RegistrationCredentialDto>,
+}
+
+/// The credential returned by `navigator.credentials.create()`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationCredentialDto {
+    pub raw_id: String,
+    pub response: AttestationResponseDto,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponseDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// The credential returned by `navigator.credentials.get()`.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeySignInDto {
+    #[validate(length(min = 1))]
+    pub raw_id: String,
+    pub response: AssertionResponseDto,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponseDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
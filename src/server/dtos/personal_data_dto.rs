@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::user::User;
+use crate::database::user_identity::UserIdentity;
+use crate::server::dtos::account_dto::AccountResponseDto;
+use crate::server::dtos::api_key_dto::ApiKeyDto;
+use crate::server::dtos::budget_dto::BudgetResponseDto;
+use crate::server::dtos::category_dto::CategoryResponseDto;
+use crate::server::dtos::passkey_dto::PasskeyDto;
+use crate::server::dtos::recurring_transaction_dto::RecurringTransactionResponseDto;
+use crate::server::dtos::session_dto::SessionDto;
+use crate::server::dtos::transaction_dto::TransactionResponseDto;
+
+impl User {
+    pub fn into_profile_export_dto(self) -> ProfileExportDto {
+        ProfileExportDto {
+            id: self.id,
+            name: self.name,
+            email: self.email,
+            verified_at: self.verified_at,
+            mfa_enabled: self.mfa_enabled_at.is_some(),
+            base_currency: self.base_currency,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl UserIdentity {
+    pub fn into_export_dto(self) -> IdentityExportDto {
+        IdentityExportDto {
+            provider: self.provider,
+            subject: self.subject,
+            email: self.email,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Everything we store about a user, handed out as a downloadable archive. Secrets such as password and key hashes
+/// are left out. The archive is streamed a field at a time in this shape, in this order.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UserExportDto {
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    pub profile: ProfileExportDto,
+    pub categories: Vec<CategoryResponseDto>,
+    pub sessions: Vec<SessionDto>,
+    pub api_keys: Vec<ApiKeyDto>,
+    pub identities: Vec<IdentityExportDto>,
+    pub passkeys: Vec<PasskeyDto>,
+    pub transactions: Vec<TransactionResponseDto>,
+    pub budgets: Vec<BudgetResponseDto>,
+    pub accounts: Vec<AccountResponseDto>,
+    pub recurring_transactions: Vec<RecurringTransactionResponseDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProfileExportDto {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub verified_at: Option<OffsetDateTime>,
+    pub mfa_enabled: bool,
+    pub base_currency: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// An account at the OIDC provider linked to the user.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IdentityExportDto {
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct DeleteAccountDto {
+    #[validate(required, length(min = 1))]
+    pub password: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccountDeletionDto {
+    #[serde(with = "time::serde::rfc3339")]
+    pub delete_after: OffsetDateTime,
+}
@@ -0,0 +1,102 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::Date;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::recurring_transaction::RecurringTransaction;
+use crate::database::transaction::TransactionType;
+use crate::server::utils::date_utils::iso_date;
+use crate::server::utils::recurrence_utils;
+
+impl RecurringTransaction {
+    pub fn into_dto(self) -> RecurringTransactionResponseDto {
+        RecurringTransactionResponseDto {
+            rrule: recurrence_utils::format_rrule(&self.rule()),
+            id: self.id,
+            amount: self.amount.normalize(),
+            currency: self.currency,
+            tx_type: self.tx_type,
+            payee: self.payee,
+            note: self.note,
+            category_id: self.category_id,
+            account_id: self.account_id,
+            starts_on: self.starts_on,
+            next_date: self.next_date,
+            occurrences_recorded: self.materialized_count,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecurringTransactionResponseDto {
+    pub id: Uuid,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Decimal,
+    pub currency: String,
+    pub tx_type: TransactionType,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Uuid,
+    pub rrule: String,
+    #[serde(with = "iso_date")]
+    pub starts_on: Date,
+    /// The next occurrence still to be recorded, empty once the schedule has ended.
+    #[serde(with = "iso_date::option")]
+    pub next_date: Option<Date>,
+    pub occurrences_recorded: i32,
+}
+
+/// The schedule is given as an RFC 5545 recurrence rule such as `FREQ=MONTHLY;COUNT=12`, the first occurrence being
+/// the start date.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct RecurringTransactionCreateDto {
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[validate(required)]
+    pub amount: Option<Decimal>,
+    /// Defaults to the currency of the account.
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub tx_type: TransactionType,
+    #[validate(required, length(min = 1))]
+    pub payee: Option<String>,
+    pub note: Option<String>,
+    #[validate(required)]
+    pub category_id: Option<Uuid>,
+    #[validate(required)]
+    pub account_id: Option<Uuid>,
+    #[validate(required, length(min = 1))]
+    pub rrule: Option<String>,
+    #[serde(default, with = "iso_date::option")]
+    #[validate(required)]
+    pub starts_on: Option<Date>,
+}
+
+/// Only the template can be changed, a different schedule needs a new recurring transaction.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct RecurringTransactionUpdateDto {
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub amount: Option<Decimal>,
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    pub tx_type: Option<TransactionType>,
+    #[validate(length(min = 1))]
+    pub payee: Option<String>,
+    /// An empty note clears the note.
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct OccurrencesQuery {
+    pub count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OccurrenceDto {
+    #[serde(with = "iso_date")]
+    pub date: Date,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::role::Role;
+
+impl Role {
+    pub fn into_dto(self) -> RoleDto {
+        RoleDto {
+            name: self.name,
+            permissions: self.permissions,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoleDto {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
@@ -1,20 +1,26 @@
+use std::net::IpAddr;
+
 use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::database::session::Session;
 
 impl Session {
-    pub fn new(self) -> SessionResponseDto {
-        SessionResponseDto {
-            access_token: "access_token".to_string(),
-            refresh_token: "refresh_token".to_string(),
+    pub fn into_dto(self) -> SessionDto {
+        SessionDto {
+            id: self.id,
+            user_agent: self.user_agent,
+            ip_address: self.ip_address,
+            browser: self.browser,
+            os: self.os,
+            device: self.device,
+            created_at: self.created_at,
+            last_seen_at: self.last_seen_at,
+            exp: self.exp,
         }
     }
-
-    pub fn new_access_token(self) -> String {
-        "access_token".to_string()
-    }
 }
 
 #[derive(Serialize, Deserialize, Default, Debug)]
@@ -24,10 +30,26 @@ pub struct SessionResponseDto {
     pub refresh_token: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SessionDto {
+    pub id: Uuid,
+    pub user_agent: String,
+    pub ip_address: Option<String>,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exp: OffsetDateTime,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
 pub struct NewSessionDto {
     #[validate(required)]
     pub user_id: Option<Uuid>,
-    #[validate(required, length(min = 1))]
     pub user_agent: Option<String>,
+    pub ip_address: Option<IpAddr>,
 }
@@ -0,0 +1,96 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::Date;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::transaction::{Transaction, TransactionType};
+use crate::server::utils::date_utils::iso_date;
+
+impl Transaction {
+    pub fn into_dto(self) -> TransactionResponseDto {
+        TransactionResponseDto {
+            id: self.id,
+            // the scale read back from postgres depends on the digits stored, so trailing zeros are dropped
+            amount: self.amount.normalize(),
+            currency: self.currency,
+            tx_type: self.tx_type,
+            date: self.date,
+            payee: self.payee,
+            note: self.note,
+            category_id: self.category_id,
+            account_id: self.account_id,
+            transfer_id: self.transfer_id,
+            recurring_id: self.recurring_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionResponseDto {
+    pub id: Uuid,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Decimal,
+    pub currency: String,
+    pub tx_type: TransactionType,
+    #[serde(with = "iso_date")]
+    pub date: Date,
+    pub payee: String,
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    /// Shared by both legs of a transfer between two accounts.
+    pub transfer_id: Option<Uuid>,
+    /// The recurring transaction this one was recorded from.
+    pub recurring_id: Option<Uuid>,
+}
+
+/// Amounts are sent as strings so they are never rounded through a float.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct TransactionCreateDto {
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[validate(required)]
+    pub amount: Option<Decimal>,
+    /// Defaults to the currency of the account.
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub tx_type: TransactionType,
+    #[serde(default, with = "iso_date::option")]
+    #[validate(required)]
+    pub date: Option<Date>,
+    #[validate(required, length(min = 1))]
+    pub payee: Option<String>,
+    pub note: Option<String>,
+    #[validate(required)]
+    pub category_id: Option<Uuid>,
+    #[validate(required)]
+    pub account_id: Option<Uuid>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct TransactionUpdateDto {
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub amount: Option<Decimal>,
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    pub tx_type: Option<TransactionType>,
+    #[serde(default, with = "iso_date::option")]
+    pub date: Option<Date>,
+    #[validate(length(min = 1))]
+    pub payee: Option<String>,
+    /// An empty note clears the note.
+    pub note: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct TransactionQuery {
+    #[serde(default, with = "iso_date::option")]
+    pub from: Option<Date>,
+    #[serde(default, with = "iso_date::option")]
+    pub to: Option<Date>,
+    pub category_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+}
@@ -10,7 +10,11 @@ impl User {
             id: self.id,
             email: self.email,
             name: self.name,
+            email_verified: self.verified_at.is_some(),
+            mfa_enabled: self.mfa_enabled_at.is_some(),
+            base_currency: self.base_currency,
             access_token: Some(token),
+            impersonated_by: None,
         }
     }
 }
@@ -21,8 +25,14 @@ pub struct ResponseUserDto {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    pub email_verified: bool,
+    pub mfa_enabled: bool,
+    pub base_currency: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
+    /// The admin acting as the user, only present on responses of impersonation tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize, Default, Debug)]
@@ -45,12 +55,52 @@ impl UserAuthenicationResponse {
                 id,
                 name,
                 email,
+                email_verified: false,
+                mfa_enabled: false,
+                base_currency: String::new(),
                 access_token,
+                impersonated_by: None,
             },
         }
     }
 }
 
+/// Result of the password step of a sign in, users with MFA enabled still have to present a code.
+#[derive(Debug)]
+pub enum SignInOutcome {
+    /// The user is signed in, carrying the refresh token of the new session.
+    Authenticated(ResponseUserDto, String),
+    /// The password was correct, the MFA token has to be exchanged along with a code.
+    MfaRequired(String),
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct MfaEnrollmentDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct MfaCodeDto {
+    #[validate(required, length(min = 6))]
+    pub code: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct SignInMfaDto {
+    #[validate(required, length(min = 1))]
+    pub mfa_token: Option<String>,
+    #[validate(required, length(min = 6))]
+    pub code: Option<String>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct UserProfileDto {
     pub name: String,
@@ -77,11 +127,52 @@ pub struct SignInUserDto {
     pub password: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct ForgotPasswordDto {
+    #[validate(required, length(min = 1), email(message = "email is invalid"))]
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct ResetPasswordDto {
+    #[validate(required, length(min = 1))]
+    pub token: Option<String>,
+    #[validate(required, length(min = 6))]
+    pub password: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct MagicLinkDto {
+    #[validate(required, length(min = 1), email(message = "email is invalid"))]
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct SignInMagicLinkDto {
+    #[validate(required, length(min = 1))]
+    pub token: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct VerifyEmailDto {
+    #[validate(required, length(min = 1))]
+    pub token: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct ResendVerificationDto {
+    #[validate(required, length(min = 1), email(message = "email is invalid"))]
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate, Default)]
 pub struct UpdateUserDto {
+    #[validate(length(min = 1), email(message = "email is invalid"))]
     pub email: Option<String>,
     pub name: Option<String>,
     pub password: Option<String>,
+    #[validate(length(equal = 3))]
+    pub base_currency: Option<String>,
     pub bio: Option<String>,
     pub image: Option<String>,
 }
@@ -8,7 +8,6 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use thiserror::Error;
 use tracing::debug;
-use tracing::log::error;
 use validator::{ValidationErrors, ValidationErrorsKind};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -36,6 +35,10 @@ pub enum Error {
     InvalidLoginAttmpt,
     #[error("user does not have privilege to access this resource")]
     Forbidden,
+    #[error("email address has not been verified")]
+    EmailNotVerified,
+    #[error("too many failed sign in attempts, try again later")]
+    TooManyLoginAttempts,
     #[error("{0}")]
     NotFound(String),
     #[error("{0}")]
@@ -69,19 +72,19 @@ impl Error {
                 for error in field_meta.into_iter() {
                     validation_errors
                         .entry(Cow::from(field_property))
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(error.message.unwrap_or_else(|| {
                             // required validators contain None for their message, assume a default response
                             let params: Vec<Cow<'static, str>> = error
                                 .params
                                 .iter()
-                                .filter(|(key, _value)| key.to_owned() != "value")
+                                .filter(|(key, _value)| *key != "value")
                                 .map(|(key, value)| {
-                                    Cow::from(format!("{} value is {}", key, value.to_string()))
+                                    Cow::from(format!("{} value is {}", key, value))
                                 })
                                 .collect();
 
-                            if params.len() >= 1 {
+                            if !params.is_empty() {
                                 Cow::from(params.join(", "))
                             } else {
                                 Cow::from(format!("{} is required", field_property))
@@ -97,7 +100,7 @@ impl Error {
                         for error in field_meta.into_iter() {
                             validation_errors
                                 .entry(Cow::from(struct_property))
-                                .or_insert_with(Vec::new)
+                                .or_default()
                                 .push(error.message.unwrap_or_else(|| {
                                     // required validators contain None for their message, assume a default response
                                     Cow::from(format!("{} is required", struct_property))
@@ -127,12 +130,18 @@ impl IntoResponse for Error {
             Self::InternalServerErrorWithContext(err) => (StatusCode::INTERNAL_SERVER_ERROR, err),
             Self::NotFound(err) => (StatusCode::NOT_FOUND, err),
             Self::ObjectConflict(err) => (StatusCode::CONFLICT, err),
+            Self::BadRequest(err) => (StatusCode::BAD_REQUEST, err),
             Self::InvalidLoginAttmpt => (
                 StatusCode::BAD_REQUEST,
                 Self::InvalidLoginAttmpt.to_string(),
             ),
             Self::Unauthorized => (StatusCode::UNAUTHORIZED, Self::Unauthorized.to_string()),
             Self::Forbidden => (StatusCode::FORBIDDEN, Self::Forbidden.to_string()),
+            Self::EmailNotVerified => (StatusCode::FORBIDDEN, Self::EmailNotVerified.to_string()),
+            Self::TooManyLoginAttempts => (
+                StatusCode::TOO_MANY_REQUESTS,
+                Self::TooManyLoginAttempts.to_string(),
+            ),
             Self::AxumJsonRejection(err) => (StatusCode::BAD_REQUEST, err.body_text()),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
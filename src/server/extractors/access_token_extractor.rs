@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::Extension;
+
+use crate::server::error::Error;
+use crate::server::services::Services;
+use crate::server::utils::jwt_utils::AccessToken;
+
+/// Extracts the access token from the Authorization header when it holds a valid one, without requiring it.
+pub struct AccessTokenExtractor(pub Option<AccessToken>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AccessTokenExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Extension(services): Extension<Services> = Extension::from_request_parts(parts, state)
+            .await
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        let access_token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|header_value| header_value.to_str().ok())
+            .and_then(|header_value| header_value.strip_prefix("Bearer "))
+            .and_then(|token_value| {
+                services
+                    .jwt_util
+                    .get_access_from_token(String::from(token_value))
+                    .ok()
+            });
+
+        Ok(AccessTokenExtractor(access_token))
+    }
+}
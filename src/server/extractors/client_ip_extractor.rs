@@ -0,0 +1,35 @@
+use std::net::{IpAddr, SocketAddr};
+
+use async_trait::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::Extension;
+
+use crate::server::error::Error;
+use crate::server::services::Services;
+
+/// Extracts the IP address of the client, as reported by trusted proxies or otherwise the connected address,
+/// if the server was started with connection info.
+pub struct ClientIpExtractor(pub Option<IpAddr>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIpExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Extension(services): Extension<Services> = Extension::from_request_parts(parts, state)
+            .await
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+
+        Ok(ClientIpExtractor(
+            services.client_ip_util.client_ip(&parts.headers, peer_ip),
+        ))
+    }
+}
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use tracing::error;
+
+use crate::server::error::Error;
+use crate::server::utils::cookie_utils;
+
+/// Guards cookie authenticated routes, requiring the CSRF header to match the CSRF cookie.
+pub struct CsrfExtractor;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CsrfExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if !cookie_utils::verify_csrf_token(&parts.headers) {
+            error!("request is missing a matching CSRF token");
+            return Err(Error::Forbidden);
+        }
+
+        Ok(CsrfExtractor)
+    }
+}
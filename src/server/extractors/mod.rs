@@ -1,9 +1,17 @@
+mod access_token_extractor;
+mod client_ip_extractor;
+mod csrf_extractor;
+mod required_authentication_extractor;
+mod required_role_extractor;
 mod session_extractor;
 mod user_agent_extractor;
 mod validation_extractor;
-mod required_authentication_extractor;
 
+pub use access_token_extractor::*;
+pub use client_ip_extractor::*;
+pub use csrf_extractor::*;
+pub use required_authentication_extractor::*;
+pub use required_role_extractor::*;
 pub use session_extractor::*;
 pub use user_agent_extractor::*;
 pub use validation_extractor::*;
-pub use required_authentication_extractor::*;
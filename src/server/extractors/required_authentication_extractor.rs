@@ -1,15 +1,50 @@
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRequestParts, OriginalUri};
 use axum::http::header::AUTHORIZATION;
 use axum::http::request::Parts;
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
 use axum::Extension;
 use tracing::error;
 use uuid::Uuid;
 
+use crate::database::role::AccessGrants;
 use crate::server::error::Error;
 use crate::server::services::Services;
+use crate::server::utils::api_key_utils;
+use crate::server::utils::jwt_utils::AccessToken;
+use crate::server::utils::permission_utils::RoutePermissions;
+
+/// Response header naming the admin behind a request made with an impersonation token.
+pub const IMPERSONATED_BY_HEADER: &str = "x-impersonated-by";
+
+/// Slot `authenticate` fills with the impersonating admin, extractors only see the request so the response is marked
+/// by `mark_impersonated_responses` once the handler returns.
+#[derive(Clone, Default)]
+pub struct ImpersonationMarker(Arc<Mutex<Option<Uuid>>>);
+
+/// Marks every response to a request made with an impersonation token with the `X-Impersonated-By` header.
+pub async fn mark_impersonated_responses<B>(mut request: Request<B>, next: Next<B>) -> Response {
+    let marker = ImpersonationMarker::default();
+    request.extensions_mut().insert(marker.clone());
+
+    let mut response = next.run(request).await;
+
+    if let Some(actor_id) = *marker.0.lock().unwrap() {
+        if let Ok(header_value) = HeaderValue::from_str(&actor_id.to_string()) {
+            response
+                .headers_mut()
+                .insert(IMPERSONATED_BY_HEADER, header_value);
+        }
+    }
+
+    response
+}
 
-/// Extracts the JWT from the Authorization token header.
+/// Extracts the JWT, or an API key, from the Authorization token header.
 pub struct RequiredAuthentication(pub Uuid, pub Services);
 
 #[async_trait]
@@ -19,48 +54,162 @@ where
 {
     type Rejection = Error;
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let Extension(services): Extension<Services> = Extension::from_request_parts(parts, state)
-            .await
-            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        let (user_id, _grants, _actor_id, services) = authenticate(parts, state).await?;
 
-        if let Some(authorization_header) = parts.headers.get(AUTHORIZATION) {
-            let header_value = authorization_header
-                .to_str()
-                .map_err(|_| Error::Unauthorized)?;
+        Ok(RequiredAuthentication(user_id, services))
+    }
+}
 
-            if !header_value.contains("Bearer") {
-                error!("request does not contain valid 'Bearer' prefix for authorization");
-                return Err(Error::Unauthorized);
-            }
+/// Authenticates the request like `RequiredAuthentication`, rejecting impersonation tokens so sensitive
+/// operations, like changing the password, are left to the account holder.
+pub struct RequiredAccountHolder(pub Uuid, pub Services);
 
-            let tokenized_value: Vec<_> = header_value.split(' ').collect();
+#[async_trait]
+impl<S> FromRequestParts<S> for RequiredAccountHolder
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let (user_id, _grants, actor_id, services) = authenticate(parts, state).await?;
 
-            if tokenized_value.len() != 2 || tokenized_value.get(1).is_none() {
-                error!("request does not contain a valid token");
-                return Err(Error::Unauthorized);
-            }
+        if let Some(actor_id) = actor_id {
+            error!(
+                "admin {:?} attempted a sensitive operation while impersonating user {:?}",
+                actor_id, user_id
+            );
+            return Err(Error::Forbidden);
+        }
+
+        Ok(RequiredAccountHolder(user_id, services))
+    }
+}
 
-            let token_value = tokenized_value.into_iter().nth(1).unwrap();
+/// Authenticates the bearer token of a request, enforcing the `RoutePermissions` of the route if it declares any.
+/// Requests authenticated with an API key carry no roles, requests authenticated with an impersonation token
+/// also return the impersonating admin and are recorded to the audit trail.
+pub(crate) async fn authenticate<S>(
+    parts: &mut Parts,
+    state: &S,
+) -> Result<(Uuid, AccessGrants, Option<Uuid>, Services), Error>
+where
+    S: Send + Sync,
+{
+    let Extension(services): Extension<Services> = Extension::from_request_parts(parts, state)
+        .await
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+    if let Some(authorization_header) = parts.headers.get(AUTHORIZATION) {
+        let header_value = authorization_header
+            .to_str()
+            .map_err(|_| Error::Unauthorized)?;
+
+        if !header_value.contains("Bearer") {
+            error!("request does not contain valid 'Bearer' prefix for authorization");
+            return Err(Error::Unauthorized);
+        }
+
+        let tokenized_value: Vec<_> = header_value.split(' ').collect();
+
+        if tokenized_value.len() != 2 || tokenized_value.get(1).is_none() {
+            error!("request does not contain a valid token");
+            return Err(Error::Unauthorized);
+        }
+
+        let token_value = tokenized_value.into_iter().nth(1).unwrap();
+
+        let required_permission = parts
+            .extensions
+            .get::<RoutePermissions>()
+            .map(|permissions| permissions.required_for(&parts.method));
+
+        let (user_id, grants, actor_id) = if api_key_utils::is_api_key(token_value) {
+            // API keys are only accepted by routes declaring the permissions they require
             let user_id = services
+                .api_keys
+                .authenticate(
+                    String::from(token_value),
+                    required_permission.map(String::from),
+                )
+                .await
+                .map_err(|err| {
+                    error!("could not authenticate API key: {:?}", err);
+                    match err {
+                        Error::Forbidden => Error::Forbidden,
+                        _ => Error::Unauthorized,
+                    }
+                })?;
+
+            (user_id, AccessGrants::default(), None)
+        } else {
+            let access_token = services
                 .jwt_util
-                .get_user_id_from_token(String::from(token_value))
+                .get_access_from_token(String::from(token_value))
                 .map_err(|err| {
                     error!("could not validate user ID from token: {:?}", err);
                     Error::Unauthorized
                 })?;
 
-            let user = services
-                .users
-                .get_current_user(user_id)
+            services
+                .sessions
+                .ensure_access_token_active(&access_token)
                 .await
                 .map_err(|err| {
-                    error!("invalid user ID from token: {:?}", err);
+                    error!("access token was rejected: {:?}", err);
                     Error::Unauthorized
                 })?;
 
-            Ok(RequiredAuthentication(user.id, services))
-        } else {
-            Err(Error::Unauthorized)
+            if access_token.actor_id.is_some() {
+                // nested routers strip their prefix from the URI, so prefer the one the request arrived with
+                let path = parts
+                    .extensions
+                    .get::<OriginalUri>()
+                    .map(|uri| uri.path().to_string())
+                    .unwrap_or_else(|| parts.uri.path().to_string());
+
+                services
+                    .impersonation
+                    .record_request(&access_token, parts.method.to_string(), path)
+                    .await?;
+            }
+
+            let AccessToken {
+                user_id,
+                grants,
+                actor_id,
+                ..
+            } = access_token;
+
+            if let Some(required_permission) = required_permission {
+                if !grants.has_permission(required_permission) {
+                    error!(
+                        "user {:?} is missing the {:?} permission",
+                        user_id, required_permission
+                    );
+                    return Err(Error::Forbidden);
+                }
+            }
+
+            (user_id, grants, actor_id)
+        };
+
+        let user = services
+            .users
+            .get_current_user(user_id)
+            .await
+            .map_err(|err| {
+                error!("invalid user ID from token: {:?}", err);
+                Error::Unauthorized
+            })?;
+
+        if let (Some(actor_id), Some(marker)) =
+            (actor_id, parts.extensions.get::<ImpersonationMarker>())
+        {
+            *marker.0.lock().unwrap() = Some(actor_id);
         }
+
+        Ok((user.id, grants, actor_id, services))
+    } else {
+        Err(Error::Unauthorized)
     }
 }
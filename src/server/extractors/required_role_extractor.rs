@@ -0,0 +1,47 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::server::error::Error;
+use crate::server::services::Services;
+use crate::server::utils::permission_utils::ROLE_ADMIN;
+
+use super::required_authentication_extractor::authenticate;
+
+/// A role that routes can demand through `RequiredRole`.
+pub trait RoleRequirement {
+    const NAME: &'static str;
+}
+
+/// Restricts a route to administrators.
+pub struct Admin;
+
+impl RoleRequirement for Admin {
+    const NAME: &'static str = ROLE_ADMIN;
+}
+
+/// Authenticates the request like `RequiredAuthentication`, additionally requiring the user to hold the role `R`.
+pub struct RequiredRole<R>(pub Uuid, pub Services, pub PhantomData<R>);
+
+#[async_trait]
+impl<S, R> FromRequestParts<S> for RequiredRole<R>
+where
+    S: Send + Sync,
+    R: RoleRequirement,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let (user_id, grants, _actor_id, services) = authenticate(parts, state).await?;
+
+        if !grants.has_role(R::NAME) {
+            error!("user {:?} is missing the {:?} role", user_id, R::NAME);
+            return Err(Error::Forbidden);
+        }
+
+        Ok(RequiredRole(user_id, services, PhantomData))
+    }
+}
@@ -5,7 +5,7 @@ use axum::http::request::Parts;
 
 use crate::server::error::Error;
 
-/// Extracts the JWT from the cookie token header.
+/// Extracts the User-Agent header, clients are not required to send one.
 pub struct UserAgentExtractor(pub Option<String>);
 
 #[async_trait]
@@ -15,12 +15,11 @@ where
 {
     type Rejection = Error;
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(authorization_header) = parts.headers.get(USER_AGENT) {
-            let header_value = authorization_header.to_str().unwrap_or(&"");
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .map(|header_value| header_value.to_str().unwrap_or("").to_string());
 
-            Ok(UserAgentExtractor(Some(header_value.to_string())))
-        } else {
-            Err(Error::Unauthorized)
-        }
+        Ok(UserAgentExtractor(user_agent))
     }
 }
@@ -1,18 +1,18 @@
-mod api;
+pub mod api;
 pub mod dtos;
 pub mod error;
 pub mod extractors;
 pub mod services;
 pub mod utils;
 
-use std::future::ready;
+use std::future::{ready, Future};
 use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use axum::extract::MatchedPath;
-use axum::http::{HeaderValue, Request};
+use axum::http::{HeaderName, HeaderValue, Request};
 use axum::middleware::{self, Next};
 use axum::response::IntoResponse;
 use axum::routing::get;
@@ -21,17 +21,25 @@ use axum::{error_handling::HandleErrorLayer, http::StatusCode, BoxError, Json, R
 use lazy_static::lazy_static;
 use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
 use serde_json::json;
+use sqlx::types::time::OffsetDateTime;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tower::{buffer::BufferLayer, limit::RateLimitLayer, ServiceBuilder};
 use tower_http::{cors::Any, cors::CorsLayer, trace::TraceLayer};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 use crate::config::AppConfig;
 use crate::database::Database;
+use crate::server::extractors::IMPERSONATED_BY_HEADER;
 use crate::server::services::seed_services::SeedService;
 use crate::server::services::Services;
+use crate::server::utils::cookie_utils::CSRF_TOKEN_HEADER;
 
 lazy_static! {
     static ref HTTP_TIMEOUT: u64 = 30;
+    static ref SIGNING_KEY_REFRESH_SECONDS: u64 = 60;
+    static ref ACCOUNT_PURGE_SECONDS: u64 = 3600;
+    static ref RECURRING_TRANSACTIONS_SECONDS: u64 = 300;
     static ref EXPONENTIAL_SECONDS: &'static [f64] =
         &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,];
 }
@@ -46,11 +54,30 @@ impl ApplicationServer {
                 *EXPONENTIAL_SECONDS,
             )
             .context("could not setup buckets for metrics, verify matchers are correct")?
+            .set_buckets_for_metric(
+                Matcher::Prefix(String::from("password_hashing_")),
+                *EXPONENTIAL_SECONDS,
+            )
+            .context("could not setup buckets for metrics, verify matchers are correct")?
             .install_recorder()
             .context("could not install metrics recorder")?;
 
         let services = Services::new(db, config.clone());
 
+        services
+            .signing_keys
+            .rotate_keys()
+            .await
+            .context("could not load the keys access tokens are signed with")?;
+
+        // background tasks stop once the server has drained its requests, finishing the run they are in
+        let (stop_background_tasks, shutdown) = watch::channel(false);
+        let background_tasks = vec![
+            Self::refresh_signing_keys(services.clone(), shutdown.clone()),
+            Self::purge_deleted_accounts(services.clone(), shutdown.clone()),
+            Self::materialize_recurring_transactions(services.clone(), shutdown),
+        ];
+
         if config.seed {
             info!("seeding enabled, creating test data...");
             SeedService::new(services.clone())
@@ -64,11 +91,16 @@ impl ApplicationServer {
         let cors = CorsLayer::new()
             .allow_origin(cors_origin.parse::<HeaderValue>().unwrap())
             .allow_methods(Any)
-            .allow_headers(Any);
+            .allow_headers(Any)
+            .expose_headers([
+                HeaderName::from_static(CSRF_TOKEN_HEADER),
+                HeaderName::from_static(IMPERSONATED_BY_HEADER),
+            ]);
 
         let router = Router::new()
             .nest("/api/v1", api::app())
             .route("/", get(api::health))
+            .route("/.well-known/jwks.json", get(api::jwks))
             .route("/metrics", get(move || ready(recorder_handle.render())))
             .layer(
                 ServiceBuilder::new()
@@ -90,14 +122,97 @@ impl ApplicationServer {
         info!("🚀 Server has launched on https://{addr}");
         debug!("routes initialized, listening on port {}", port);
         axum::Server::bind(&addr)
-            .serve(router.into_make_service())
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
             .with_graceful_shutdown(Self::shutdown_signal())
             .await
             .context("error while starting API server")?;
 
+        info!("waiting for background tasks to finish...");
+        stop_background_tasks.send_replace(true);
+
+        for task in background_tasks {
+            task.await
+                .context("a background task did not shut down cleanly")?;
+        }
+
         Ok(())
     }
 
+    /// Keeps the signing keys in sync with other instances, rotating them once they are due.
+    fn refresh_signing_keys(services: Services, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        Self::run_periodically(*SIGNING_KEY_REFRESH_SECONDS, shutdown, move || {
+            let services = services.clone();
+
+            async move {
+                if let Err(err) = services.signing_keys.rotate_keys().await {
+                    error!("could not refresh the signing keys: {:?}", err);
+                }
+            }
+        })
+    }
+
+    /// Permanently deletes the accounts whose deletion grace period has passed.
+    fn purge_deleted_accounts(
+        services: Services,
+        shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        Self::run_periodically(*ACCOUNT_PURGE_SECONDS, shutdown, move || {
+            let services = services.clone();
+
+            async move {
+                if let Err(err) = services.personal_data.purge_deleted_accounts().await {
+                    error!("could not purge deleted accounts: {:?}", err);
+                }
+            }
+        })
+    }
+
+    /// Records the occurrences of recurring transactions as they fall due, the first run catches up on the ones
+    /// missed while the server was down.
+    fn materialize_recurring_transactions(
+        services: Services,
+        shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        Self::run_periodically(*RECURRING_TRANSACTIONS_SECONDS, shutdown, move || {
+            let services = services.clone();
+
+            async move {
+                let today = OffsetDateTime::now_utc().date();
+
+                match services
+                    .recurring_transactions
+                    .materialize_due_transactions(today)
+                    .await
+                {
+                    Ok(created) => info!("recorded {} recurring transactions", created),
+                    Err(err) => error!("could not record recurring transactions: {:?}", err),
+                }
+            }
+        })
+    }
+
+    /// Runs a task right away and then on every interval until shutdown, a run in progress is never interrupted.
+    fn run_periodically<F, Fut>(
+        seconds: u64,
+        mut shutdown: watch::Receiver<bool>,
+        mut task: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(seconds));
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => task().await,
+                    _ = shutdown.changed() => break,
+                }
+            }
+        })
+    }
+
     /// Adds a custom handler for tower's `TimeoutLayer`, see https://docs.rs/axum/latest/axum/middleware/index.html#commonly-used-middleware.
     async fn handle_timeout_error(err: BoxError) -> (StatusCode, Json<serde_json::Value>) {
         if err.is::<tower::timeout::error::Elapsed>() {
@@ -0,0 +1,235 @@
+use mockall::automock;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::{
+        account::DynAccountsRepository,
+        transaction::{DynTransactionsRepository, TransferFields},
+    },
+    server::{
+        dtos::account_dto::{
+            AccountCreateDto, AccountResponseDto, AccountUpdateDto, LedgerEntryDto,
+            TransferCreateDto, TransferResponseDto,
+        },
+        error::{AppResult, Error},
+        utils::{currency_utils, permission_utils},
+    },
+};
+
+/// A reference counter for our account service, managing where users hold their money and moving it between them.
+pub type DynAccountsService = Arc<dyn AccountsServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait AccountsServiceTrait {
+    async fn create_account(
+        &self,
+        user_id: Uuid,
+        request: AccountCreateDto,
+    ) -> AppResult<AccountResponseDto>;
+
+    async fn get_account_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<AccountResponseDto>;
+
+    async fn get_accounts(&self, user_id: Uuid) -> AppResult<Vec<AccountResponseDto>>;
+
+    async fn updated_account(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: AccountUpdateDto,
+    ) -> AppResult<AccountResponseDto>;
+
+    /// Removes an account, its transactions are kept without an account.
+    async fn delete_account(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    /// Lists the transactions of an account, newest first, with the running balance after each of them.
+    async fn get_ledger(&self, id: Uuid, user_id: Uuid) -> AppResult<Vec<LedgerEntryDto>>;
+
+    /// Moves money between two accounts of the user sharing a currency, both legs are recorded atomically.
+    async fn create_transfer(
+        &self,
+        user_id: Uuid,
+        request: TransferCreateDto,
+    ) -> AppResult<TransferResponseDto>;
+}
+
+#[derive(Clone)]
+pub struct AccountsService {
+    repository: DynAccountsRepository,
+    transactions_repository: DynTransactionsRepository,
+}
+
+impl AccountsService {
+    pub fn new(
+        repository: DynAccountsRepository,
+        transactions_repository: DynTransactionsRepository,
+    ) -> Self {
+        Self {
+            repository,
+            transactions_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl AccountsServiceTrait for AccountsService {
+    async fn create_account(
+        &self,
+        user_id: Uuid,
+        request: AccountCreateDto,
+    ) -> AppResult<AccountResponseDto> {
+        let currency = currency_utils::normalize_currency(&request.currency.unwrap())?;
+        let opening_balance = request.opening_balance.unwrap_or(Decimal::ZERO);
+        currency_utils::ensure_amount_fits(opening_balance)?;
+
+        let created_account = self
+            .repository
+            .create_account(
+                user_id,
+                request.name.unwrap(),
+                request.acc_type,
+                currency,
+                opening_balance,
+            )
+            .await?;
+
+        info!("user created account successfully");
+
+        Ok(created_account.into_dto())
+    }
+
+    async fn get_account_by_id(&self, id: Uuid, user_id: Uuid) -> AppResult<AccountResponseDto> {
+        let existing_account =
+            permission_utils::find_owned_account(&self.repository, id, user_id).await?;
+
+        Ok(existing_account.into_dto())
+    }
+
+    async fn get_accounts(&self, user_id: Uuid) -> AppResult<Vec<AccountResponseDto>> {
+        let accounts = self.repository.get_accounts(user_id).await?;
+
+        info!("found {} accounts", accounts.len());
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| account.into_dto())
+            .collect())
+    }
+
+    async fn updated_account(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: AccountUpdateDto,
+    ) -> AppResult<AccountResponseDto> {
+        let existing_account =
+            permission_utils::find_owned_account(&self.repository, id, user_id).await?;
+
+        let opening_balance = request
+            .opening_balance
+            .unwrap_or(existing_account.opening_balance);
+        currency_utils::ensure_amount_fits(opening_balance)?;
+
+        let updated_account = self
+            .repository
+            .update_account(
+                id,
+                request.name.unwrap_or(existing_account.name),
+                request.acc_type.unwrap_or(existing_account.acc_type),
+                opening_balance,
+            )
+            .await?;
+
+        Ok(updated_account.into_dto())
+    }
+
+    async fn delete_account(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let existing_account =
+            permission_utils::find_owned_account(&self.repository, id, user_id).await?;
+
+        self.repository.delete_account(existing_account.id).await?;
+
+        Ok(())
+    }
+
+    async fn get_ledger(&self, id: Uuid, user_id: Uuid) -> AppResult<Vec<LedgerEntryDto>> {
+        let existing_account =
+            permission_utils::find_owned_account(&self.repository, id, user_id).await?;
+
+        let entries = self.repository.get_ledger(existing_account.id).await?;
+
+        Ok(entries.into_iter().map(|entry| entry.into_dto()).collect())
+    }
+
+    async fn create_transfer(
+        &self,
+        user_id: Uuid,
+        request: TransferCreateDto,
+    ) -> AppResult<TransferResponseDto> {
+        let from_account_id = request.from_account_id.unwrap();
+        let to_account_id = request.to_account_id.unwrap();
+
+        if from_account_id == to_account_id {
+            return Err(Error::BadRequest(String::from(
+                "transfers must be between two different accounts",
+            )));
+        }
+
+        let amount = request.amount.unwrap();
+
+        if amount <= Decimal::ZERO {
+            error!("transfer amount {:?} is not positive", amount);
+            return Err(Error::BadRequest(String::from(
+                "amount must be greater than zero",
+            )));
+        }
+
+        currency_utils::ensure_amount_fits(amount)?;
+
+        let from_account =
+            permission_utils::find_owned_account(&self.repository, from_account_id, user_id)
+                .await?;
+        let to_account =
+            permission_utils::find_owned_account(&self.repository, to_account_id, user_id).await?;
+
+        if from_account.currency != to_account.currency {
+            error!(
+                "cannot transfer between {:?} and {:?} accounts",
+                from_account.currency, to_account.currency
+            );
+            return Err(Error::BadRequest(String::from(
+                "transfers must be between accounts of the same currency",
+            )));
+        }
+
+        info!(
+            "transferring {:?} from account {:?} to account {:?}",
+            amount, from_account_id, to_account_id
+        );
+        let (outgoing, incoming) = self
+            .transactions_repository
+            .create_transfer(
+                user_id,
+                TransferFields {
+                    from_account_id,
+                    to_account_id,
+                    amount,
+                    currency: from_account.currency,
+                    date: request.date.unwrap(),
+                    note: request.note,
+                },
+            )
+            .await?;
+
+        Ok(TransferResponseDto {
+            transfer_id: outgoing.transfer_id.unwrap_or_default(),
+            outgoing: outgoing.into_dto(),
+            incoming: incoming.into_dto(),
+        })
+    }
+}
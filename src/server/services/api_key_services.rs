@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::database::api_key::DynApiKeysRepository;
+use crate::database::role::{AccessGrants, DynRolesRepository};
+use crate::server::dtos::api_key_dto::{ApiKeyDto, CreateApiKeyDto, CreatedApiKeyDto};
+use crate::server::error::{AppResult, Error};
+use crate::server::utils::{api_key_utils, permission_utils, token_utils};
+
+/// A reference counter for our API key service, managing and authenticating the keys used by scripts and integrations.
+pub type DynApiKeysService = Arc<dyn ApiKeysServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ApiKeysServiceTrait {
+    async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        request: CreateApiKeyDto,
+    ) -> AppResult<CreatedApiKeyDto>;
+
+    async fn get_api_keys(&self, user_id: Uuid) -> AppResult<Vec<ApiKeyDto>>;
+
+    async fn revoke_api_key(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    /// Validates an API key, returning the ID of the user it belongs to. Keys lacking the required
+    /// scope, or whose owner no longer holds that permission, are forbidden. No required scope
+    /// means the route does not accept API keys at all.
+    async fn authenticate(&self, key: String, required_scope: Option<String>) -> AppResult<Uuid>;
+}
+
+#[derive(Clone)]
+pub struct ApiKeysService {
+    repository: DynApiKeysRepository,
+    roles_repository: DynRolesRepository,
+    config: Arc<AppConfig>,
+}
+
+impl ApiKeysService {
+    pub fn new(
+        repository: DynApiKeysRepository,
+        roles_repository: DynRolesRepository,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            roles_repository,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeysServiceTrait for ApiKeysService {
+    async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        request: CreateApiKeyDto,
+    ) -> AppResult<CreatedApiKeyDto> {
+        let name = request.name.unwrap();
+        let mut scopes = request.scopes.unwrap();
+        let expires_in_days = request
+            .expires_in_days
+            .unwrap_or(self.config.api_key_ttl_days);
+
+        if let Some(unknown_scope) = scopes
+            .iter()
+            .find(|scope| !api_key_utils::SCOPES.contains(&scope.as_str()))
+        {
+            return Err(Error::BadRequest(format!(
+                "scope {} does not exist",
+                unknown_scope
+            )));
+        }
+
+        let grants = AccessGrants::from(self.roles_repository.get_roles_by_user_id(user_id).await?);
+
+        // keys can never grant more than their owner is allowed to do
+        if scopes.iter().any(|scope| !grants.has_permission(scope)) {
+            error!("user {:?} requested scopes they do not hold", user_id);
+            return Err(Error::Forbidden);
+        }
+
+        scopes.sort();
+        scopes.dedup();
+
+        let from_now = Duration::from_secs(expires_in_days * 86400);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        let (prefix, key) = api_key_utils::generate_api_key();
+
+        info!("creating API key {:?} for user {:?}", prefix, user_id);
+        let created_api_key = self
+            .repository
+            .create_api_key(
+                user_id,
+                &name,
+                &prefix,
+                &token_utils::hash_token(&key),
+                scopes,
+                &exp,
+            )
+            .await?;
+
+        Ok(CreatedApiKeyDto {
+            api_key: created_api_key.into_dto(),
+            key,
+        })
+    }
+
+    async fn get_api_keys(&self, user_id: Uuid) -> AppResult<Vec<ApiKeyDto>> {
+        info!("retrieving API keys for user {:?}", user_id);
+        let api_keys = self.repository.get_api_keys_by_user_id(user_id).await?;
+
+        Ok(api_keys
+            .into_iter()
+            .map(|api_key| api_key.into_dto())
+            .collect())
+    }
+
+    async fn revoke_api_key(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let api_key = self.repository.get_api_key_by_id(id).await?;
+
+        if let Some(existing_api_key) = api_key {
+            permission_utils::ensure_owner(existing_api_key.user_id, user_id)?;
+
+            self.repository.delete_api_key(existing_api_key.id).await?;
+
+            info!("API key {:?} revoked", id);
+
+            return Ok(());
+        }
+
+        Err(Error::NotFound(String::from("API key was not found")))
+    }
+
+    async fn authenticate(&self, key: String, required_scope: Option<String>) -> AppResult<Uuid> {
+        let prefix = api_key_utils::parse_prefix(&key).ok_or_else(|| {
+            error!("malformed API key");
+            Error::Unauthorized
+        })?;
+
+        let api_key = self
+            .repository
+            .get_api_key_by_prefix(prefix)
+            .await?
+            .filter(|api_key| api_key.key_hash == token_utils::hash_token(&key))
+            .ok_or_else(|| {
+                error!("no API key found for prefix {:?}", prefix);
+                Error::Unauthorized
+            })?;
+
+        if api_key.exp < OffsetDateTime::from(SystemTime::now()) {
+            error!("API key {:?} has expired", api_key.id);
+            return Err(Error::Unauthorized);
+        }
+
+        let required_scope = required_scope
+            .filter(|required_scope| api_key.scopes.contains(required_scope))
+            .ok_or_else(|| {
+                error!("API key {:?} is missing the required scope", api_key.id);
+                Error::Forbidden
+            })?;
+
+        let grants = AccessGrants::from(
+            self.roles_repository
+                .get_roles_by_user_id(api_key.user_id)
+                .await?,
+        );
+
+        // scopes only narrow what the owner can do, losing a role also takes it away from their keys
+        if !grants.has_permission(&required_scope) {
+            error!(
+                "owner of API key {:?} no longer holds the {:?} permission",
+                api_key.id, required_scope
+            );
+            return Err(Error::Forbidden);
+        }
+
+        self.repository.touch_api_key(api_key.id).await?;
+
+        Ok(api_key.user_id)
+    }
+}
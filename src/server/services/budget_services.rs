@@ -0,0 +1,200 @@
+use mockall::automock;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::{
+        budget::DynBudgetsRepository, category::DynCategoriesRepository, user::DynUsersRepository,
+    },
+    server::{
+        dtos::budget_dto::{BudgetCopyDto, BudgetResponseDto, BudgetSetDto, BudgetSummaryDto},
+        error::{AppResult, Error},
+        utils::{currency_utils, date_utils, permission_utils},
+    },
+};
+
+/// A reference counter for our budget service, tracking monthly spending limits of categories.
+pub type DynBudgetsService = Arc<dyn BudgetsServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait BudgetsServiceTrait {
+    /// Sets the limit of a category for a month, months are formatted as `YYYY-MM`.
+    async fn set_budget(
+        &self,
+        user_id: Uuid,
+        month: String,
+        category_id: Uuid,
+        request: BudgetSetDto,
+    ) -> AppResult<BudgetResponseDto>;
+
+    /// Copies the budgets of another month, limits already set for the month are left untouched.
+    async fn copy_budgets(
+        &self,
+        user_id: Uuid,
+        month: String,
+        request: BudgetCopyDto,
+    ) -> AppResult<Vec<BudgetResponseDto>>;
+
+    async fn delete_budget(&self, user_id: Uuid, month: String, category_id: Uuid)
+        -> AppResult<()>;
+
+    /// Compares the expenses of a month against its budgets, per category and per category type.
+    async fn get_budget_summary(&self, user_id: Uuid, month: String)
+        -> AppResult<BudgetSummaryDto>;
+}
+
+#[derive(Clone)]
+pub struct BudgetsService {
+    repository: DynBudgetsRepository,
+    categories_repository: DynCategoriesRepository,
+    users_repository: DynUsersRepository,
+}
+
+impl BudgetsService {
+    pub fn new(
+        repository: DynBudgetsRepository,
+        categories_repository: DynCategoriesRepository,
+        users_repository: DynUsersRepository,
+    ) -> Self {
+        Self {
+            repository,
+            categories_repository,
+            users_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl BudgetsServiceTrait for BudgetsService {
+    async fn set_budget(
+        &self,
+        user_id: Uuid,
+        month: String,
+        category_id: Uuid,
+        request: BudgetSetDto,
+    ) -> AppResult<BudgetResponseDto> {
+        let month = date_utils::parse_month(&month)?;
+        let amount = request.amount.unwrap();
+
+        if amount < Decimal::ZERO {
+            error!("budget amount {:?} is negative", amount);
+            return Err(Error::BadRequest(String::from("amount cannot be negative")));
+        }
+
+        currency_utils::ensure_amount_fits(amount)?;
+
+        permission_utils::find_owned_category(&self.categories_repository, category_id, user_id)
+            .await?;
+
+        let currency = match request.currency {
+            Some(currency) => currency_utils::normalize_currency(&currency)?,
+            None => {
+                self.users_repository
+                    .get_user_by_id(user_id)
+                    .await?
+                    .base_currency
+            }
+        };
+
+        info!(
+            "setting budget of category {:?} for {:?}",
+            category_id, month
+        );
+        let budget = self
+            .repository
+            .upsert_budget(user_id, category_id, month, amount, currency)
+            .await?;
+
+        Ok(budget.into_dto())
+    }
+
+    async fn copy_budgets(
+        &self,
+        user_id: Uuid,
+        month: String,
+        request: BudgetCopyDto,
+    ) -> AppResult<Vec<BudgetResponseDto>> {
+        let to_month = date_utils::parse_month(&month)?;
+        let from_month = date_utils::parse_month(&request.from_month.unwrap())?;
+
+        if from_month == to_month {
+            return Err(Error::BadRequest(String::from(
+                "budgets cannot be copied onto the same month",
+            )));
+        }
+
+        let copied = self
+            .repository
+            .copy_budgets(user_id, from_month, to_month)
+            .await?;
+
+        info!(
+            "copied {} budgets from {:?} to {:?}",
+            copied, from_month, to_month
+        );
+
+        let budgets = self.repository.get_budgets(user_id, to_month).await?;
+
+        Ok(budgets
+            .into_iter()
+            .map(|budget| budget.into_dto())
+            .collect())
+    }
+
+    async fn delete_budget(
+        &self,
+        user_id: Uuid,
+        month: String,
+        category_id: Uuid,
+    ) -> AppResult<()> {
+        let month = date_utils::parse_month(&month)?;
+        let budget = self.repository.get_budget(category_id, month).await?;
+
+        if let Some(existing_budget) = budget {
+            permission_utils::ensure_owner(existing_budget.user_id, user_id)?;
+
+            self.repository.delete_budget(existing_budget.id).await?;
+
+            return Ok(());
+        }
+
+        Err(Error::NotFound(String::from("budget was not found")))
+    }
+
+    async fn get_budget_summary(
+        &self,
+        user_id: Uuid,
+        month: String,
+    ) -> AppResult<BudgetSummaryDto> {
+        let month = date_utils::parse_month(&month)?;
+
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        let lines = self.repository.get_budget_lines(user_id, month).await?;
+        let totals = self.repository.get_budget_totals(user_id, month).await?;
+        let unconverted_currencies = self
+            .repository
+            .get_unconverted_currencies(user_id, month)
+            .await?;
+
+        if !unconverted_currencies.is_empty() {
+            error!(
+                "no exchange rate from {:?} into {:?} for {:?}",
+                unconverted_currencies, user.base_currency, month
+            );
+        }
+
+        Ok(BudgetSummaryDto {
+            month: date_utils::format_month(month),
+            currency: user.base_currency,
+            categories: lines.into_iter().map(|line| line.into_dto()).collect(),
+            totals: totals.into_iter().map(|total| total.into_dto()).collect(),
+            unconverted_currencies,
+        })
+    }
+}
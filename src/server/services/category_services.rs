@@ -10,6 +10,7 @@ use crate::{
     server::{
         dtos::category_dto::{CategoryCreateDto, CategoryResponseDto, CategoryUpdateDto},
         error::{AppResult, Error},
+        utils::permission_utils,
     },
 };
 
@@ -74,10 +75,7 @@ impl CategoriesServiceTrait for CategoriesService {
         let category = self.repository.get_category_by_id(id).await?;
 
         if let Some(existing_category) = category {
-            // verify the user IDs match on the request and the category
-            if existing_category.user_id != user_id {
-                return Err(Error::Forbidden);
-            }
+            permission_utils::ensure_owner(existing_category.user_id, user_id)?;
 
             return Ok(existing_category.into_dto());
         }
@@ -100,10 +98,7 @@ impl CategoriesServiceTrait for CategoriesService {
         let category_to_update = self.repository.get_category_by_id(id).await?;
 
         if let Some(existing_category) = category_to_update {
-            // verify the user IDs match on the request and the category
-            if existing_category.user_id != user_id {
-                return Err(Error::Forbidden);
-            }
+            permission_utils::ensure_owner(existing_category.user_id, user_id)?;
 
             let updated_name = request.name.unwrap_or(existing_category.name);
             let update_cat_type = request.cat_type.unwrap_or(existing_category.cat_type);
@@ -123,10 +118,7 @@ impl CategoriesServiceTrait for CategoriesService {
         let category = self.repository.get_category_by_id(id).await?;
 
         if let Some(existing_category) = category {
-            // verify the user IDs match on the request and the category
-            if existing_category.user_id != user_id {
-                return Err(Error::Forbidden);
-            }
+            permission_utils::ensure_owner(existing_category.user_id, user_id)?;
 
             self.repository
                 .delete_category(existing_category.id)
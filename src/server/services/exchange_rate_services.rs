@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use mockall::automock;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    config::ExchangeRatesFormat,
+    database::exchange_rate::{DynExchangeRatesRepository, ExchangeRateFields},
+    server::{
+        dtos::exchange_rate_dto::ExchangeRateRecordDto,
+        error::{AppResult, Error},
+        utils::{currency_utils, date_utils},
+    },
+};
+
+/// Columns a CSV file of exchange rates needs, in any order.
+pub const EXCHANGE_RATE_COLUMNS: [&str; 4] =
+    ["base_currency", "quote_currency", "rate", "effective_on"];
+
+/// Rates are stored with ten decimal places and at most fourteen digits before the decimal point.
+const MAX_RATE_SCALE: u32 = 10;
+
+/// A reference counter for our exchange rate service, loading the rates reports are converted with.
+pub type DynExchangeRatesService = Arc<dyn ExchangeRatesServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ExchangeRatesServiceTrait {
+    /// Validates every rate of a file before storing any of them, returning the number of rates stored.
+    async fn import_exchange_rates(
+        &self,
+        contents: &str,
+        format: ExchangeRatesFormat,
+    ) -> AppResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct ExchangeRatesService {
+    repository: DynExchangeRatesRepository,
+}
+
+impl ExchangeRatesService {
+    pub fn new(repository: DynExchangeRatesRepository) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl ExchangeRatesServiceTrait for ExchangeRatesService {
+    async fn import_exchange_rates(
+        &self,
+        contents: &str,
+        format: ExchangeRatesFormat,
+    ) -> AppResult<u64> {
+        let records = match format {
+            ExchangeRatesFormat::Csv => parse_csv(contents)?,
+            ExchangeRatesFormat::Json => parse_json(contents)?,
+        };
+
+        if records.is_empty() {
+            return Err(Error::BadRequest(String::from(
+                "the file does not contain any exchange rates",
+            )));
+        }
+
+        let rates = records
+            .into_iter()
+            .map(|(position, record)| {
+                validate_record(record).map_err(|err| match err {
+                    Error::BadRequest(message) => {
+                        Error::BadRequest(format!("{}: {}", position, message))
+                    }
+                    err => err,
+                })
+            })
+            .collect::<AppResult<Vec<ExchangeRateFields>>>()?;
+
+        info!("storing {} exchange rates", rates.len());
+        let stored = self.repository.upsert_exchange_rates(rates).await?;
+
+        Ok(stored)
+    }
+}
+
+/// Reads the rows of a CSV file along with their line numbers, fields are split on commas and may be
+/// quoted to contain commas, with `""` standing for a quote. Quoted fields cannot span lines.
+fn parse_csv(contents: &str) -> AppResult<Vec<(String, ExchangeRateRecordDto)>> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let header = match lines.next() {
+        Some((index, header)) => split_csv_line(header, index)?,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut columns = [0; EXCHANGE_RATE_COLUMNS.len()];
+
+    for (index, column) in EXCHANGE_RATE_COLUMNS.iter().enumerate() {
+        columns[index] = header
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(column))
+            .ok_or_else(|| {
+                Error::BadRequest(format!("the CSV header is missing the {} column", column))
+            })?;
+    }
+
+    let [base_currency, quote_currency, rate, effective_on] = columns;
+
+    lines
+        .map(|(index, line)| {
+            let position = format!("line {}", index + 1);
+            let fields = split_csv_line(line, index)?;
+
+            if fields.len() != header.len() {
+                error!("{} has {} fields", position, fields.len());
+                return Err(Error::BadRequest(format!(
+                    "{}: expected {} fields",
+                    position,
+                    header.len()
+                )));
+            }
+
+            let record = ExchangeRateRecordDto {
+                base_currency: fields[base_currency].clone(),
+                quote_currency: fields[quote_currency].clone(),
+                rate: Decimal::from_str(&fields[rate]).map_err(|_| {
+                    Error::BadRequest(format!("{}: rate must be a decimal number", position))
+                })?,
+                effective_on: date_utils::parse_date(&fields[effective_on]).map_err(|_| {
+                    Error::BadRequest(format!(
+                        "{}: effective_on must be formatted as YYYY-MM-DD",
+                        position
+                    ))
+                })?,
+            };
+
+            Ok((position, record))
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str, index: usize) -> AppResult<Vec<String>> {
+    let invalid_quotes = || {
+        error!("line {} has unbalanced quotes", index + 1);
+        Error::BadRequest(format!("line {}: fields have unbalanced quotes", index + 1))
+    };
+
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| *c != ',' && c.is_whitespace()).is_some() {}
+
+        let mut field = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(invalid_quotes()),
+                }
+            }
+
+            while chars.next_if(|c| *c != ',' && c.is_whitespace()).is_some() {}
+
+            if chars.peek().is_some_and(|c| *c != ',') {
+                return Err(invalid_quotes());
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                if c == '"' {
+                    return Err(invalid_quotes());
+                }
+
+                field.push(c);
+            }
+
+            field.truncate(field.trim_end().len());
+        }
+
+        fields.push(field);
+
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+fn parse_json(contents: &str) -> AppResult<Vec<(String, ExchangeRateRecordDto)>> {
+    let records = serde_json::from_str::<Vec<ExchangeRateRecordDto>>(contents).map_err(|err| {
+        error!("could not parse exchange rates: {:?}", err);
+        Error::BadRequest(format!("invalid exchange rates file, {}", err))
+    })?;
+
+    Ok(records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| (format!("rate {}", index + 1), record))
+        .collect())
+}
+
+fn validate_record(record: ExchangeRateRecordDto) -> AppResult<ExchangeRateFields> {
+    let base_currency = currency_utils::normalize_currency(&record.base_currency)?;
+    let quote_currency = currency_utils::normalize_currency(&record.quote_currency)?;
+
+    if base_currency == quote_currency {
+        return Err(Error::BadRequest(String::from(
+            "a rate needs two different currencies",
+        )));
+    }
+
+    if record.rate <= Decimal::ZERO {
+        return Err(Error::BadRequest(String::from(
+            "rate must be greater than zero",
+        )));
+    }
+
+    if record.rate.normalize().scale() > MAX_RATE_SCALE
+        || record.rate >= Decimal::from(100_000_000_000_000u64)
+    {
+        return Err(Error::BadRequest(String::from(
+            "rate must have at most fourteen digits before and ten after the decimal point",
+        )));
+    }
+
+    Ok(ExchangeRateFields {
+        base_currency,
+        quote_currency,
+        rate: record.rate,
+        effective_on: record.effective_on,
+    })
+}
@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use mockall::automock;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::database::impersonation_event::DynImpersonationEventsRepository;
+use crate::database::role::{AccessGrants, DynRolesRepository};
+use crate::database::user::DynUsersRepository;
+use crate::server::dtos::user_dto::ResponseUserDto;
+use crate::server::error::{AppResult, Error};
+use crate::server::utils::jwt_utils::{AccessToken, DynJwtUtil};
+use crate::server::utils::permission_utils::ROLE_ADMIN;
+
+/// Audit event recorded when an admin is issued an impersonation token.
+pub const IMPERSONATION_STARTED: &str = "started";
+
+/// Audit event recorded for every request made with an impersonation token.
+pub const IMPERSONATION_REQUEST: &str = "request";
+
+/// A reference counter for our impersonation service, letting admins act as other users.
+pub type DynImpersonationService = Arc<dyn ImpersonationServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait ImpersonationServiceTrait {
+    /// Issues a short-lived access token acting as the user on behalf of the admin, admins cannot be impersonated.
+    async fn impersonate(&self, admin_id: Uuid, user_id: Uuid) -> AppResult<ResponseUserDto>;
+
+    /// Retrieves the impersonated user without issuing a new token, the regular token of the user must never leak.
+    async fn get_impersonated_user(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<ResponseUserDto>;
+
+    /// Appends a request made with an impersonation token to the audit trail.
+    async fn record_request(
+        &self,
+        access_token: &AccessToken,
+        method: String,
+        path: String,
+    ) -> AppResult<()>;
+}
+
+#[derive(Clone)]
+pub struct ImpersonationService {
+    repository: DynImpersonationEventsRepository,
+    users_repository: DynUsersRepository,
+    roles_repository: DynRolesRepository,
+    jwt_util: DynJwtUtil,
+}
+
+impl ImpersonationService {
+    pub fn new(
+        repository: DynImpersonationEventsRepository,
+        users_repository: DynUsersRepository,
+        roles_repository: DynRolesRepository,
+        jwt_util: DynJwtUtil,
+    ) -> Self {
+        Self {
+            repository,
+            users_repository,
+            roles_repository,
+            jwt_util,
+        }
+    }
+}
+
+#[async_trait]
+impl ImpersonationServiceTrait for ImpersonationService {
+    async fn impersonate(&self, admin_id: Uuid, user_id: Uuid) -> AppResult<ResponseUserDto> {
+        if admin_id == user_id {
+            error!("admin {:?} attempted to impersonate themselves", admin_id);
+            return Err(Error::BadRequest(String::from(
+                "admins cannot impersonate themselves",
+            )));
+        }
+
+        info!("retrieving user {:?}", user_id);
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        let grants = AccessGrants::from(self.roles_repository.get_roles_by_user_id(user_id).await?);
+
+        // impersonating an admin would hand out admin access without an audit of who holds it
+        if grants.has_role(ROLE_ADMIN) {
+            error!(
+                "admin {:?} attempted to impersonate admin {:?}",
+                admin_id, user_id
+            );
+            return Err(Error::Forbidden);
+        }
+
+        let token = self.jwt_util.new_impersonation_token(
+            user.id,
+            user.email.as_str(),
+            &grants,
+            admin_id,
+        )?;
+
+        let access_token_id = self.jwt_util.get_access_from_token(token.clone())?.jti;
+
+        info!("admin {:?} is impersonating user {:?}", admin_id, user_id);
+        self.repository
+            .create_impersonation_event(
+                admin_id,
+                user_id,
+                IMPERSONATION_STARTED,
+                Some(access_token_id),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut user = user.into_dto(token);
+        user.impersonated_by = Some(admin_id);
+
+        Ok(user)
+    }
+
+    async fn get_impersonated_user(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<ResponseUserDto> {
+        info!("retrieving user {:?}", user_id);
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        let mut user = user.into_dto(String::new());
+        user.access_token = None;
+        user.impersonated_by = Some(admin_id);
+
+        Ok(user)
+    }
+
+    async fn record_request(
+        &self,
+        access_token: &AccessToken,
+        method: String,
+        path: String,
+    ) -> AppResult<()> {
+        let admin_id = access_token.actor_id.ok_or(Error::Unauthorized)?;
+
+        info!(
+            "admin {:?} acting as user {:?} requested {} {}",
+            admin_id, access_token.user_id, method, path
+        );
+        self.repository
+            .create_impersonation_event(
+                admin_id,
+                access_token.user_id,
+                IMPERSONATION_REQUEST,
+                Some(access_token.jti),
+                Some(method),
+                Some(path),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
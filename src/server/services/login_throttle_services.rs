@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::database::lockout_event::DynLockoutEventsRepository;
+use crate::database::login_throttle::DynLoginThrottlesRepository;
+use crate::database::user::DynUsersRepository;
+use crate::server::error::{AppResult, Error};
+
+pub const ACCOUNT_SCOPE: &str = "account";
+pub const IP_SCOPE: &str = "ip";
+pub const MFA_SCOPE: &str = "mfa";
+
+pub const LOCKED_EVENT: &str = "locked";
+pub const UNLOCKED_EVENT: &str = "unlocked";
+
+/// A reference counter for our login throttle service, tracking failed sign in attempts to lock out brute force attacks.
+pub type DynLoginThrottlesService = Arc<dyn LoginThrottlesServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait LoginThrottlesServiceTrait {
+    /// Rejects the attempt while either the account or the client IP is locked out. Accounts are tracked by
+    /// email whether or not they exist, so a lockout reveals nothing about which emails are registered.
+    async fn ensure_allowed(&self, email: &str, ip_address: Option<IpAddr>) -> AppResult<()>;
+
+    /// Counts a failed attempt against the account and the client IP, locking them out once they run out of attempts.
+    async fn record_failure(&self, email: &str, ip_address: Option<IpAddr>) -> AppResult<()>;
+
+    /// Forgets the failed attempts of an account, used once its owner proves who they are.
+    async fn clear_account(&self, email: &str) -> AppResult<()>;
+
+    /// Rejects the second factor of a sign in while the user or the client IP is locked out. MFA failures are
+    /// tracked by user apart from password failures, so passing the password step again does not reset them.
+    async fn ensure_mfa_allowed(&self, user_id: Uuid, ip_address: Option<IpAddr>) -> AppResult<()>;
+
+    /// Counts a wrong TOTP or recovery code against the user and the client IP.
+    async fn record_mfa_failure(&self, user_id: Uuid, ip_address: Option<IpAddr>) -> AppResult<()>;
+
+    /// Forgets the failed MFA attempts of a user once they completed a sign in.
+    async fn clear_mfa(&self, user_id: Uuid) -> AppResult<()>;
+
+    /// Lifts the lockout of a user on behalf of an admin.
+    async fn unlock_user(&self, user_id: Uuid) -> AppResult<()>;
+}
+
+#[derive(Clone)]
+pub struct LoginThrottlesService {
+    repository: DynLoginThrottlesRepository,
+    lockout_events_repository: DynLockoutEventsRepository,
+    users_repository: DynUsersRepository,
+    config: Arc<AppConfig>,
+}
+
+impl LoginThrottlesService {
+    pub fn new(
+        repository: DynLoginThrottlesRepository,
+        lockout_events_repository: DynLockoutEventsRepository,
+        users_repository: DynUsersRepository,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            lockout_events_repository,
+            users_repository,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl LoginThrottlesServiceTrait for LoginThrottlesService {
+    async fn ensure_allowed(&self, email: &str, ip_address: Option<IpAddr>) -> AppResult<()> {
+        self.ensure_unlocked(ACCOUNT_SCOPE, &account_subject(email))
+            .await?;
+
+        if let Some(ip_address) = ip_address {
+            self.ensure_unlocked(IP_SCOPE, &ip_address.to_string())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn record_failure(&self, email: &str, ip_address: Option<IpAddr>) -> AppResult<()> {
+        self.record_failure_for(
+            ACCOUNT_SCOPE,
+            &account_subject(email),
+            self.config.login_max_failed_attempts,
+        )
+        .await?;
+
+        if let Some(ip_address) = ip_address {
+            self.record_failure_for(
+                IP_SCOPE,
+                &ip_address.to_string(),
+                self.config.login_max_failed_attempts_per_ip,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_account(&self, email: &str) -> AppResult<()> {
+        self.clear_subject(ACCOUNT_SCOPE, &account_subject(email))
+            .await
+    }
+
+    async fn ensure_mfa_allowed(&self, user_id: Uuid, ip_address: Option<IpAddr>) -> AppResult<()> {
+        self.ensure_unlocked(MFA_SCOPE, &user_id.to_string())
+            .await?;
+
+        if let Some(ip_address) = ip_address {
+            self.ensure_unlocked(IP_SCOPE, &ip_address.to_string())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn record_mfa_failure(&self, user_id: Uuid, ip_address: Option<IpAddr>) -> AppResult<()> {
+        self.record_failure_for(
+            MFA_SCOPE,
+            &user_id.to_string(),
+            self.config.login_max_failed_attempts,
+        )
+        .await?;
+
+        if let Some(ip_address) = ip_address {
+            self.record_failure_for(
+                IP_SCOPE,
+                &ip_address.to_string(),
+                self.config.login_max_failed_attempts_per_ip,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_mfa(&self, user_id: Uuid) -> AppResult<()> {
+        self.clear_subject(MFA_SCOPE, &user_id.to_string()).await
+    }
+
+    async fn unlock_user(&self, user_id: Uuid) -> AppResult<()> {
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        info!("unlocking user {:?}", user_id);
+        self.clear_account(&user.email).await?;
+        self.clear_mfa(user_id).await
+    }
+}
+
+impl LoginThrottlesService {
+    async fn clear_subject(&self, scope: &str, subject: &str) -> AppResult<()> {
+        let cleared_throttle = self
+            .repository
+            .delete_login_throttle(scope, subject)
+            .await?;
+
+        if let Some(throttle) = cleared_throttle.filter(|throttle| throttle.locked_until.is_some())
+        {
+            info!("lockout lifted for {} {:?}", scope, subject);
+            self.lockout_events_repository
+                .create_lockout_event(
+                    scope,
+                    subject,
+                    UNLOCKED_EVENT,
+                    throttle.failed_attempts,
+                    None,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_unlocked(&self, scope: &str, subject: &str) -> AppResult<()> {
+        let throttle = self.repository.get_login_throttle(scope, subject).await?;
+        let now = OffsetDateTime::from(SystemTime::now());
+
+        if let Some(locked_until) = throttle.and_then(|throttle| throttle.locked_until) {
+            if locked_until > now {
+                warn!(
+                    "rejecting sign in attempt for locked {} {:?}",
+                    scope, subject
+                );
+                return Err(Error::TooManyLoginAttempts);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_failure_for(
+        &self,
+        scope: &str,
+        subject: &str,
+        max_failed_attempts: u32,
+    ) -> AppResult<()> {
+        let now = SystemTime::now();
+        let window = Duration::from_secs(self.config.login_failure_window_minutes * 60);
+        let window_start = OffsetDateTime::from(now - window);
+
+        let throttle = self
+            .repository
+            .record_failed_login(scope, subject, &window_start)
+            .await?;
+
+        let failed_attempts = u32::try_from(throttle.failed_attempts).unwrap_or(0);
+
+        if failed_attempts < max_failed_attempts {
+            return Ok(());
+        }
+
+        let lockout = lockout_duration(
+            failed_attempts - max_failed_attempts,
+            self.config.login_lockout_seconds,
+            self.config.login_max_lockout_seconds,
+        );
+        let locked_until = OffsetDateTime::from(now + lockout);
+
+        warn!(
+            "locking {} {:?} for {} seconds after {} failed attempts",
+            scope,
+            subject,
+            lockout.as_secs(),
+            failed_attempts
+        );
+        self.repository
+            .lock_login_throttle(scope, subject, &locked_until)
+            .await?;
+
+        self.lockout_events_repository
+            .create_lockout_event(
+                scope,
+                subject,
+                LOCKED_EVENT,
+                throttle.failed_attempts,
+                Some(locked_until),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Emails are compared case insensitively so a lockout cannot be sidestepped by changing their case.
+fn account_subject(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Doubles the lockout for every attempt past the allowed number, up to the configured maximum.
+pub fn lockout_duration(attempts_over_limit: u32, base_seconds: u64, max_seconds: u64) -> Duration {
+    let seconds = 2u64
+        .checked_pow(attempts_over_limit)
+        .and_then(|multiplier| base_seconds.checked_mul(multiplier))
+        .unwrap_or(max_seconds)
+        .min(max_seconds);
+
+    Duration::from_secs(seconds)
+}
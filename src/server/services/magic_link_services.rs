@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+use crate::{
+    config::AppConfig,
+    database::{magic_link::DynMagicLinksRepository, user::DynUsersRepository},
+    server::{
+        dtos::user_dto::{MagicLinkDto, SignInMagicLinkDto, SignInOutcome},
+        error::{AppResult, Error},
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::DynJwtUtil,
+            mailer_utils::{DynMailer, Email},
+            token_utils,
+        },
+    },
+};
+
+use super::session_services::{self, DynSessionsService};
+
+/// A reference counter for our magic link service, signing users in with single-use links sent by email.
+pub type DynMagicLinksService = Arc<dyn MagicLinksServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait MagicLinksServiceTrait {
+    /// Emails a sign in link, responding the same way for unknown and rate limited emails so neither can be detected.
+    async fn send_magic_link(&self, request: MagicLinkDto) -> AppResult<()>;
+
+    /// Exchanges the token of a sign in link for a session, users with MFA enabled still have to provide a code.
+    async fn signin_magic_link(
+        &self,
+        request: SignInMagicLinkDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome>;
+}
+
+#[derive(Clone)]
+pub struct MagicLinksService {
+    repository: DynMagicLinksRepository,
+    users_repository: DynUsersRepository,
+    argon_util: DynArgonUtil,
+    jwt_util: DynJwtUtil,
+    session_service: DynSessionsService,
+    mailer: DynMailer,
+    config: Arc<AppConfig>,
+}
+
+impl MagicLinksService {
+    pub fn new(
+        repository: DynMagicLinksRepository,
+        users_repository: DynUsersRepository,
+        argon_util: DynArgonUtil,
+        jwt_util: DynJwtUtil,
+        session_service: DynSessionsService,
+        mailer: DynMailer,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            users_repository,
+            argon_util,
+            jwt_util,
+            session_service,
+            mailer,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl MagicLinksServiceTrait for MagicLinksService {
+    async fn send_magic_link(&self, request: MagicLinkDto) -> AppResult<()> {
+        let email = request.email.unwrap();
+
+        info!("searching for existing user {:?}", email);
+        let existing_user = self.users_repository.get_user_by_email(&email).await?;
+
+        if existing_user.is_none() {
+            info!("no user found for {:?}, skipping sign in link email", email);
+            return Ok(());
+        }
+
+        let user = existing_user.unwrap();
+
+        let window = Duration::from_secs(self.config.magic_link_window_minutes * 60);
+        let window_start = OffsetDateTime::from(SystemTime::now().checked_sub(window).unwrap());
+
+        let recent_links = self
+            .repository
+            .count_magic_links_since(user.id, &window_start)
+            .await?;
+
+        if recent_links >= self.config.magic_link_max_requests as i64 {
+            error!(
+                "sign in link limit reached for user {:?}, skipping email",
+                user.id
+            );
+            return Ok(());
+        }
+
+        let token = token_utils::generate_token();
+        let from_now = Duration::from_secs(self.config.magic_link_ttl_minutes * 60);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        self.repository
+            .create_magic_link(user.id, &token_utils::hash_token(&token), &exp)
+            .await?;
+
+        info!("sign in link created for user {:?}, sending email", user.id);
+        let email = Email {
+            to: user.email,
+            subject: String::from("Your sign in link"),
+            body: format!(
+                "Hi {},\n\n\
+                Use the link below to sign in. It expires in {} minutes and can only be used once.\n\n\
+                {}/signin-link?token={}\n\n\
+                If you did not request this link, you can ignore this email.\n",
+                user.name, self.config.magic_link_ttl_minutes, self.config.app_url, token
+            ),
+        };
+
+        if let Err(err) = self.mailer.send(email).await {
+            error!(
+                "could not send sign in link email to user {:?}: {:?}",
+                user.id, err
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn signin_magic_link(
+        &self,
+        request: SignInMagicLinkDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome> {
+        let token = request.token.unwrap();
+
+        let magic_link = self
+            .repository
+            .consume_magic_link(&token_utils::hash_token(&token))
+            .await?;
+
+        if magic_link.is_none() {
+            error!("invalid or expired sign in link");
+            return Err(Error::Unauthorized);
+        }
+
+        let user_id = magic_link.unwrap().user_id;
+
+        info!("retrieving user {:?}", user_id);
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        // the link was delivered to the login email, so following it proves the address is in use
+        let user = session_services::verify_unverified_user(
+            &self.users_repository,
+            &self.argon_util,
+            &self.session_service,
+            user,
+        )
+        .await?;
+
+        if user.mfa_enabled_at.is_some() {
+            info!(
+                "sign in link accepted for user {:?}, MFA code required",
+                user_id
+            );
+            let mfa_token = self.jwt_util.new_mfa_token(user_id)?;
+
+            return Ok(SignInOutcome::MfaRequired(mfa_token));
+        }
+
+        info!("user {:?} signed in with a sign in link", user_id);
+        let (user, refresh_token) = session_services::start_session(
+            &self.users_repository,
+            &self.session_service,
+            user,
+            user_agent,
+            ip_address,
+        )
+        .await?;
+
+        Ok(SignInOutcome::Authenticated(user, refresh_token))
+    }
+}
@@ -3,67 +3,263 @@ use std::sync::Arc;
 use tracing::info;
 
 use crate::{
-    config::AppConfig,
-    database::Database,
+    config::{AppConfig, MailerTransport, RevocationStore},
+    database::{
+        revoked_access_token::{
+            DynRevokedAccessTokensRepository, InMemoryRevokedAccessTokensRepository,
+        },
+        Database,
+    },
     server::{
         services::{
-            category_services::CategoriesService, session_services::SessionsService,
+            account_services::AccountsService, api_key_services::ApiKeysService,
+            budget_services::BudgetsService, category_services::CategoriesService,
+            impersonation_services::ImpersonationService,
+            login_throttle_services::LoginThrottlesService, magic_link_services::MagicLinksService,
+            oidc_services::OidcService, passkey_services::PasskeysService,
+            personal_data_services::PersonalDataService,
+            recurring_transaction_services::RecurringTransactionsService,
+            role_services::RolesService, session_services::SessionsService,
+            signing_key_services::SigningKeysService, transaction_services::TransactionsService,
             user_services::UsersService,
         },
         utils::{
             argon_utils::{ArgonSecurityUtil, DynArgonUtil},
+            client_ip_utils::ClientIpUtil,
+            cookie_utils::CookieUtil,
             jwt_utils::JwtTokenUtil,
+            mailer_utils::{DynMailer, FileMailer, InMemoryMailer, SmtpMailer},
+            oidc_utils::{DynOidcClient, HttpOidcClient},
+            signing_key_utils::KeyRing,
         },
     },
 };
 
 use self::{
-    category_services::DynCategoriesService, session_services::DynSessionsService,
+    account_services::DynAccountsService, api_key_services::DynApiKeysService,
+    budget_services::DynBudgetsService, category_services::DynCategoriesService,
+    impersonation_services::DynImpersonationService,
+    login_throttle_services::DynLoginThrottlesService, magic_link_services::DynMagicLinksService,
+    oidc_services::DynOidcService, passkey_services::DynPasskeysService,
+    personal_data_services::DynPersonalDataService,
+    recurring_transaction_services::DynRecurringTransactionsService,
+    role_services::DynRolesService, session_services::DynSessionsService,
+    signing_key_services::DynSigningKeysService, transaction_services::DynTransactionsService,
     user_services::DynUsersService,
 };
 
 use super::utils::jwt_utils::DynJwtUtil;
 
+pub mod account_services;
+pub mod api_key_services;
+pub mod budget_services;
 pub mod category_services;
+pub mod exchange_rate_services;
+pub mod impersonation_services;
+pub mod login_throttle_services;
+pub mod magic_link_services;
+pub mod oidc_services;
+pub mod passkey_services;
+pub mod personal_data_services;
+pub mod recurring_transaction_services;
+pub mod role_services;
 pub mod seed_services;
 pub mod session_services;
+pub mod signing_key_services;
+pub mod transaction_services;
 pub mod user_services;
 
 #[derive(Clone)]
 pub struct Services {
     pub jwt_util: DynJwtUtil,
+    pub cookie_util: Arc<CookieUtil>,
+    pub client_ip_util: Arc<ClientIpUtil>,
+    pub signing_keys: DynSigningKeysService,
     pub users: DynUsersService,
     pub sessions: DynSessionsService,
+    pub login_throttles: DynLoginThrottlesService,
+    pub magic_links: DynMagicLinksService,
+    pub oidc: DynOidcService,
+    pub passkeys: DynPasskeysService,
+    pub api_keys: DynApiKeysService,
+    pub roles: DynRolesService,
+    pub impersonation: DynImpersonationService,
     pub categories: DynCategoriesService,
+    pub transactions: DynTransactionsService,
+    pub accounts: DynAccountsService,
+    pub recurring_transactions: DynRecurringTransactionsService,
+    pub budgets: DynBudgetsService,
+    pub personal_data: DynPersonalDataService,
 }
 
 impl Services {
     pub fn new(db: Database, config: Arc<AppConfig>) -> Self {
         info!("initializing utility services...");
         let security_service = Arc::new(ArgonSecurityUtil::new(config.clone())) as DynArgonUtil;
-        let jwt_util = Arc::new(JwtTokenUtil::new(config)) as DynJwtUtil;
+        let key_ring = Arc::new(KeyRing::default());
+        let jwt_util = Arc::new(JwtTokenUtil::new(config.clone(), key_ring.clone())) as DynJwtUtil;
+        let mailer = match config.mailer_transport {
+            MailerTransport::Smtp => Arc::new(
+                SmtpMailer::new(config.clone()).expect("could not initialize the SMTP mailer"),
+            ) as DynMailer,
+            MailerTransport::File => Arc::new(FileMailer::new(config.clone())) as DynMailer,
+            MailerTransport::Memory => Arc::new(InMemoryMailer::default()) as DynMailer,
+        };
+        let oidc_client = Arc::new(HttpOidcClient::new(config.clone())) as DynOidcClient;
+        let cookie_util = Arc::new(CookieUtil::new(config.clone()));
+        let client_ip_util = Arc::new(ClientIpUtil::new(config.clone()));
 
         info!("utility services initialized, building feature services...");
         let repository = Arc::new(db);
 
-        let sessions = Arc::new(SessionsService::new(repository.clone(), jwt_util.clone()))
-            as DynSessionsService;
+        let signing_keys = Arc::new(SigningKeysService::new(
+            repository.clone(),
+            key_ring,
+            config.clone(),
+        )) as DynSigningKeysService;
+
+        let revoked_access_tokens = match config.revocation_store {
+            RevocationStore::Postgres => repository.clone() as DynRevokedAccessTokensRepository,
+            RevocationStore::Memory => Arc::new(InMemoryRevokedAccessTokensRepository::default())
+                as DynRevokedAccessTokensRepository,
+        };
+
+        let sessions = Arc::new(SessionsService::new(
+            repository.clone(),
+            repository.clone(),
+            revoked_access_tokens,
+            jwt_util.clone(),
+            config.clone(),
+        )) as DynSessionsService;
+
+        let login_throttles = Arc::new(LoginThrottlesService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            config.clone(),
+        )) as DynLoginThrottlesService;
 
         let users = Arc::new(UsersService::new(
             repository.clone(),
-            security_service,
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            security_service.clone(),
             jwt_util.clone(),
             sessions.clone(),
+            login_throttles.clone(),
+            mailer.clone(),
+            config.clone(),
         )) as DynUsersService;
 
+        let magic_links = Arc::new(MagicLinksService::new(
+            repository.clone(),
+            repository.clone(),
+            security_service.clone(),
+            jwt_util.clone(),
+            sessions.clone(),
+            mailer,
+            config.clone(),
+        )) as DynMagicLinksService;
+
+        let oidc = Arc::new(OidcService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            oidc_client,
+            security_service.clone(),
+            jwt_util.clone(),
+            sessions.clone(),
+            config.clone(),
+        )) as DynOidcService;
+
+        let passkeys = Arc::new(PasskeysService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            sessions.clone(),
+            config.clone(),
+        )) as DynPasskeysService;
+
+        let api_keys = Arc::new(ApiKeysService::new(
+            repository.clone(),
+            repository.clone(),
+            config.clone(),
+        )) as DynApiKeysService;
+
+        let roles = Arc::new(RolesService::new(
+            repository.clone(),
+            repository.clone(),
+            sessions.clone(),
+        )) as DynRolesService;
+
+        let impersonation = Arc::new(ImpersonationService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            jwt_util.clone(),
+        )) as DynImpersonationService;
+
         let categories =
             Arc::new(CategoriesService::new(repository.clone())) as DynCategoriesService;
 
+        let transactions = Arc::new(TransactionsService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+        )) as DynTransactionsService;
+
+        let accounts = Arc::new(AccountsService::new(repository.clone(), repository.clone()))
+            as DynAccountsService;
+
+        let recurring_transactions = Arc::new(RecurringTransactionsService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+        )) as DynRecurringTransactionsService;
+
+        let budgets = Arc::new(BudgetsService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+        )) as DynBudgetsService;
+
+        let personal_data = Arc::new(PersonalDataService::new(
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            repository.clone(),
+            security_service,
+            sessions.clone(),
+            login_throttles.clone(),
+            config,
+        )) as DynPersonalDataService;
+
         Self {
             jwt_util,
+            cookie_util,
+            client_ip_util,
+            signing_keys,
             users,
             sessions,
+            login_throttles,
+            magic_links,
+            oidc,
+            passkeys,
+            api_keys,
+            roles,
+            impersonation,
             categories,
+            transactions,
+            accounts,
+            recurring_transactions,
+            budgets,
+            personal_data,
         }
     }
 }
@@ -0,0 +1,263 @@
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+use async_trait::async_trait;
+
+use crate::{
+    config::AppConfig,
+    oidc_login_state::DynOidcLoginStatesRepository,
+    server::{
+        dtos::{
+            oidc_dto::{OidcAuthorizationDto, OidcCallbackDto},
+            user_dto::SignInOutcome,
+        },
+        error::{AppResult, Error},
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::DynJwtUtil,
+            oidc_utils::{self, DynOidcClient, OidcIdentity},
+            token_utils,
+        },
+    },
+    user::{DynUsersRepository, User},
+    user_identity::DynUserIdentitiesRepository,
+};
+
+use super::session_services::{self, DynSessionsService};
+
+/// A reference counter for our OIDC service, signing users in through the configured external provider.
+pub type DynOidcService = Arc<dyn OidcServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait OidcServiceTrait {
+    /// Starts an authorization code flow, returning the provider URL the client has to navigate to.
+    async fn authorize(&self) -> AppResult<OidcAuthorizationDto>;
+
+    /// Completes the flow, signing in the user linked to the provider subject.
+    async fn callback(
+        &self,
+        request: OidcCallbackDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome>;
+}
+
+#[derive(Clone)]
+pub struct OidcService {
+    repository: DynUsersRepository,
+    identities_repository: DynUserIdentitiesRepository,
+    login_states_repository: DynOidcLoginStatesRepository,
+    oidc_client: DynOidcClient,
+    argon_util: DynArgonUtil,
+    jwt_util: DynJwtUtil,
+    session_service: DynSessionsService,
+    config: Arc<AppConfig>,
+}
+
+impl OidcService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: DynUsersRepository,
+        identities_repository: DynUserIdentitiesRepository,
+        login_states_repository: DynOidcLoginStatesRepository,
+        oidc_client: DynOidcClient,
+        argon_util: DynArgonUtil,
+        jwt_util: DynJwtUtil,
+        session_service: DynSessionsService,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            identities_repository,
+            login_states_repository,
+            oidc_client,
+            argon_util,
+            jwt_util,
+            session_service,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl OidcServiceTrait for OidcService {
+    async fn authorize(&self) -> AppResult<OidcAuthorizationDto> {
+        let state = token_utils::generate_token();
+        let nonce = token_utils::generate_token();
+        let code_verifier = token_utils::generate_token();
+
+        let authorization_url = self
+            .oidc_client
+            .authorization_url(&state, &nonce, &oidc_utils::code_challenge(&code_verifier))
+            .await?;
+
+        let from_now = Duration::from_secs(self.config.oidc_state_ttl_minutes * 60);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        info!("storing OIDC login state, redirecting to the provider");
+        self.login_states_repository
+            .create_oidc_login_state(
+                &token_utils::hash_token(&state),
+                &code_verifier,
+                &nonce,
+                &exp,
+            )
+            .await?;
+
+        Ok(OidcAuthorizationDto { authorization_url })
+    }
+
+    async fn callback(
+        &self,
+        request: OidcCallbackDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome> {
+        let code = request.code.unwrap();
+        let state = request.state.unwrap();
+
+        let login_state = self
+            .login_states_repository
+            .consume_oidc_login_state(&token_utils::hash_token(&state))
+            .await?;
+
+        if login_state.is_none() {
+            error!("invalid or expired OIDC login state");
+            return Err(Error::BadRequest(String::from(
+                "sign in state is invalid or has expired",
+            )));
+        }
+
+        let login_state = login_state.unwrap();
+
+        let identity = self
+            .oidc_client
+            .exchange_code(&code, &login_state.code_verifier, &login_state.nonce)
+            .await?;
+
+        info!("ID token validated for subject {:?}", identity.subject);
+        let user = self.resolve_user(identity).await?;
+
+        if self.config.require_email_verification && user.verified_at.is_none() {
+            error!("OIDC login attempt for unverified user {:?}", user.id);
+            return Err(Error::EmailNotVerified);
+        }
+
+        if user.mfa_enabled_at.is_some() {
+            info!("OIDC login for user {:?}, MFA code required", user.id);
+            let mfa_token = self.jwt_util.new_mfa_token(user.id)?;
+
+            return Ok(SignInOutcome::MfaRequired(mfa_token));
+        }
+
+        info!(
+            "OIDC login successful for user {:?}, generating tokens",
+            user.id
+        );
+        let (user, refresh_token) = session_services::start_session(
+            &self.repository,
+            &self.session_service,
+            user,
+            user_agent,
+            ip_address,
+        )
+        .await?;
+
+        Ok(SignInOutcome::Authenticated(user, refresh_token))
+    }
+}
+
+impl OidcService {
+    /// Finds the user linked to the provider subject, linking an existing account by email or
+    /// creating a new one on first sign in.
+    async fn resolve_user(&self, identity: OidcIdentity) -> AppResult<User> {
+        let provider = self.config.oidc_provider.as_str();
+
+        if let Some(linked_identity) = self
+            .identities_repository
+            .get_user_identity(provider, &identity.subject)
+            .await?
+        {
+            info!(
+                "subject {:?} is linked to user {:?}",
+                identity.subject, linked_identity.user_id
+            );
+            return Ok(self
+                .repository
+                .get_user_by_id(linked_identity.user_id)
+                .await?);
+        }
+
+        let email = identity.email.clone().ok_or_else(|| {
+            error!("provider did not share an email for {:?}", identity.subject);
+            Error::BadRequest(String::from("the provider did not share an email address"))
+        })?;
+
+        let user = match self.repository.get_user_by_email(&email).await? {
+            Some(existing_user) => self.link_existing_user(existing_user, &identity).await?,
+            None => self.create_user(&email, &identity).await?,
+        };
+
+        info!(
+            "linking subject {:?} to user {:?}",
+            identity.subject, user.id
+        );
+        self.identities_repository
+            .create_user_identity(user.id, provider, &identity.subject, identity.email)
+            .await?;
+
+        Ok(user)
+    }
+
+    async fn link_existing_user(&self, user: User, identity: &OidcIdentity) -> AppResult<User> {
+        // only a provider vouching for the address may take over an account with that email
+        if !identity.email_verified {
+            error!(
+                "unverified provider email matches existing user {:?}",
+                user.id
+            );
+            return Err(Error::ObjectConflict(format!(
+                "email {} is taken",
+                user.email
+            )));
+        }
+
+        session_services::verify_unverified_user(
+            &self.repository,
+            &self.argon_util,
+            &self.session_service,
+            user,
+        )
+        .await
+    }
+
+    async fn create_user(&self, email: &str, identity: &OidcIdentity) -> AppResult<User> {
+        let name = identity
+            .name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| email.split('@').next().unwrap_or(email).to_string());
+
+        // the account has no usable password until the user sets one through a password reset
+        info!("creating user {:?} from the provider identity", email);
+        let hashed_password = self
+            .argon_util
+            .hash_password(&token_utils::generate_token())
+            .await?;
+        let created_user = self
+            .repository
+            .create_user(email, &name, &hashed_password)
+            .await?;
+
+        if !identity.email_verified {
+            return Ok(created_user);
+        }
+
+        Ok(self.repository.verify_email(created_user.id, email).await?)
+    }
+}
@@ -0,0 +1,385 @@
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    config::AppConfig,
+    database::{
+        passkey::DynPasskeysRepository, user::DynUsersRepository,
+        webauthn_challenge::DynWebauthnChallengesRepository,
+    },
+    server::{
+        dtos::{
+            passkey_dto::{
+                AuthenticatorSelectionDto, PasskeyAuthenticationOptionsDto, PasskeyDto,
+                PasskeyRegistrationOptionsDto, PasskeySignInDto, PasskeyUserDto,
+                PublicKeyCredentialDescriptorDto, PublicKeyCredentialParametersDto,
+                RegisterPasskeyDto, RelyingPartyDto,
+            },
+            user_dto::ResponseUserDto,
+        },
+        error::{AppResult, Error},
+        utils::{
+            permission_utils, token_utils,
+            webauthn_utils::{self, AUTHENTICATION_CEREMONY, REGISTRATION_CEREMONY},
+        },
+    },
+};
+
+use super::session_services::{self, DynSessionsService};
+
+/// A reference counter for our passkey service, registering WebAuthn credentials and signing users in with them.
+pub type DynPasskeysService = Arc<dyn PasskeysServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait PasskeysServiceTrait {
+    /// Starts a registration ceremony for the signed in user.
+    async fn registration_options(&self, user_id: Uuid)
+        -> AppResult<PasskeyRegistrationOptionsDto>;
+
+    async fn register_passkey(
+        &self,
+        user_id: Uuid,
+        request: RegisterPasskeyDto,
+    ) -> AppResult<PasskeyDto>;
+
+    async fn get_passkeys(&self, user_id: Uuid) -> AppResult<Vec<PasskeyDto>>;
+
+    async fn delete_passkey(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    /// Starts an authentication ceremony, the browser picks the passkey so no user is known yet.
+    async fn authentication_options(&self) -> AppResult<PasskeyAuthenticationOptionsDto>;
+
+    /// Completes an authentication ceremony, exchanging a valid assertion for a session.
+    async fn signin_passkey(
+        &self,
+        request: PasskeySignInDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<(ResponseUserDto, String)>;
+}
+
+#[derive(Clone)]
+pub struct PasskeysService {
+    repository: DynPasskeysRepository,
+    challenges_repository: DynWebauthnChallengesRepository,
+    users_repository: DynUsersRepository,
+    session_service: DynSessionsService,
+    config: Arc<AppConfig>,
+}
+
+impl PasskeysService {
+    pub fn new(
+        repository: DynPasskeysRepository,
+        challenges_repository: DynWebauthnChallengesRepository,
+        users_repository: DynUsersRepository,
+        session_service: DynSessionsService,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            challenges_repository,
+            users_repository,
+            session_service,
+            config,
+        }
+    }
+
+    async fn create_challenge(&self, ceremony: &str, user_id: Option<Uuid>) -> AppResult<String> {
+        let challenge = webauthn_utils::generate_challenge();
+
+        let from_now = Duration::from_secs(self.config.webauthn_challenge_ttl_seconds);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        self.challenges_repository
+            .create_webauthn_challenge(
+                &token_utils::hash_token(&challenge),
+                ceremony,
+                user_id,
+                &exp,
+            )
+            .await?;
+
+        Ok(challenge)
+    }
+
+    fn timeout_millis(&self) -> u64 {
+        self.config.webauthn_challenge_ttl_seconds * 1000
+    }
+}
+
+#[async_trait]
+impl PasskeysServiceTrait for PasskeysService {
+    async fn registration_options(
+        &self,
+        user_id: Uuid,
+    ) -> AppResult<PasskeyRegistrationOptionsDto> {
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        // keeps authenticators from creating a second passkey for the same account
+        let exclude_credentials = self
+            .repository
+            .get_passkeys_by_user_id(user_id)
+            .await?
+            .into_iter()
+            .map(|passkey| PublicKeyCredentialDescriptorDto {
+                credential_type: String::from("public-key"),
+                id: passkey.credential_id,
+            })
+            .collect();
+
+        let challenge = self
+            .create_challenge(REGISTRATION_CEREMONY, Some(user_id))
+            .await?;
+
+        info!("starting passkey registration for user {:?}", user_id);
+
+        Ok(PasskeyRegistrationOptionsDto {
+            challenge,
+            rp: RelyingPartyDto {
+                id: self.config.webauthn_rp_id.clone(),
+                name: self.config.webauthn_rp_name.clone(),
+            },
+            user: PasskeyUserDto {
+                id: webauthn_utils::user_handle(&user.id),
+                name: user.email,
+                display_name: user.name,
+            },
+            pub_key_cred_params: webauthn_utils::SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|alg| PublicKeyCredentialParametersDto {
+                    credential_type: String::from("public-key"),
+                    alg: *alg,
+                })
+                .collect(),
+            timeout: self.timeout_millis(),
+            attestation: String::from("none"),
+            authenticator_selection: AuthenticatorSelectionDto {
+                resident_key: String::from("required"),
+                user_verification: String::from("required"),
+            },
+            exclude_credentials,
+        })
+    }
+
+    async fn register_passkey(
+        &self,
+        user_id: Uuid,
+        request: RegisterPasskeyDto,
+    ) -> AppResult<PasskeyDto> {
+        let name = request.name.unwrap();
+        let credential = request.credential.unwrap();
+
+        let challenge = webauthn_utils::decode(&credential.response.client_data_json)
+            .and_then(|client_data_json| {
+                webauthn_utils::verify_client_data(
+                    &client_data_json,
+                    "webauthn.create",
+                    &self.config.cors_origin,
+                )
+            })
+            .map_err(|err| {
+                error!("invalid passkey registration client data: {:?}", err);
+                Error::BadRequest(String::from("passkey registration could not be verified"))
+            })?;
+
+        let registration_challenge = self
+            .challenges_repository
+            .consume_webauthn_challenge(&token_utils::hash_token(&challenge), REGISTRATION_CEREMONY)
+            .await?;
+
+        if registration_challenge.map(|challenge| challenge.user_id) != Some(Some(user_id)) {
+            error!("invalid or expired passkey registration challenge");
+            return Err(Error::BadRequest(String::from(
+                "passkey registration has expired, please try again",
+            )));
+        }
+
+        let registered_credential = webauthn_utils::decode(&credential.raw_id)
+            .and_then(|raw_id| {
+                let attestation_object =
+                    webauthn_utils::decode(&credential.response.attestation_object)?;
+
+                webauthn_utils::verify_registration(
+                    &attestation_object,
+                    &raw_id,
+                    &self.config.webauthn_rp_id,
+                )
+            })
+            .map_err(|err| {
+                error!("invalid passkey registration: {:?}", err);
+                Error::BadRequest(String::from("passkey registration could not be verified"))
+            })?;
+
+        if self
+            .repository
+            .get_passkey_by_credential_id(&registered_credential.credential_id)
+            .await?
+            .is_some()
+        {
+            return Err(Error::ObjectConflict(String::from(
+                "passkey is already registered",
+            )));
+        }
+
+        info!("registering passkey {:?} for user {:?}", name, user_id);
+        let passkey = self
+            .repository
+            .create_passkey(
+                user_id,
+                &name,
+                &registered_credential.credential_id,
+                &registered_credential.public_key,
+                registered_credential.sign_count as i64,
+            )
+            .await?;
+
+        Ok(passkey.into_dto())
+    }
+
+    async fn get_passkeys(&self, user_id: Uuid) -> AppResult<Vec<PasskeyDto>> {
+        info!("retrieving passkeys for user {:?}", user_id);
+        let passkeys = self.repository.get_passkeys_by_user_id(user_id).await?;
+
+        Ok(passkeys
+            .into_iter()
+            .map(|passkey| passkey.into_dto())
+            .collect())
+    }
+
+    async fn delete_passkey(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let passkey = self.repository.get_passkey_by_id(id).await?;
+
+        if let Some(existing_passkey) = passkey {
+            permission_utils::ensure_owner(existing_passkey.user_id, user_id)?;
+
+            self.repository.delete_passkey(existing_passkey.id).await?;
+
+            info!("passkey {:?} deleted", id);
+
+            return Ok(());
+        }
+
+        Err(Error::NotFound(String::from("passkey was not found")))
+    }
+
+    async fn authentication_options(&self) -> AppResult<PasskeyAuthenticationOptionsDto> {
+        let challenge = self.create_challenge(AUTHENTICATION_CEREMONY, None).await?;
+
+        Ok(PasskeyAuthenticationOptionsDto {
+            challenge,
+            rp_id: self.config.webauthn_rp_id.clone(),
+            timeout: self.timeout_millis(),
+            user_verification: String::from("required"),
+            allow_credentials: Vec::new(),
+        })
+    }
+
+    async fn signin_passkey(
+        &self,
+        request: PasskeySignInDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<(ResponseUserDto, String)> {
+        let response = request.response;
+
+        let decode = |value: &str| {
+            webauthn_utils::decode(value).map_err(|err| {
+                error!("malformed passkey assertion: {:?}", err);
+                Error::Unauthorized
+            })
+        };
+
+        let raw_id = decode(&request.raw_id)?;
+        let client_data_json = decode(&response.client_data_json)?;
+        let authenticator_data = decode(&response.authenticator_data)?;
+        let signature = decode(&response.signature)?;
+
+        let passkey = self
+            .repository
+            .get_passkey_by_credential_id(&webauthn_utils::encode(&raw_id))
+            .await?
+            .ok_or_else(|| {
+                error!("no passkey found for the presented credential");
+                Error::Unauthorized
+            })?;
+
+        let challenge = webauthn_utils::verify_client_data(
+            &client_data_json,
+            "webauthn.get",
+            &self.config.cors_origin,
+        )
+        .map_err(|err| {
+            error!("invalid passkey assertion client data: {:?}", err);
+            Error::Unauthorized
+        })?;
+
+        self.challenges_repository
+            .consume_webauthn_challenge(
+                &token_utils::hash_token(&challenge),
+                AUTHENTICATION_CEREMONY,
+            )
+            .await?
+            .ok_or_else(|| {
+                error!("invalid or expired passkey authentication challenge");
+                Error::Unauthorized
+            })?;
+
+        if let Some(user_handle) = response.user_handle {
+            if decode(&user_handle)? != passkey.user_id.as_bytes() {
+                error!("passkey {:?} was presented for another user", passkey.id);
+                return Err(Error::Unauthorized);
+            }
+        }
+
+        let sign_count = webauthn_utils::verify_assertion(
+            &passkey.public_key,
+            &authenticator_data,
+            &client_data_json,
+            &signature,
+            &self.config.webauthn_rp_id,
+        )
+        .map_err(|err| {
+            error!("invalid assertion for passkey {:?}: {:?}", passkey.id, err);
+            Error::Unauthorized
+        })?;
+
+        if !self
+            .repository
+            .use_passkey(passkey.id, sign_count as i64)
+            .await?
+        {
+            error!(
+                "signature counter of passkey {:?} went backwards, the authenticator may have been cloned",
+                passkey.id
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        let user = self
+            .users_repository
+            .get_user_by_id(passkey.user_id)
+            .await?;
+
+        if self.config.require_email_verification && user.verified_at.is_none() {
+            error!("user {:?} has not verified their email", user.id);
+            return Err(Error::EmailNotVerified);
+        }
+
+        info!("user {:?} signed in with passkey {:?}", user.id, passkey.id);
+        session_services::start_session(
+            &self.users_repository,
+            &self.session_service,
+            user,
+            user_agent,
+            ip_address,
+        )
+        .await
+    }
+}
@@ -0,0 +1,402 @@
+use async_trait::async_trait;
+use axum::body::{Body, Bytes};
+use hyper::body::Sender;
+use mockall::automock;
+use serde::Serialize;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use time::format_description::well_known::Rfc3339;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    config::AppConfig,
+    database::{
+        account::DynAccountsRepository,
+        api_key::DynApiKeysRepository,
+        budget::DynBudgetsRepository,
+        category::DynCategoriesRepository,
+        passkey::DynPasskeysRepository,
+        recurring_transaction::DynRecurringTransactionsRepository,
+        transaction::{DynTransactionsRepository, TransactionFilter},
+        user::{DynUsersRepository, User},
+        user_identity::DynUserIdentitiesRepository,
+    },
+    server::{
+        dtos::personal_data_dto::{AccountDeletionDto, DeleteAccountDto},
+        error::{AppResult, Error},
+        utils::argon_utils::DynArgonUtil,
+    },
+};
+
+use super::login_throttle_services::DynLoginThrottlesService;
+use super::session_services::DynSessionsService;
+
+/// A reference counter for our personal data service, exporting and deleting everything we store about a user.
+pub type DynPersonalDataService = Arc<dyn PersonalDataServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait PersonalDataServiceTrait {
+    /// Streams the archive shaped like `UserExportDto`, each section is written as soon as it is fetched so only one of
+    /// them is held in memory at a time. Failing halfway through aborts the body rather than ending it early.
+    async fn export_user_data(&self, user_id: Uuid) -> AppResult<Body>;
+
+    /// Signs the user out everywhere, revoking their sessions and API keys, and schedules the account for deletion
+    /// once the grace period passes. Signing in again before then cancels the deletion, API keys stay revoked.
+    ///
+    /// The deletion has to be confirmed with the password, wrong ones are throttled like failed sign ins so a stolen
+    /// access token cannot be used to guess it. Users who only ever signed in through the OIDC provider have a random
+    /// password they were never told, they need to set one through the forgot password flow first.
+    async fn delete_account(
+        &self,
+        user_id: Uuid,
+        request: DeleteAccountDto,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<AccountDeletionDto>;
+
+    /// Permanently deletes the accounts whose grace period has passed, their data goes with them through cascading deletes.
+    async fn purge_deleted_accounts(&self) -> AppResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct PersonalDataService {
+    users_repository: DynUsersRepository,
+    categories_repository: DynCategoriesRepository,
+    api_keys_repository: DynApiKeysRepository,
+    identities_repository: DynUserIdentitiesRepository,
+    passkeys_repository: DynPasskeysRepository,
+    transactions_repository: DynTransactionsRepository,
+    budgets_repository: DynBudgetsRepository,
+    accounts_repository: DynAccountsRepository,
+    recurring_transactions_repository: DynRecurringTransactionsRepository,
+    argon_util: DynArgonUtil,
+    sessions_service: DynSessionsService,
+    login_throttles_service: DynLoginThrottlesService,
+    config: Arc<AppConfig>,
+}
+
+impl PersonalDataService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        users_repository: DynUsersRepository,
+        categories_repository: DynCategoriesRepository,
+        api_keys_repository: DynApiKeysRepository,
+        identities_repository: DynUserIdentitiesRepository,
+        passkeys_repository: DynPasskeysRepository,
+        transactions_repository: DynTransactionsRepository,
+        budgets_repository: DynBudgetsRepository,
+        accounts_repository: DynAccountsRepository,
+        recurring_transactions_repository: DynRecurringTransactionsRepository,
+        argon_util: DynArgonUtil,
+        sessions_service: DynSessionsService,
+        login_throttles_service: DynLoginThrottlesService,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            users_repository,
+            categories_repository,
+            api_keys_repository,
+            identities_repository,
+            passkeys_repository,
+            transactions_repository,
+            budgets_repository,
+            accounts_repository,
+            recurring_transactions_repository,
+            argon_util,
+            sessions_service,
+            login_throttles_service,
+            config,
+        }
+    }
+}
+
+impl PersonalDataService {
+    async fn write_export(&self, user: User, writer: &mut ExportWriter) -> AppResult<()> {
+        let user_id = user.id;
+        let exported_at = OffsetDateTime::from(SystemTime::now())
+            .format(&Rfc3339)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        writer.write_field("exported_at", &exported_at).await?;
+        writer
+            .write_field("profile", &user.into_profile_export_dto())
+            .await?;
+
+        let categories = self.categories_repository.get_categories(user_id).await?;
+        writer
+            .write_section(
+                "categories",
+                categories.into_iter().map(|category| category.into_dto()),
+            )
+            .await?;
+
+        let sessions = self.sessions_service.get_sessions(user_id).await?;
+        writer.write_section("sessions", sessions).await?;
+
+        let api_keys = self
+            .api_keys_repository
+            .get_api_keys_by_user_id(user_id)
+            .await?;
+        writer
+            .write_section(
+                "api_keys",
+                api_keys.into_iter().map(|api_key| api_key.into_dto()),
+            )
+            .await?;
+
+        let identities = self
+            .identities_repository
+            .get_user_identities_by_user_id(user_id)
+            .await?;
+        writer
+            .write_section(
+                "identities",
+                identities
+                    .into_iter()
+                    .map(|identity| identity.into_export_dto()),
+            )
+            .await?;
+
+        let passkeys = self
+            .passkeys_repository
+            .get_passkeys_by_user_id(user_id)
+            .await?;
+        writer
+            .write_section(
+                "passkeys",
+                passkeys.into_iter().map(|passkey| passkey.into_dto()),
+            )
+            .await?;
+
+        let transactions = self
+            .transactions_repository
+            .get_transactions(user_id, TransactionFilter::default())
+            .await?;
+        writer
+            .write_section(
+                "transactions",
+                transactions
+                    .into_iter()
+                    .map(|transaction| transaction.into_dto()),
+            )
+            .await?;
+
+        let budgets = self.budgets_repository.get_all_budgets(user_id).await?;
+        writer
+            .write_section(
+                "budgets",
+                budgets.into_iter().map(|budget| budget.into_dto()),
+            )
+            .await?;
+
+        let accounts = self.accounts_repository.get_accounts(user_id).await?;
+        writer
+            .write_section(
+                "accounts",
+                accounts.into_iter().map(|account| account.into_dto()),
+            )
+            .await?;
+
+        let recurring_transactions = self
+            .recurring_transactions_repository
+            .get_recurring_transactions(user_id)
+            .await?;
+        writer
+            .write_section(
+                "recurring_transactions",
+                recurring_transactions
+                    .into_iter()
+                    .map(|recurring_transaction| recurring_transaction.into_dto()),
+            )
+            .await?;
+
+        writer.finish().await
+    }
+}
+
+/// Chunks are flushed once they grow past this many bytes, large sections are sent in several of them.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Writes the archive as a single JSON object, one field at a time, into the body it is streamed through.
+struct ExportWriter {
+    sender: Option<Sender>,
+    started: bool,
+}
+
+impl ExportWriter {
+    fn new(sender: Sender) -> Self {
+        Self {
+            sender: Some(sender),
+            started: false,
+        }
+    }
+
+    async fn write_field<T: Serialize>(&mut self, name: &str, value: &T) -> AppResult<()> {
+        let mut chunk = self.field_name(name)?;
+        serde_json::to_writer(&mut chunk, value)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        self.send(chunk).await
+    }
+
+    /// Serializes the items one by one, so a section is never held in memory twice.
+    async fn write_section<T: Serialize>(
+        &mut self,
+        name: &str,
+        items: impl IntoIterator<Item = T>,
+    ) -> AppResult<()> {
+        let mut chunk = self.field_name(name)?;
+        chunk.push(b'[');
+
+        for (index, item) in items.into_iter().enumerate() {
+            if index > 0 {
+                chunk.push(b',');
+            }
+
+            serde_json::to_writer(&mut chunk, &item)
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+            if chunk.len() >= EXPORT_CHUNK_SIZE {
+                self.send(std::mem::take(&mut chunk)).await?;
+            }
+        }
+
+        chunk.push(b']');
+        self.send(chunk).await
+    }
+
+    async fn finish(&mut self) -> AppResult<()> {
+        self.send(vec![b'}']).await?;
+        self.sender.take();
+
+        Ok(())
+    }
+
+    /// Fails the response instead of leaving the client with a truncated archive that looks complete.
+    fn abort(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            sender.abort();
+        }
+    }
+
+    fn field_name(&mut self, name: &str) -> AppResult<Vec<u8>> {
+        let mut chunk = vec![if self.started { b',' } else { b'{' }];
+        self.started = true;
+
+        serde_json::to_writer(&mut chunk, name)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        chunk.push(b':');
+
+        Ok(chunk)
+    }
+
+    async fn send(&mut self, chunk: Vec<u8>) -> AppResult<()> {
+        let sender = self.sender.as_mut().ok_or_else(|| {
+            Error::InternalServerErrorWithContext(String::from("the export was already closed"))
+        })?;
+
+        sender
+            .send_data(Bytes::from(chunk))
+            .await
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersonalDataServiceTrait for PersonalDataService {
+    async fn export_user_data(&self, user_id: Uuid) -> AppResult<Body> {
+        info!("exporting personal data of user {:?}", user_id);
+
+        // a missing user is still reported with a status code, anything failing later can only abort the body
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        let (sender, body) = Body::channel();
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut writer = ExportWriter::new(sender);
+
+            if let Err(err) = service.write_export(user, &mut writer).await {
+                error!(
+                    "could not export personal data of user {:?}: {:?}",
+                    user_id, err
+                );
+                writer.abort();
+            }
+        });
+
+        Ok(body)
+    }
+
+    async fn delete_account(
+        &self,
+        user_id: Uuid,
+        request: DeleteAccountDto,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<AccountDeletionDto> {
+        let user = self.users_repository.get_user_by_id(user_id).await?;
+
+        self.login_throttles_service
+            .ensure_allowed(&user.email, ip_address)
+            .await?;
+
+        let is_valid_password = self
+            .argon_util
+            .verify_password(&user.password, request.password.unwrap())
+            .await?;
+
+        if !is_valid_password {
+            error!(
+                "invalid password confirming the account deletion of user {:?}",
+                user_id
+            );
+            self.login_throttles_service
+                .record_failure(&user.email, ip_address)
+                .await?;
+
+            return Err(Error::InvalidLoginAttmpt);
+        }
+
+        self.login_throttles_service
+            .clear_account(&user.email)
+            .await?;
+
+        let from_now = Duration::from_secs(self.config.account_deletion_grace_days * 86400);
+        let delete_after = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        self.users_repository
+            .schedule_user_deletion(user_id, &delete_after)
+            .await?;
+
+        self.sessions_service.revoke_user_sessions(user_id).await?;
+
+        let deleted_api_keys = self
+            .api_keys_repository
+            .delete_user_api_keys(user_id)
+            .await?;
+        info!(
+            "revoked {} API keys of user {:?}",
+            deleted_api_keys, user_id
+        );
+
+        info!(
+            "user {:?} scheduled for deletion after {:?}",
+            user_id, delete_after
+        );
+
+        Ok(AccountDeletionDto { delete_after })
+    }
+
+    async fn purge_deleted_accounts(&self) -> AppResult<u64> {
+        let deleted_users = self.users_repository.delete_scheduled_users().await?;
+
+        if deleted_users > 0 {
+            info!("permanently deleted {} scheduled accounts", deleted_users);
+        }
+
+        Ok(deleted_users)
+    }
+}
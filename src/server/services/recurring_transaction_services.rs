@@ -0,0 +1,384 @@
+use mockall::automock;
+use sqlx::types::time::{Date, OffsetDateTime};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::{
+        account::DynAccountsRepository,
+        category::DynCategoriesRepository,
+        recurring_transaction::{DynRecurringTransactionsRepository, RecurringTransaction},
+        transaction::TransactionFields,
+    },
+    server::{
+        dtos::recurring_transaction_dto::{
+            OccurrenceDto, RecurringTransactionCreateDto, RecurringTransactionResponseDto,
+            RecurringTransactionUpdateDto,
+        },
+        error::{AppResult, Error},
+        utils::{permission_utils, recurrence_utils},
+    },
+};
+
+use super::transaction_services::{updated_note, validate_fields};
+
+/// Occurrences previewed when no count is given.
+pub const DEFAULT_PREVIEW_COUNT: u32 = 10;
+
+/// The most occurrences previewed at once.
+pub const MAX_PREVIEW_COUNT: u32 = 100;
+
+/// The most occurrences of a single recurring transaction recorded in one go, catching up on a schedule that started
+/// long ago is spread over several runs rather than one large database transaction.
+pub const MAX_OCCURRENCES_PER_RUN: usize = 500;
+
+/// A reference counter for our recurring transaction service, recording transactions that repeat on a schedule.
+pub type DynRecurringTransactionsService = Arc<dyn RecurringTransactionsServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait RecurringTransactionsServiceTrait {
+    /// Creates a recurring transaction, recording the occurrences already due right away.
+    async fn create_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        request: RecurringTransactionCreateDto,
+    ) -> AppResult<RecurringTransactionResponseDto>;
+
+    async fn get_recurring_transaction_by_id(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<RecurringTransactionResponseDto>;
+
+    async fn get_recurring_transactions(
+        &self,
+        user_id: Uuid,
+    ) -> AppResult<Vec<RecurringTransactionResponseDto>>;
+
+    /// Updates the template of future occurrences, transactions already recorded are left untouched.
+    async fn updated_recurring_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: RecurringTransactionUpdateDto,
+    ) -> AppResult<RecurringTransactionResponseDto>;
+
+    /// Stops a recurring transaction, transactions already recorded are kept.
+    async fn delete_recurring_transaction(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    /// Lists the next occurrences still to be recorded.
+    async fn preview_occurrences(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        count: Option<u32>,
+    ) -> AppResult<Vec<OccurrenceDto>>;
+
+    /// Records every occurrence due on or before the date, returning the number of transactions created. Occurrences
+    /// are recorded at most once, so running it again or from several instances is safe.
+    async fn materialize_due_transactions(&self, today: Date) -> AppResult<u64>;
+}
+
+#[derive(Clone)]
+pub struct RecurringTransactionsService {
+    repository: DynRecurringTransactionsRepository,
+    categories_repository: DynCategoriesRepository,
+    accounts_repository: DynAccountsRepository,
+}
+
+impl RecurringTransactionsService {
+    pub fn new(
+        repository: DynRecurringTransactionsRepository,
+        categories_repository: DynCategoriesRepository,
+        accounts_repository: DynAccountsRepository,
+    ) -> Self {
+        Self {
+            repository,
+            categories_repository,
+            accounts_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl RecurringTransactionsServiceTrait for RecurringTransactionsService {
+    async fn create_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        request: RecurringTransactionCreateDto,
+    ) -> AppResult<RecurringTransactionResponseDto> {
+        let rule = recurrence_utils::parse_rrule(&request.rrule.unwrap())?;
+        let starts_on = request.starts_on.unwrap();
+
+        if matches!(rule.until, Some(until) if until < starts_on) {
+            return Err(Error::BadRequest(String::from(
+                "the recurrence rule cannot end before the start date",
+            )));
+        }
+
+        let category_id = request.category_id.unwrap();
+        permission_utils::find_owned_category(&self.categories_repository, category_id, user_id)
+            .await?;
+
+        let account = permission_utils::find_owned_account(
+            &self.accounts_repository,
+            request.account_id.unwrap(),
+            user_id,
+        )
+        .await?;
+
+        let fields = TransactionFields {
+            amount: request.amount.unwrap(),
+            currency: request.currency.unwrap_or_else(|| account.currency.clone()),
+            tx_type: request.tx_type,
+            date: starts_on,
+            payee: request.payee.unwrap(),
+            note: request.note,
+            category_id: Some(category_id),
+            account_id: Some(account.id),
+        };
+
+        let fields = validate_fields(fields, Some(&account))?;
+
+        let created_recurring_transaction = self
+            .repository
+            .create_recurring_transaction(user_id, fields, rule)
+            .await?;
+
+        info!("user created recurring transaction successfully");
+
+        let created = self
+            .materialize(
+                &created_recurring_transaction,
+                OffsetDateTime::now_utc().date(),
+            )
+            .await?;
+
+        if created == 0 {
+            return Ok(created_recurring_transaction.into_dto());
+        }
+
+        let existing_recurring_transaction = self
+            .find_owned_recurring_transaction(created_recurring_transaction.id, user_id)
+            .await?;
+
+        Ok(existing_recurring_transaction.into_dto())
+    }
+
+    async fn get_recurring_transaction_by_id(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<RecurringTransactionResponseDto> {
+        let existing_recurring_transaction =
+            self.find_owned_recurring_transaction(id, user_id).await?;
+
+        Ok(existing_recurring_transaction.into_dto())
+    }
+
+    async fn get_recurring_transactions(
+        &self,
+        user_id: Uuid,
+    ) -> AppResult<Vec<RecurringTransactionResponseDto>> {
+        let recurring_transactions = self.repository.get_recurring_transactions(user_id).await?;
+
+        info!(
+            "found {} recurring transactions",
+            recurring_transactions.len()
+        );
+
+        Ok(recurring_transactions
+            .into_iter()
+            .map(|recurring_transaction| recurring_transaction.into_dto())
+            .collect())
+    }
+
+    async fn updated_recurring_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: RecurringTransactionUpdateDto,
+    ) -> AppResult<RecurringTransactionResponseDto> {
+        let existing_recurring_transaction =
+            self.find_owned_recurring_transaction(id, user_id).await?;
+
+        if let Some(category_id) = request.category_id {
+            permission_utils::find_owned_category(
+                &self.categories_repository,
+                category_id,
+                user_id,
+            )
+            .await?;
+        }
+
+        let account = permission_utils::find_owned_account(
+            &self.accounts_repository,
+            request
+                .account_id
+                .unwrap_or(existing_recurring_transaction.account_id),
+            user_id,
+        )
+        .await?;
+
+        let fields = TransactionFields {
+            amount: request
+                .amount
+                .unwrap_or(existing_recurring_transaction.amount),
+            currency: request
+                .currency
+                .unwrap_or(existing_recurring_transaction.currency),
+            tx_type: request
+                .tx_type
+                .unwrap_or(existing_recurring_transaction.tx_type),
+            date: existing_recurring_transaction.starts_on,
+            payee: request
+                .payee
+                .unwrap_or(existing_recurring_transaction.payee),
+            note: updated_note(request.note, existing_recurring_transaction.note),
+            category_id: request
+                .category_id
+                .or(existing_recurring_transaction.category_id),
+            account_id: Some(account.id),
+        };
+
+        let fields = validate_fields(fields, Some(&account))?;
+
+        let updated_recurring_transaction = self
+            .repository
+            .update_recurring_transaction(id, fields)
+            .await?;
+
+        Ok(updated_recurring_transaction.into_dto())
+    }
+
+    async fn delete_recurring_transaction(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let existing_recurring_transaction =
+            self.find_owned_recurring_transaction(id, user_id).await?;
+
+        self.repository
+            .delete_recurring_transaction(existing_recurring_transaction.id)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn preview_occurrences(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        count: Option<u32>,
+    ) -> AppResult<Vec<OccurrenceDto>> {
+        let count = count.unwrap_or(DEFAULT_PREVIEW_COUNT);
+
+        if count == 0 || count > MAX_PREVIEW_COUNT {
+            error!("cannot preview {} occurrences", count);
+            return Err(Error::BadRequest(format!(
+                "count must be between 1 and {}",
+                MAX_PREVIEW_COUNT
+            )));
+        }
+
+        let existing_recurring_transaction =
+            self.find_owned_recurring_transaction(id, user_id).await?;
+
+        Ok(recurrence_utils::occurrences(
+            &existing_recurring_transaction.rule(),
+            existing_recurring_transaction.starts_on,
+        )
+        .skip(existing_recurring_transaction.materialized_count as usize)
+        .take(count as usize)
+        .map(|date| OccurrenceDto { date })
+        .collect())
+    }
+
+    async fn materialize_due_transactions(&self, today: Date) -> AppResult<u64> {
+        let due = self
+            .repository
+            .get_due_recurring_transactions(today)
+            .await?;
+
+        info!("found {} due recurring transactions", due.len());
+
+        let mut created = 0;
+
+        // one failing schedule should not hold back the others, it is retried on the next run
+        for recurring_transaction in due.iter() {
+            match self.materialize(recurring_transaction, today).await {
+                Ok(count) => created += count,
+                Err(err) => error!(
+                    "could not record the occurrences of recurring transaction {:?}: {:?}",
+                    recurring_transaction.id, err
+                ),
+            }
+        }
+
+        Ok(created)
+    }
+}
+
+impl RecurringTransactionsService {
+    /// Records the occurrences of a recurring transaction due on or before the date, picking up from the last one
+    /// recorded so occurrences missed while the server was down are caught up on.
+    async fn materialize(
+        &self,
+        recurring_transaction: &RecurringTransaction,
+        today: Date,
+    ) -> AppResult<u64> {
+        let mut occurrences = recurrence_utils::occurrences(
+            &recurring_transaction.rule(),
+            recurring_transaction.starts_on,
+        )
+        .skip(recurring_transaction.materialized_count as usize)
+        .peekable();
+
+        let mut dates = Vec::new();
+
+        while dates.len() < MAX_OCCURRENCES_PER_RUN {
+            match occurrences.next_if(|date| *date <= today) {
+                Some(date) => dates.push(date),
+                None => break,
+            }
+        }
+
+        if dates.is_empty() {
+            return Ok(0);
+        }
+
+        let next_date = occurrences.peek().copied();
+
+        info!(
+            "recording {} occurrences of recurring transaction {:?}",
+            dates.len(),
+            recurring_transaction.id
+        );
+        let created = self
+            .repository
+            .create_occurrences(recurring_transaction, dates, next_date)
+            .await?;
+
+        Ok(created)
+    }
+
+    async fn find_owned_recurring_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<RecurringTransaction> {
+        info!("searching for existing recurring transaction {:?}", id);
+        let recurring_transaction = self.repository.get_recurring_transaction_by_id(id).await?;
+
+        if let Some(existing_recurring_transaction) = recurring_transaction {
+            permission_utils::ensure_owner(existing_recurring_transaction.user_id, user_id)?;
+
+            return Ok(existing_recurring_transaction);
+        }
+
+        Err(Error::NotFound(String::from(
+            "recurring transaction was not found",
+        )))
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use mockall::automock;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::database::role::{DynRolesRepository, Role};
+use crate::database::user::DynUsersRepository;
+use crate::server::dtos::role_dto::RoleDto;
+use crate::server::error::{AppResult, Error};
+use crate::server::services::session_services::DynSessionsService;
+use crate::server::utils::permission_utils::ROLE_ADMIN;
+
+/// A reference counter for our role service, managing the roles held by users.
+pub type DynRolesService = Arc<dyn RolesServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait RolesServiceTrait {
+    async fn get_user_roles(&self, user_id: Uuid) -> AppResult<Vec<RoleDto>>;
+
+    /// Grants a role to a user, their outstanding access tokens are revoked so the next refresh picks it up.
+    async fn assign_role(&self, user_id: Uuid, role: String) -> AppResult<Vec<RoleDto>>;
+
+    /// Takes a role away from a user along with their outstanding access tokens, admins cannot take away
+    /// their own admin role.
+    async fn revoke_role(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        role: String,
+    ) -> AppResult<Vec<RoleDto>>;
+}
+
+#[derive(Clone)]
+pub struct RolesService {
+    repository: DynRolesRepository,
+    users_repository: DynUsersRepository,
+    sessions_service: DynSessionsService,
+}
+
+impl RolesService {
+    pub fn new(
+        repository: DynRolesRepository,
+        users_repository: DynUsersRepository,
+        sessions_service: DynSessionsService,
+    ) -> Self {
+        Self {
+            repository,
+            users_repository,
+            sessions_service,
+        }
+    }
+}
+
+#[async_trait]
+impl RolesServiceTrait for RolesService {
+    async fn get_user_roles(&self, user_id: Uuid) -> AppResult<Vec<RoleDto>> {
+        info!("retrieving roles for user {:?}", user_id);
+        self.users_repository.get_user_by_id(user_id).await?;
+
+        let roles = self.repository.get_roles_by_user_id(user_id).await?;
+
+        Ok(roles.into_iter().map(|role| role.into_dto()).collect())
+    }
+
+    async fn assign_role(&self, user_id: Uuid, role: String) -> AppResult<Vec<RoleDto>> {
+        self.users_repository.get_user_by_id(user_id).await?;
+        let existing_role = self.find_role(&role).await?;
+
+        info!("assigning role {:?} to user {:?}", role, user_id);
+        self.repository
+            .assign_role(user_id, existing_role.id)
+            .await?;
+
+        self.sessions_service
+            .revoke_user_access_tokens(user_id)
+            .await?;
+
+        self.get_user_roles(user_id).await
+    }
+
+    async fn revoke_role(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        role: String,
+    ) -> AppResult<Vec<RoleDto>> {
+        if admin_id == user_id && role == ROLE_ADMIN {
+            error!(
+                "admin {:?} attempted to revoke their own admin role",
+                admin_id
+            );
+            return Err(Error::BadRequest(String::from(
+                "admins cannot revoke their own admin role",
+            )));
+        }
+
+        self.users_repository.get_user_by_id(user_id).await?;
+        let existing_role = self.find_role(&role).await?;
+
+        info!("revoking role {:?} from user {:?}", role, user_id);
+        self.repository
+            .revoke_role(user_id, existing_role.id)
+            .await?;
+
+        self.sessions_service
+            .revoke_user_access_tokens(user_id)
+            .await?;
+
+        self.get_user_roles(user_id).await
+    }
+}
+
+impl RolesService {
+    async fn find_role(&self, name: &str) -> AppResult<Role> {
+        self.repository
+            .get_role_by_name(name)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("role {} was not found", name)))
+    }
+}
@@ -8,11 +8,15 @@ use crate::{
             category_dto::CategoryCreateDto,
             user_dto::{ResponseUserDto, SignInUserDto, SignUpUserDto},
         },
-        error::AppResult,
+        error::{AppResult, Error},
+        utils::permission_utils::ROLE_ADMIN,
     },
 };
 
-use super::{category_services::DynCategoriesService, user_services::DynUsersService, Services};
+use super::{
+    category_services::DynCategoriesService, role_services::DynRolesService,
+    user_services::DynUsersService, Services,
+};
 
 lazy_static! {
     static ref TEST_USER_1_NAME: &'static str = "testuser1";
@@ -28,6 +32,7 @@ lazy_static! {
 
 pub struct SeedService {
     user_services: DynUsersService,
+    role_services: DynRolesService,
     category_services: DynCategoriesService,
 }
 
@@ -35,13 +40,14 @@ impl SeedService {
     pub fn new(services: Services) -> Self {
         Self {
             user_services: services.users,
+            role_services: services.roles,
             category_services: services.categories,
         }
     }
 
     pub async fn seed(&self) -> AppResult<()> {
         // assume that if we have an active user in the users table, data has been seeded
-        let seed_data_exists = self
+        let seed_signin = self
             .user_services
             .signin_user(
                 SignInUserDto {
@@ -49,9 +55,10 @@ impl SeedService {
                     password: Some(String::from(*TEST_USER_1_PASSWORD)),
                 },
                 Some(String::from("Seed Agent")),
+                None,
             )
-            .await
-            .is_ok();
+            .await;
+        let seed_data_exists = matches!(seed_signin, Ok(_) | Err(Error::EmailNotVerified));
 
         if seed_data_exists {
             info!("data has already been seeded, bypassing test data setup");
@@ -70,9 +77,17 @@ impl SeedService {
             .create_user(*TEST_USER_3_NAME, *TEST_USER_3_EMAIL, *TEST_USER_3_PASSWORD)
             .await?;
 
-        info!("users created, seeding categories...");
+        info!(
+            "users created, granting {:?} the admin role...",
+            *TEST_USER_1_EMAIL
+        );
+        self.role_services
+            .assign_role(created_user_1.id, String::from(ROLE_ADMIN))
+            .await?;
+
+        info!("admin role granted, seeding categories...");
 
-        let created_users = vec![created_user_1, created_user_2, created_user_3];
+        let created_users = [created_user_1, created_user_2, created_user_3];
         for user in created_users.iter() {
             for index in 1..5 {
                 self.category_services
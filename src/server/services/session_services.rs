@@ -1,16 +1,23 @@
 use async_trait::async_trait;
 use mockall::automock;
 use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::database::session::DynSessionsRepository;
-use crate::server::dtos::session_dto::{NewSessionDto, SessionResponseDto};
+use crate::config::AppConfig;
+use crate::database::revoked_access_token::DynRevokedAccessTokensRepository;
+use crate::database::role::{AccessGrants, DynRolesRepository};
+use crate::database::session::{DynSessionsRepository, SessionMetadata};
+use crate::database::user::{DynUsersRepository, User};
+use crate::server::dtos::session_dto::{NewSessionDto, SessionDto, SessionResponseDto};
 use crate::server::dtos::user_dto::ResponseUserDto;
-use crate::server::error::AppResult;
-use crate::server::utils::jwt_utils::DynJwtUtil;
+use crate::server::error::{AppResult, Error};
+use crate::server::utils::argon_utils::DynArgonUtil;
+use crate::server::utils::jwt_utils::{AccessToken, DynJwtUtil, MfaToken};
+use crate::server::utils::{permission_utils, token_utils, user_agent_utils};
 
 /// A reference counter for our user service allows us safely pass instances user utils
 /// around which themselves depend on the user repostiory, and ultimately, our Posgres connection pool.
@@ -21,36 +28,130 @@ pub type DynSessionsService = Arc<dyn SessionsServiceTrait + Send + Sync>;
 pub trait SessionsServiceTrait {
     async fn new_session(&self, request: NewSessionDto) -> AppResult<SessionResponseDto>;
 
-    async fn refresh_access_token(&self, id: Uuid) -> AppResult<ResponseUserDto>;
+    /// Issues an access token carrying the current roles and permissions of the user.
+    async fn new_access_token(&self, user_id: Uuid, email: &str) -> AppResult<String>;
+
+    async fn refresh_access_token(
+        &self,
+        id: Uuid,
+        refresh_token_id: Uuid,
+    ) -> AppResult<(ResponseUserDto, String)>;
+
+    async fn get_sessions(&self, user_id: Uuid) -> AppResult<Vec<SessionDto>>;
+
+    async fn revoke_session(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    async fn revoke_other_sessions(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+
+    async fn signout_session(&self, id: Uuid) -> AppResult<()>;
+
+    /// Signs the user out everywhere, revoking their sessions along with every access token issued to them.
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> AppResult<()>;
+
+    /// Rejects access tokens that were revoked, on their own or along with every other token of the user. Impersonation
+    /// tokens are also rejected once the admin acting through them lost the admin role or had their tokens revoked.
+    async fn ensure_access_token_active(&self, access_token: &AccessToken) -> AppResult<()>;
+
+    async fn revoke_access_token(&self, access_token: &AccessToken) -> AppResult<()>;
+
+    /// Revokes every access token issued to the user so far, tokens issued afterwards are unaffected.
+    async fn revoke_user_access_tokens(&self, user_id: Uuid) -> AppResult<()>;
+
+    /// Spends an MFA token, rejecting it when it was presented before whether or not that attempt succeeded.
+    async fn consume_mfa_token(&self, mfa_token: &MfaToken) -> AppResult<()>;
 }
 
 #[derive(Clone)]
 pub struct SessionsService {
     repository: DynSessionsRepository,
+    roles_repository: DynRolesRepository,
+    revoked_access_tokens_repository: DynRevokedAccessTokensRepository,
     jwt_util: DynJwtUtil,
+    config: Arc<AppConfig>,
 }
 
 impl SessionsService {
-    pub fn new(repository: DynSessionsRepository, jwt_util: DynJwtUtil) -> Self {
+    pub fn new(
+        repository: DynSessionsRepository,
+        roles_repository: DynRolesRepository,
+        revoked_access_tokens_repository: DynRevokedAccessTokensRepository,
+        jwt_util: DynJwtUtil,
+        config: Arc<AppConfig>,
+    ) -> Self {
         Self {
             repository,
+            roles_repository,
+            revoked_access_tokens_repository,
             jwt_util,
+            config,
         }
     }
+
+    /// Sessions expire once they have not been refreshed for the idle timeout.
+    fn idle_session_exp(&self) -> OffsetDateTime {
+        let from_now = Duration::from_secs(self.config.session_idle_timeout_minutes * 60);
+
+        OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap())
+    }
+
+    fn session_max_age(&self) -> Duration {
+        Duration::from_secs(self.config.session_max_age_days * 86400)
+    }
+
+    /// Signing the admin out everywhere or revoking their admin role only revokes the tokens issued to the admin, the
+    /// impersonation tokens they hold are issued to the user they act as.
+    async fn ensure_actor_active(
+        &self,
+        access_token: &AccessToken,
+        actor_id: Uuid,
+    ) -> AppResult<()> {
+        let revoked = self
+            .revoked_access_tokens_repository
+            .is_access_token_revoked(access_token.jti, actor_id, &access_token.issued_at)
+            .await?;
+
+        if revoked {
+            warn!(
+                "impersonation token {:?} presented after the tokens of admin {:?} were revoked",
+                access_token.jti, actor_id
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        let grants =
+            AccessGrants::from(self.roles_repository.get_roles_by_user_id(actor_id).await?);
+
+        if !grants.has_role(permission_utils::ROLE_ADMIN) {
+            warn!(
+                "impersonation token {:?} presented after admin {:?} lost the admin role",
+                access_token.jti, actor_id
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl SessionsServiceTrait for SessionsService {
     async fn new_session(&self, request: NewSessionDto) -> AppResult<SessionResponseDto> {
         let user_id = request.user_id.unwrap();
-        let user_agent = request.user_agent.unwrap();
-        let from_now = Duration::from_secs(604800);
-        let expired_future_time = SystemTime::now().checked_add(from_now).unwrap();
-        let exp = OffsetDateTime::from(expired_future_time);
+        let user_agent = request.user_agent.unwrap_or_default();
+        let user_agent_info = user_agent_utils::parse_user_agent(&user_agent);
+        let exp = self.idle_session_exp();
+
+        let metadata = SessionMetadata {
+            user_agent,
+            ip_address: request.ip_address.map(|ip_address| ip_address.to_string()),
+            browser: user_agent_info.browser,
+            os: user_agent_info.os,
+            device: user_agent_info.device,
+        };
 
         let created_session = self
             .repository
-            .new_session(user_id, user_agent.as_str(), &exp)
+            .new_session(user_id, &metadata, &exp)
             .await?;
 
         let user_session = self
@@ -61,10 +162,14 @@ impl SessionsServiceTrait for SessionsService {
 
         info!("session successfully created, generating tokens");
         let access_token = self
-            .jwt_util
-            .new_access_token(user_session.id, &user_session.email)?;
+            .new_access_token(user_session.id, &user_session.email)
+            .await?;
 
-        let refresh_token = self.jwt_util.new_refresh_token(created_session.id)?;
+        let refresh_token = self.jwt_util.new_refresh_token(
+            created_session.id,
+            created_session.refresh_token_id,
+            &created_session.exp,
+        )?;
 
         Ok(SessionResponseDto {
             access_token,
@@ -72,16 +177,259 @@ impl SessionsServiceTrait for SessionsService {
         })
     }
 
-    async fn refresh_access_token(&self, id: Uuid) -> AppResult<ResponseUserDto> {
-        let user_in_session = self.repository.get_user_by_session_id(id).await?;
+    async fn new_access_token(&self, user_id: Uuid, email: &str) -> AppResult<String> {
+        let roles = self.roles_repository.get_roles_by_user_id(user_id).await?;
 
-        if let Some(user) = user_in_session {
-            info!("existing session found, generating access token");
-            let access_token = self.jwt_util.new_access_token(user.id, &user.email)?;
+        self.jwt_util
+            .new_access_token(user_id, email, &AccessGrants::from(roles))
+    }
 
-            return Ok(user.into_dto(access_token));
+    async fn refresh_access_token(
+        &self,
+        id: Uuid,
+        refresh_token_id: Uuid,
+    ) -> AppResult<(ResponseUserDto, String)> {
+        let rotated_session = self
+            .repository
+            .rotate_refresh_token(
+                id,
+                refresh_token_id,
+                &self.idle_session_exp(),
+                self.session_max_age(),
+            )
+            .await?;
+
+        if let Some(session) = rotated_session {
+            let user_in_session = self.repository.get_user_by_session_id(session.id).await?;
+
+            if let Some(user) = user_in_session {
+                info!("existing session found, generating access and refresh tokens");
+                let access_token = self.new_access_token(user.id, &user.email).await?;
+                let refresh_token = self.jwt_util.new_refresh_token(
+                    session.id,
+                    session.refresh_token_id,
+                    &session.exp,
+                )?;
+
+                return Ok((user.into_dto(access_token), refresh_token));
+            }
+
+            return Err(Error::Unauthorized);
+        }
+
+        // a validly signed token for a live session that is no longer current has already been
+        // exchanged once, so assume it was stolen and revoke the whole token family. A current
+        // token that could not be rotated belongs to a session that has expired.
+        if let Some(session) = self
+            .repository
+            .get_session_by_id(id)
+            .await?
+            .filter(|session| session.refresh_token_id != refresh_token_id)
+        {
+            warn!(
+                "refresh token reuse detected for session {:?} of user {:?}, revoking session",
+                session.id, session.user_id
+            );
+            self.repository.delete_session(session.id).await?;
+        }
+
+        Err(Error::Unauthorized)
+    }
+
+    async fn get_sessions(&self, user_id: Uuid) -> AppResult<Vec<SessionDto>> {
+        let sessions = self.repository.get_sessions_by_user_id(user_id).await?;
+
+        info!("found {} active sessions", sessions.len());
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| session.into_dto())
+            .collect())
+    }
+
+    async fn revoke_session(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let session = self.repository.get_session_by_id(id).await?;
+
+        if let Some(existing_session) = session {
+            permission_utils::ensure_owner(existing_session.user_id, user_id)?;
+
+            self.repository.delete_session(existing_session.id).await?;
+
+            info!("session {:?} revoked", id);
+
+            return Ok(());
         }
 
-        Err(crate::server::error::Error::Unauthorized)
+        Err(Error::NotFound(String::from("session was not found")))
     }
+
+    async fn revoke_other_sessions(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let session = self.repository.get_session_by_id(id).await?;
+
+        if let Some(existing_session) = session {
+            // the current session must belong to the authenticated user
+            permission_utils::ensure_owner(existing_session.user_id, user_id)?;
+
+            let revoked = self.repository.delete_other_sessions(user_id, id).await?;
+
+            info!("revoked {} other sessions for user {:?}", revoked, user_id);
+
+            return Ok(());
+        }
+
+        Err(Error::Unauthorized)
+    }
+
+    async fn signout_session(&self, id: Uuid) -> AppResult<()> {
+        info!("removing session {:?}", id);
+
+        self.repository.delete_session(id).await?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_sessions(&self, user_id: Uuid) -> AppResult<()> {
+        let revoked = self.repository.delete_sessions_by_user_id(user_id).await?;
+
+        info!("revoked {} sessions for user {:?}", revoked, user_id);
+
+        self.revoke_user_access_tokens(user_id).await
+    }
+
+    async fn ensure_access_token_active(&self, access_token: &AccessToken) -> AppResult<()> {
+        let revoked = self
+            .revoked_access_tokens_repository
+            .is_access_token_revoked(
+                access_token.jti,
+                access_token.user_id,
+                &access_token.issued_at,
+            )
+            .await?;
+
+        if revoked {
+            warn!(
+                "revoked access token {:?} presented for user {:?}",
+                access_token.jti, access_token.user_id
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        if let Some(actor_id) = access_token.actor_id {
+            self.ensure_actor_active(access_token, actor_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_access_token(&self, access_token: &AccessToken) -> AppResult<()> {
+        info!(
+            "revoking access token {:?} of user {:?}",
+            access_token.jti, access_token.user_id
+        );
+
+        self.revoked_access_tokens_repository
+            .revoke_access_token(access_token.jti, &access_token.expires_at)
+            .await?;
+
+        self.revoked_access_tokens_repository
+            .delete_expired_revoked_access_tokens()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_access_tokens(&self, user_id: Uuid) -> AppResult<()> {
+        // access tokens carry the microsecond they were issued at, the cutoff is exclusive so everything issued up to
+        // and including this one is revoked while the token returned along with a changed password is issued after it
+        let now = OffsetDateTime::now_utc();
+        let issued_before = OffsetDateTime::from_unix_timestamp_nanos(
+            (now.unix_timestamp_nanos() / 1000 + 1) * 1000,
+        )
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        // the revocation has to outlive every token it denies, impersonation tokens may be the longest lived
+        let longest_ttl = self
+            .config
+            .access_token_ttl_seconds
+            .max(self.config.impersonation_ttl_seconds);
+        let expires_at = now + Duration::from_secs(longest_ttl);
+
+        info!("revoking every access token issued to user {:?}", user_id);
+
+        self.revoked_access_tokens_repository
+            .revoke_user_access_tokens(user_id, &issued_before, &expires_at)
+            .await?;
+
+        self.revoked_access_tokens_repository
+            .delete_expired_revoked_access_tokens()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn consume_mfa_token(&self, mfa_token: &MfaToken) -> AppResult<()> {
+        let unused = self
+            .revoked_access_tokens_repository
+            .consume_token(mfa_token.jti, &mfa_token.expires_at)
+            .await?;
+
+        if !unused {
+            warn!(
+                "MFA token {:?} of user {:?} was already used",
+                mfa_token.jti, mfa_token.user_id
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(())
+    }
+}
+
+/// Starts a session for a user who proved who they are. Every way of signing in goes through here, so signing in
+/// during the grace period restores an account scheduled for deletion whichever way the user signs in.
+pub(crate) async fn start_session(
+    users_repository: &DynUsersRepository,
+    session_service: &DynSessionsService,
+    user: User,
+    user_agent: Option<String>,
+    ip_address: Option<IpAddr>,
+) -> AppResult<(ResponseUserDto, String)> {
+    if user.delete_after.is_some() {
+        info!("cancelling the scheduled deletion of user {:?}", user.id);
+        users_repository.cancel_user_deletion(user.id).await?;
+    }
+
+    let token = session_service
+        .new_session(NewSessionDto {
+            user_id: Some(user.id),
+            user_agent,
+            ip_address,
+        })
+        .await?;
+
+    Ok((user.into_dto(token.access_token), token.refresh_token))
+}
+
+/// Verifies the email of a user who proved they own the address some other way than the verification email. The
+/// password of an unverified account may have been set by someone else registering the address first, so it is
+/// replaced and its sessions revoked before handing the account over.
+pub(crate) async fn verify_unverified_user(
+    users_repository: &DynUsersRepository,
+    argon_util: &DynArgonUtil,
+    session_service: &DynSessionsService,
+    user: User,
+) -> AppResult<User> {
+    if user.verified_at.is_some() {
+        return Ok(user);
+    }
+
+    info!("verifying user {:?}, resetting their password", user.id);
+    let hashed_password = argon_util
+        .hash_password(&token_utils::generate_token())
+        .await?;
+    users_repository
+        .update_password(user.id, &hashed_password)
+        .await?;
+    session_service.revoke_user_sessions(user.id).await?;
+
+    Ok(users_repository.verify_email(user.id, &user.email).await?)
 }
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::info;
+
+use crate::config::{AppConfig, JwtAlgorithm};
+use crate::database::signing_key::DynSigningKeysRepository;
+use crate::server::error::{AppResult, Error};
+use crate::server::utils::signing_key_utils::{self, KeyRing};
+
+/// How far ahead of its activation a new key is published, giving other instances and JWKS consumers time to pick it up.
+pub const NEXT_KEY_LEAD: Duration = Duration::from_secs(600);
+
+/// A reference counter for our signing key service, rotating the keys access tokens are signed with.
+pub type DynSigningKeysService = Arc<dyn SigningKeysServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait SigningKeysServiceTrait {
+    /// Reloads the signing keys, creating the next key once the active one is due for rotation.
+    async fn rotate_keys(&self) -> AppResult<()>;
+
+    /// Returns the public keys access tokens may currently be signed with.
+    fn get_jwks(&self) -> JwkSet;
+}
+
+#[derive(Clone)]
+pub struct SigningKeysService {
+    repository: DynSigningKeysRepository,
+    key_ring: Arc<KeyRing>,
+    config: Arc<AppConfig>,
+}
+
+impl SigningKeysService {
+    pub fn new(
+        repository: DynSigningKeysRepository,
+        key_ring: Arc<KeyRing>,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            repository,
+            key_ring,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl SigningKeysServiceTrait for SigningKeysService {
+    async fn rotate_keys(&self) -> AppResult<()> {
+        if self.config.jwt_algorithm == JwtAlgorithm::Hs256 {
+            return Ok(());
+        }
+
+        self.repository.delete_expired_signing_keys().await?;
+
+        let now = SystemTime::now();
+        let rotation = Duration::from_secs(self.config.jwt_key_rotation_hours * 3600);
+        let grace = Duration::from_secs(self.config.jwt_key_grace_hours * 3600);
+
+        let keys = self.repository.get_signing_keys().await?;
+        let algorithm = algorithm_name(self.config.jwt_algorithm);
+
+        // the newest key might still be waiting for its activation, in which case there is nothing to do yet
+        let next_activation = match keys.first() {
+            Some(newest) if newest.algorithm == algorithm => {
+                let rotates_at = SystemTime::from(newest.activates_at) + rotation;
+
+                (rotates_at <= now + NEXT_KEY_LEAD).then_some(now + NEXT_KEY_LEAD)
+            }
+            // nothing can verify a key nobody has seen yet, the very first key is used right away
+            _ => Some(now),
+        };
+
+        if let Some(activates_at) = next_activation {
+            let configured_algorithm = self.config.jwt_algorithm;
+            let generated_key = tokio::task::spawn_blocking(move || {
+                signing_key_utils::generate_signing_key(configured_algorithm)
+            })
+            .await
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))??;
+
+            let public_jwk = serde_json::to_string(&generated_key.public_jwk)
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+            let expires_at = activates_at + rotation + NEXT_KEY_LEAD + grace;
+
+            info!(
+                "creating signing key {:?}, active from {:?}",
+                generated_key.kid,
+                OffsetDateTime::from(activates_at)
+            );
+            self.repository
+                .create_signing_key(
+                    &generated_key.kid,
+                    algorithm,
+                    &generated_key.private_key,
+                    &public_jwk,
+                    &OffsetDateTime::from(activates_at),
+                    &OffsetDateTime::from(expires_at),
+                )
+                .await?;
+        }
+
+        self.key_ring
+            .load(self.repository.get_signing_keys().await?)
+    }
+
+    fn get_jwks(&self) -> JwkSet {
+        self.key_ring.jwks()
+    }
+}
+
+/// Name of the algorithm as it appears in token headers, which is also how keys store it.
+fn algorithm_name(algorithm: JwtAlgorithm) -> &'static str {
+    match algorithm {
+        JwtAlgorithm::Hs256 => "HS256",
+        JwtAlgorithm::Rs256 => "RS256",
+        JwtAlgorithm::EdDsa => "EdDSA",
+    }
+}
@@ -0,0 +1,293 @@
+use mockall::automock;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        category::DynCategoriesRepository,
+        transaction::{
+            DynTransactionsRepository, Transaction, TransactionFields, TransactionFilter,
+        },
+    },
+    server::{
+        dtos::transaction_dto::{
+            TransactionCreateDto, TransactionQuery, TransactionResponseDto, TransactionUpdateDto,
+        },
+        error::{AppResult, Error},
+        utils::{currency_utils, permission_utils},
+    },
+};
+
+/// A reference counter for our transaction service, recording the money users spend and earn.
+pub type DynTransactionsService = Arc<dyn TransactionsServiceTrait + Send + Sync>;
+
+#[automock]
+#[async_trait]
+pub trait TransactionsServiceTrait {
+    /// Records a transaction against one of the accounts and categories of the user, transfers between accounts
+    /// are recorded through the accounts service.
+    async fn create_transaction(
+        &self,
+        user_id: Uuid,
+        request: TransactionCreateDto,
+    ) -> AppResult<TransactionResponseDto>;
+
+    async fn get_transaction_by_id(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<TransactionResponseDto>;
+
+    /// Lists the transactions of the user, newest first, narrowed down by date range, category and account.
+    async fn get_transactions(
+        &self,
+        user_id: Uuid,
+        query: TransactionQuery,
+    ) -> AppResult<Vec<TransactionResponseDto>>;
+
+    async fn updated_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: TransactionUpdateDto,
+    ) -> AppResult<TransactionResponseDto>;
+
+    /// Removes a transaction, removing a leg of a transfer removes the whole transfer.
+    async fn delete_transaction(&self, user_id: Uuid, id: Uuid) -> AppResult<()>;
+}
+
+#[derive(Clone)]
+pub struct TransactionsService {
+    repository: DynTransactionsRepository,
+    categories_repository: DynCategoriesRepository,
+    accounts_repository: DynAccountsRepository,
+}
+
+impl TransactionsService {
+    pub fn new(
+        repository: DynTransactionsRepository,
+        categories_repository: DynCategoriesRepository,
+        accounts_repository: DynAccountsRepository,
+    ) -> Self {
+        Self {
+            repository,
+            categories_repository,
+            accounts_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionsServiceTrait for TransactionsService {
+    async fn create_transaction(
+        &self,
+        user_id: Uuid,
+        request: TransactionCreateDto,
+    ) -> AppResult<TransactionResponseDto> {
+        let category_id = request.category_id.unwrap();
+        permission_utils::find_owned_category(&self.categories_repository, category_id, user_id)
+            .await?;
+
+        let account = permission_utils::find_owned_account(
+            &self.accounts_repository,
+            request.account_id.unwrap(),
+            user_id,
+        )
+        .await?;
+
+        let fields = TransactionFields {
+            amount: request.amount.unwrap(),
+            currency: request.currency.unwrap_or_else(|| account.currency.clone()),
+            tx_type: request.tx_type,
+            date: request.date.unwrap(),
+            payee: request.payee.unwrap(),
+            note: request.note,
+            category_id: Some(category_id),
+            account_id: Some(account.id),
+        };
+
+        let fields = validate_fields(fields, Some(&account))?;
+
+        let created_transaction = self.repository.create_transaction(user_id, fields).await?;
+
+        info!("user created transaction successfully");
+
+        Ok(created_transaction.into_dto())
+    }
+
+    async fn get_transaction_by_id(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<TransactionResponseDto> {
+        let existing_transaction = self.find_owned_transaction(id, user_id).await?;
+
+        Ok(existing_transaction.into_dto())
+    }
+
+    async fn get_transactions(
+        &self,
+        user_id: Uuid,
+        query: TransactionQuery,
+    ) -> AppResult<Vec<TransactionResponseDto>> {
+        let transactions = self
+            .repository
+            .get_transactions(
+                user_id,
+                TransactionFilter {
+                    from: query.from,
+                    to: query.to,
+                    category_id: query.category_id,
+                    account_id: query.account_id,
+                },
+            )
+            .await?;
+
+        info!("found {} transactions", transactions.len());
+
+        Ok(transactions
+            .into_iter()
+            .map(|transaction| transaction.into_dto())
+            .collect())
+    }
+
+    async fn updated_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        request: TransactionUpdateDto,
+    ) -> AppResult<TransactionResponseDto> {
+        let existing_transaction = self.find_owned_transaction(id, user_id).await?;
+
+        if existing_transaction.transfer_id.is_some() {
+            error!("transaction {:?} is part of a transfer", id);
+            return Err(Error::BadRequest(String::from(
+                "transfers cannot be edited, remove the transfer and record it again",
+            )));
+        }
+
+        if let Some(category_id) = request.category_id {
+            permission_utils::find_owned_category(
+                &self.categories_repository,
+                category_id,
+                user_id,
+            )
+            .await?;
+        }
+
+        let account_id = request.account_id.or(existing_transaction.account_id);
+        let account = match account_id {
+            Some(account_id) => Some(
+                permission_utils::find_owned_account(
+                    &self.accounts_repository,
+                    account_id,
+                    user_id,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let fields = TransactionFields {
+            amount: request.amount.unwrap_or(existing_transaction.amount),
+            currency: request.currency.unwrap_or(existing_transaction.currency),
+            tx_type: request.tx_type.unwrap_or(existing_transaction.tx_type),
+            date: request.date.unwrap_or(existing_transaction.date),
+            payee: request.payee.unwrap_or(existing_transaction.payee),
+            note: updated_note(request.note, existing_transaction.note),
+            category_id: request.category_id.or(existing_transaction.category_id),
+            account_id,
+        };
+
+        let fields = validate_fields(fields, account.as_ref())?;
+
+        let updated_transaction = self.repository.update_transaction(id, fields).await?;
+
+        Ok(updated_transaction.into_dto())
+    }
+
+    async fn delete_transaction(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let existing_transaction = self.find_owned_transaction(id, user_id).await?;
+
+        if let Some(transfer_id) = existing_transaction.transfer_id {
+            info!("removing both legs of transfer {:?}", transfer_id);
+            self.repository.delete_transfer(transfer_id).await?;
+
+            return Ok(());
+        }
+
+        self.repository
+            .delete_transaction(existing_transaction.id)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl TransactionsService {
+    async fn find_owned_transaction(&self, id: Uuid, user_id: Uuid) -> AppResult<Transaction> {
+        info!("searching for existing transaction {:?}", id);
+        let transaction = self.repository.get_transaction_by_id(id).await?;
+
+        if let Some(existing_transaction) = transaction {
+            permission_utils::ensure_owner(existing_transaction.user_id, user_id)?;
+
+            return Ok(existing_transaction);
+        }
+
+        Err(Error::NotFound(String::from("transaction was not found")))
+    }
+}
+
+/// Notes are optional, so an empty note clears the existing one while leaving it out keeps it.
+pub(crate) fn updated_note(note: Option<String>, existing_note: Option<String>) -> Option<String> {
+    match note {
+        Some(note) if note.trim().is_empty() => None,
+        Some(note) => Some(note),
+        None => existing_note,
+    }
+}
+
+/// Amounts are always positive, the transaction type tells whether money was spent or earned. Transactions
+/// are recorded in the currency of their account.
+pub(crate) fn validate_fields(
+    mut fields: TransactionFields,
+    account: Option<&Account>,
+) -> AppResult<TransactionFields> {
+    if fields.tx_type.is_transfer() {
+        error!("transfers cannot be recorded as a single transaction");
+        return Err(Error::BadRequest(String::from(
+            "transfers must be recorded between two accounts",
+        )));
+    }
+
+    if fields.amount <= Decimal::ZERO {
+        error!("transaction amount {:?} is not positive", fields.amount);
+        return Err(Error::BadRequest(String::from(
+            "amount must be greater than zero",
+        )));
+    }
+
+    currency_utils::ensure_amount_fits(fields.amount)?;
+
+    fields.currency = currency_utils::normalize_currency(&fields.currency)?;
+
+    if let Some(account) = account {
+        if account.currency != fields.currency {
+            error!(
+                "transaction currency {:?} does not match account currency {:?}",
+                fields.currency, account.currency
+            );
+            return Err(Error::BadRequest(String::from(
+                "currency must match the currency of the account",
+            )));
+        }
+    }
+
+    Ok(fields)
+}
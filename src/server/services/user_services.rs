@@ -1,23 +1,40 @@
 use mockall::automock;
+use sqlx::types::time::OffsetDateTime;
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{error, info};
 use uuid::Uuid;
 
 use async_trait::async_trait;
 
 use crate::{
+    config::AppConfig,
+    email_verification::DynEmailVerificationsRepository,
+    password_reset::DynPasswordResetsRepository,
+    recovery_code::DynRecoveryCodesRepository,
     server::{
-        dtos::{
-            session_dto::NewSessionDto,
-            user_dto::{ResponseUserDto, SignInUserDto, SignUpUserDto, UpdateUserDto},
+        dtos::user_dto::{
+            ForgotPasswordDto, MfaCodeDto, MfaEnrollmentDto, ResendVerificationDto,
+            ResetPasswordDto, ResponseUserDto, SignInMfaDto, SignInOutcome, SignInUserDto,
+            SignUpUserDto, UpdateUserDto, VerifyEmailDto,
         },
         error::{AppResult, Error},
-        utils::{argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil},
+        utils::{
+            argon_utils::DynArgonUtil,
+            currency_utils,
+            jwt_utils::DynJwtUtil,
+            mailer_utils::{DynMailer, Email},
+            token_utils, totp_utils,
+        },
     },
-    user::DynUsersRepository,
+    user::{DynUsersRepository, User},
 };
 
-use super::session_services::DynSessionsService;
+use super::{
+    login_throttle_services::DynLoginThrottlesService,
+    session_services::{self, DynSessionsService},
+};
 
 /// A reference counter for our user service allows us safely pass instances user utils
 /// around which themselves depend on the user repostiory, and ultimately, our Posgres connection pool.
@@ -28,10 +45,20 @@ pub type DynUsersService = Arc<dyn UsersServiceTrait + Send + Sync>;
 pub trait UsersServiceTrait {
     async fn signup_user(&self, request: SignUpUserDto) -> AppResult<ResponseUserDto>;
 
+    /// Failed attempts are throttled per account and per client IP, unknown emails fail the same way as wrong passwords.
     async fn signin_user(
         &self,
         request: SignInUserDto,
         user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome>;
+
+    /// Completes a sign in that required MFA, exchanging the MFA token and a TOTP or recovery code for a session.
+    async fn signin_user_mfa(
+        &self,
+        request: SignInMfaDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
     ) -> AppResult<(ResponseUserDto, String)>;
 
     async fn get_current_user(&self, user_id: Uuid) -> AppResult<ResponseUserDto>;
@@ -41,28 +68,61 @@ pub trait UsersServiceTrait {
         user_id: Uuid,
         request: UpdateUserDto,
     ) -> AppResult<ResponseUserDto>;
+
+    async fn forgot_password(&self, request: ForgotPasswordDto) -> AppResult<()>;
+
+    async fn reset_password(&self, request: ResetPasswordDto) -> AppResult<()>;
+
+    async fn verify_email(&self, request: VerifyEmailDto) -> AppResult<ResponseUserDto>;
+
+    async fn resend_verification(&self, request: ResendVerificationDto) -> AppResult<()>;
+
+    async fn enroll_mfa(&self, user_id: Uuid) -> AppResult<MfaEnrollmentDto>;
+
+    async fn confirm_mfa(&self, user_id: Uuid, request: MfaCodeDto) -> AppResult<()>;
+
+    async fn disable_mfa(&self, user_id: Uuid, request: MfaCodeDto) -> AppResult<()>;
 }
 
 #[derive(Clone)]
 pub struct UsersService {
     repository: DynUsersRepository,
+    password_resets_repository: DynPasswordResetsRepository,
+    email_verifications_repository: DynEmailVerificationsRepository,
+    recovery_codes_repository: DynRecoveryCodesRepository,
     argon_util: DynArgonUtil,
     jwt_util: DynJwtUtil,
     session_service: DynSessionsService,
+    login_throttles_service: DynLoginThrottlesService,
+    mailer: DynMailer,
+    config: Arc<AppConfig>,
 }
 
 impl UsersService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         repository: DynUsersRepository,
+        password_resets_repository: DynPasswordResetsRepository,
+        email_verifications_repository: DynEmailVerificationsRepository,
+        recovery_codes_repository: DynRecoveryCodesRepository,
         argon_util: DynArgonUtil,
         jwt_util: DynJwtUtil,
         session_service: DynSessionsService,
+        login_throttles_service: DynLoginThrottlesService,
+        mailer: DynMailer,
+        config: Arc<AppConfig>,
     ) -> Self {
         Self {
             repository,
+            password_resets_repository,
+            email_verifications_repository,
+            recovery_codes_repository,
             argon_util,
             jwt_util,
             session_service,
+            login_throttles_service,
+            mailer,
+            config,
         }
     }
 }
@@ -82,7 +142,7 @@ impl UsersServiceTrait for UsersService {
         }
 
         info!("creating password hash for user {:?}", email);
-        let hashed_password = self.argon_util.hash_password(&password)?;
+        let hashed_password = self.argon_util.hash_password(&password).await?;
 
         info!("password hashed successfully, creating user {:?}", email);
         let created_user = self
@@ -90,6 +150,9 @@ impl UsersServiceTrait for UsersService {
             .create_user(&email, &name, &hashed_password)
             .await?;
 
+        self.send_verification_email(created_user.id, &created_user.name, &email)
+            .await;
+
         Ok(created_user.into_dto(String::new()))
     }
 
@@ -97,41 +160,124 @@ impl UsersServiceTrait for UsersService {
         &self,
         request: SignInUserDto,
         user_agent: Option<String>,
-    ) -> AppResult<(ResponseUserDto, String)> {
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<SignInOutcome> {
         let email = request.email.unwrap();
         let attempted_password = request.password.unwrap();
 
+        self.login_throttles_service
+            .ensure_allowed(&email, ip_address)
+            .await?;
+
         info!("searching for existing user {:?}", email);
         let existing_user = self.repository.get_user_by_email(&email).await?;
 
-        if existing_user.is_none() {
-            return Err(Error::NotFound(String::from("user email does not exist")));
+        let is_valid_login_attempt = match &existing_user {
+            Some(user) => {
+                info!("user found, verifying password hash for user {:?}", email);
+                self.argon_util
+                    .verify_password(&user.password, attempted_password.clone())
+                    .await?
+            }
+            None => {
+                // spend the same time hashing as a password check would, so response times do not reveal unknown emails
+                self.argon_util.hash_password(&attempted_password).await?;
+                false
+            }
+        };
+
+        if !is_valid_login_attempt {
+            error!("invalid login attempt for user {:?}", email);
+            self.login_throttles_service
+                .record_failure(&email, ip_address)
+                .await?;
+
+            return Err(Error::InvalidLoginAttmpt);
         }
 
         let user = existing_user.unwrap();
 
-        info!("user found, verifying password hash for user {:?}", email);
-        let is_valid_login_attempt = self
-            .argon_util
-            .verify_password(&user.password, attempted_password)?;
+        self.login_throttles_service
+            .clear_account(&user.email)
+            .await?;
 
-        if !is_valid_login_attempt {
-            error!("invalid login attempt for user {:?}", email);
-            return Err(Error::InvalidLoginAttmpt);
+        if self.argon_util.needs_rehash(&user.password) {
+            self.rehash_password(user.id, &attempted_password).await;
+        }
+
+        if self.config.require_email_verification && user.verified_at.is_none() {
+            error!("login attempt for unverified user {:?}", email);
+            return Err(Error::EmailNotVerified);
+        }
+
+        if user.mfa_enabled_at.is_some() {
+            info!("password verified for user {:?}, MFA code required", email);
+            let mfa_token = self.jwt_util.new_mfa_token(user.id)?;
+
+            return Ok(SignInOutcome::MfaRequired(mfa_token));
         }
 
         info!("user login successful, generating tokens");
+        let (user, refresh_token) = session_services::start_session(
+            &self.repository,
+            &self.session_service,
+            user,
+            user_agent,
+            ip_address,
+        )
+        .await?;
+
+        Ok(SignInOutcome::Authenticated(user, refresh_token))
+    }
 
-        let token = self
-            .session_service
-            .new_session(NewSessionDto {
-                user_id: Some(user.id),
-                user_agent,
-            })
-            .await
-            .unwrap();
+    async fn signin_user_mfa(
+        &self,
+        request: SignInMfaDto,
+        user_agent: Option<String>,
+        ip_address: Option<IpAddr>,
+    ) -> AppResult<(ResponseUserDto, String)> {
+        let mfa_token = request.mfa_token.unwrap();
+        let code = request.code.unwrap();
+
+        let mfa_token = self.jwt_util.get_mfa_token(mfa_token).map_err(|err| {
+            error!("could not validate MFA token: {:?}", err);
+            Error::Unauthorized
+        })?;
+        let user_id = mfa_token.user_id;
+
+        self.login_throttles_service
+            .ensure_mfa_allowed(user_id, ip_address)
+            .await?;
+
+        // each MFA token allows a single attempt, guessing again means going through the password step again
+        self.session_service.consume_mfa_token(&mfa_token).await?;
+
+        info!("retrieving user {:?}", user_id);
+        let user = self.repository.get_user_by_id(user_id).await?;
+
+        if user.mfa_enabled_at.is_none() || !self.verify_mfa_code(&user, &code, true).await? {
+            error!("invalid MFA code for user {:?}", user_id);
+            self.login_throttles_service
+                .record_mfa_failure(user_id, ip_address)
+                .await?;
+
+            return Err(Error::InvalidLoginAttmpt);
+        }
 
-        Ok((user.into_dto(token.access_token), token.refresh_token))
+        self.login_throttles_service.clear_mfa(user_id).await?;
+
+        info!(
+            "MFA code accepted for user {:?}, generating tokens",
+            user_id
+        );
+        session_services::start_session(
+            &self.repository,
+            &self.session_service,
+            user,
+            user_agent,
+            ip_address,
+        )
+        .await
     }
 
     async fn get_current_user(&self, user_id: Uuid) -> AppResult<ResponseUserDto> {
@@ -143,8 +289,9 @@ impl UsersServiceTrait for UsersService {
             user.email
         );
         let token = self
-            .jwt_util
-            .new_access_token(user.id, user.email.as_str())?;
+            .session_service
+            .new_access_token(user.id, user.email.as_str())
+            .await?;
 
         Ok(user.into_dto(token))
     }
@@ -157,19 +304,45 @@ impl UsersServiceTrait for UsersService {
         info!("retrieving user {:?}", user_id);
         let user = self.repository.get_user_by_id(user_id).await?;
 
-        let updated_email = request.email.unwrap_or(user.email);
         let updated_name = request.name.unwrap_or(user.name);
+
+        let updated_base_currency = match request.base_currency {
+            Some(base_currency) => currency_utils::normalize_currency(&base_currency)?,
+            None => user.base_currency,
+        };
+
+        // a new email address only replaces the login email once it has been verified
+        if let Some(requested_email) = request.email.filter(|email| *email != user.email) {
+            let existing_user = self.repository.get_user_by_email(&requested_email).await?;
+
+            if existing_user.is_some() {
+                error!("user {:?} already exists", requested_email);
+                return Err(Error::ObjectConflict(format!(
+                    "email {} is taken",
+                    requested_email
+                )));
+            }
+
+            info!(
+                "email change requested for user {:?}, sending verification",
+                user_id
+            );
+            self.send_verification_email(user_id, &updated_name, &requested_email)
+                .await;
+        }
+
+        let updated_email = user.email;
         let mut updated_hashed_password = user.password;
+        let mut password_changed = false;
 
         // if the password is included on the request, hash it and update the stored password
-        if request.password.is_some() && !request.password.as_ref().unwrap().is_empty() {
+        if let Some(password) = request.password.filter(|password| !password.is_empty()) {
             info!(
                 "new password found for user {:?}, hashing password",
                 user_id
             );
-            updated_hashed_password = self
-                .argon_util
-                .hash_password(request.password.unwrap().as_str())?;
+            updated_hashed_password = self.argon_util.hash_password(password.as_str()).await?;
+            password_changed = true;
         }
 
         info!("updating user {:?}", user_id);
@@ -180,14 +353,370 @@ impl UsersServiceTrait for UsersService {
                 updated_email.clone(),
                 updated_name,
                 updated_hashed_password,
+                updated_base_currency,
             )
             .await?;
 
+        // tokens issued with the old password should not outlive it, the one returned below replaces them
+        if password_changed {
+            self.session_service
+                .revoke_user_access_tokens(user_id)
+                .await?;
+        }
+
         info!("user {:?} updated, generating a new token", user_id);
         let token = self
-            .jwt_util
-            .new_access_token(user_id, updated_email.as_str())?;
+            .session_service
+            .new_access_token(user_id, updated_email.as_str())
+            .await?;
 
         Ok(updated_user.into_dto(token))
     }
+
+    async fn forgot_password(&self, request: ForgotPasswordDto) -> AppResult<()> {
+        let email = request.email.unwrap();
+
+        info!("searching for existing user {:?}", email);
+        let existing_user = self.repository.get_user_by_email(&email).await?;
+
+        // respond the same way whether or not the email exists so accounts cannot be enumerated
+        if existing_user.is_none() {
+            info!(
+                "no user found for {:?}, skipping password reset email",
+                email
+            );
+            return Ok(());
+        }
+
+        let user = existing_user.unwrap();
+
+        let token = token_utils::generate_token();
+        let from_now = Duration::from_secs(self.config.password_reset_ttl_minutes * 60);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        self.password_resets_repository
+            .create_password_reset(user.id, &token_utils::hash_token(&token), &exp)
+            .await?;
+
+        info!(
+            "password reset token created for user {:?}, sending email",
+            user.id
+        );
+        let email = Email {
+            to: user.email,
+            subject: String::from("Reset your password"),
+            body: format!(
+                "Hi {},\n\n\
+                Use the link below to reset your password. It expires in {} minutes and can only be used once.\n\n\
+                {}/reset-password?token={}\n\n\
+                If you did not request a password reset, you can ignore this email.\n",
+                user.name, self.config.password_reset_ttl_minutes, self.config.app_url, token
+            ),
+        };
+
+        if let Err(err) = self.mailer.send(email).await {
+            error!(
+                "could not send password reset email to user {:?}: {:?}",
+                user.id, err
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn reset_password(&self, request: ResetPasswordDto) -> AppResult<()> {
+        let token = request.token.unwrap();
+        let password = request.password.unwrap();
+
+        let password_reset = self
+            .password_resets_repository
+            .consume_password_reset(&token_utils::hash_token(&token))
+            .await?;
+
+        if password_reset.is_none() {
+            error!("invalid or expired password reset token");
+            return Err(Error::BadRequest(String::from(
+                "password reset token is invalid or has expired",
+            )));
+        }
+
+        let user_id = password_reset.unwrap().user_id;
+
+        info!(
+            "password reset token accepted for user {:?}, hashing password",
+            user_id
+        );
+        let hashed_password = self.argon_util.hash_password(&password).await?;
+
+        self.repository
+            .update_password(user_id, &hashed_password)
+            .await?;
+
+        info!(
+            "password updated for user {:?}, revoking existing sessions",
+            user_id
+        );
+        self.password_resets_repository
+            .delete_password_resets_by_user_id(user_id)
+            .await?;
+
+        self.session_service.revoke_user_sessions(user_id).await?;
+
+        // proving control of the email is enough to lift a lockout
+        self.login_throttles_service.unlock_user(user_id).await?;
+
+        Ok(())
+    }
+
+    async fn verify_email(&self, request: VerifyEmailDto) -> AppResult<ResponseUserDto> {
+        let token = request.token.unwrap();
+
+        let verification = self
+            .email_verifications_repository
+            .consume_email_verification(&token_utils::hash_token(&token))
+            .await?;
+
+        if verification.is_none() {
+            error!("invalid or expired email verification token");
+            return Err(Error::BadRequest(String::from(
+                "email verification token is invalid or has expired",
+            )));
+        }
+
+        let verification = verification.unwrap();
+
+        let existing_user = self
+            .repository
+            .get_user_by_email(&verification.email)
+            .await?;
+
+        if existing_user.is_some_and(|user| user.id != verification.user_id) {
+            error!("user {:?} already exists", verification.email);
+            return Err(Error::ObjectConflict(format!(
+                "email {} is taken",
+                verification.email
+            )));
+        }
+
+        info!("verifying email for user {:?}", verification.user_id);
+        let verified_user = self
+            .repository
+            .verify_email(verification.user_id, &verification.email)
+            .await?;
+
+        Ok(verified_user.into_dto(String::new()))
+    }
+
+    async fn resend_verification(&self, request: ResendVerificationDto) -> AppResult<()> {
+        let email = request.email.unwrap();
+
+        info!("searching for existing user {:?}", email);
+        let existing_user = self.repository.get_user_by_email(&email).await?;
+
+        // respond the same way whether or not the email exists so accounts cannot be enumerated
+        match existing_user {
+            Some(user) if user.verified_at.is_none() => {
+                self.send_verification_email(user.id, &user.name, &user.email)
+                    .await;
+            }
+            _ => info!("no unverified user found for {:?}, skipping email", email),
+        }
+
+        Ok(())
+    }
+
+    async fn enroll_mfa(&self, user_id: Uuid) -> AppResult<MfaEnrollmentDto> {
+        info!("retrieving user {:?}", user_id);
+        let user = self.repository.get_user_by_id(user_id).await?;
+
+        // replacing an active secret has to go through disabling MFA, which requires a valid code
+        if user.mfa_enabled_at.is_some() {
+            error!("MFA is already enabled for user {:?}", user_id);
+            return Err(Error::ObjectConflict(String::from(
+                "MFA is already enabled",
+            )));
+        }
+
+        let secret = totp_utils::generate_secret();
+        let recovery_codes: Vec<String> = (0..10)
+            .map(|_| token_utils::generate_recovery_code())
+            .collect();
+
+        info!("storing pending MFA secret for user {:?}", user_id);
+        self.repository.set_mfa_secret(user_id, &secret).await?;
+        self.recovery_codes_repository
+            .replace_recovery_codes(
+                user_id,
+                recovery_codes
+                    .iter()
+                    .map(|code| token_utils::hash_token(code))
+                    .collect(),
+            )
+            .await?;
+
+        Ok(MfaEnrollmentDto {
+            otpauth_uri: totp_utils::otpauth_uri(&secret, &self.config.mfa_issuer, &user.email),
+            secret,
+            recovery_codes,
+        })
+    }
+
+    async fn confirm_mfa(&self, user_id: Uuid, request: MfaCodeDto) -> AppResult<()> {
+        let code = request.code.unwrap();
+
+        info!("retrieving user {:?}", user_id);
+        let user = self.repository.get_user_by_id(user_id).await?;
+
+        if user.mfa_secret.is_none() || user.mfa_enabled_at.is_some() {
+            return Err(Error::BadRequest(String::from(
+                "there is no pending MFA enrollment",
+            )));
+        }
+
+        // recovery codes cannot confirm an enrollment, it has to prove the authenticator works
+        if !self.verify_mfa_code(&user, &code, false).await? {
+            error!("invalid MFA code for user {:?}", user_id);
+            return Err(Error::BadRequest(String::from("MFA code is invalid")));
+        }
+
+        info!("MFA enrollment confirmed for user {:?}", user_id);
+        self.repository.enable_mfa(user_id).await?;
+
+        Ok(())
+    }
+
+    async fn disable_mfa(&self, user_id: Uuid, request: MfaCodeDto) -> AppResult<()> {
+        let code = request.code.unwrap();
+
+        info!("retrieving user {:?}", user_id);
+        let user = self.repository.get_user_by_id(user_id).await?;
+
+        if user.mfa_enabled_at.is_none() {
+            return Err(Error::BadRequest(String::from("MFA is not enabled")));
+        }
+
+        if !self.verify_mfa_code(&user, &code, true).await? {
+            error!("invalid MFA code for user {:?}", user_id);
+            return Err(Error::BadRequest(String::from("MFA code is invalid")));
+        }
+
+        info!("disabling MFA for user {:?}", user_id);
+        self.repository.disable_mfa(user_id).await?;
+        self.recovery_codes_repository
+            .delete_recovery_codes_by_user_id(user_id)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl UsersService {
+    /// Replaces an outdated password hash while the password is at hand. Failing to do so does not fail
+    /// the sign in, it is attempted again on the next one.
+    async fn rehash_password(&self, user_id: Uuid, password: &str) {
+        info!("password hash of user {:?} is outdated, rehashing", user_id);
+
+        let hashed_password = match self.argon_util.hash_password(password).await {
+            Ok(hashed_password) => hashed_password,
+            Err(err) => {
+                error!("could not rehash password of user {:?}: {:?}", user_id, err);
+                return;
+            }
+        };
+
+        if let Err(err) = self
+            .repository
+            .update_password(user_id, &hashed_password)
+            .await
+        {
+            error!(
+                "could not store rehashed password of user {:?}: {:?}",
+                user_id, err
+            );
+        }
+    }
+
+    /// Checks a TOTP code, each step is accepted once, and optionally falls back to single-use recovery codes.
+    async fn verify_mfa_code(
+        &self,
+        user: &User,
+        code: &str,
+        allow_recovery_code: bool,
+    ) -> AppResult<bool> {
+        let secret = match &user.mfa_secret {
+            Some(secret) => secret,
+            None => return Ok(false),
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?
+            .as_secs();
+
+        if let Some(step) = totp_utils::verify_code(secret, code.trim(), now) {
+            let is_unused_step = self.repository.use_mfa_step(user.id, step as i64).await?;
+
+            if !is_unused_step {
+                error!("MFA code replayed for user {:?}", user.id);
+            }
+
+            return Ok(is_unused_step);
+        }
+
+        if !allow_recovery_code {
+            return Ok(false);
+        }
+
+        let recovery_code = self
+            .recovery_codes_repository
+            .consume_recovery_code(
+                user.id,
+                &token_utils::hash_token(&code.trim().to_lowercase()),
+            )
+            .await?;
+
+        if recovery_code.is_some() {
+            info!("recovery code used for user {:?}", user.id);
+        }
+
+        Ok(recovery_code.is_some())
+    }
+
+    /// Creates a verification token for `email` and mails it, failures are logged as the user can request another.
+    async fn send_verification_email(&self, user_id: Uuid, name: &str, email: &str) {
+        let token = token_utils::generate_token();
+        let from_now = Duration::from_secs(self.config.email_verification_ttl_minutes * 60);
+        let exp = OffsetDateTime::from(SystemTime::now().checked_add(from_now).unwrap());
+
+        let created_verification = self
+            .email_verifications_repository
+            .create_email_verification(user_id, email, &token_utils::hash_token(&token), &exp)
+            .await;
+
+        if let Err(err) = created_verification {
+            error!(
+                "could not create email verification for user {:?}: {:?}",
+                user_id, err
+            );
+            return;
+        }
+
+        let message = Email {
+            to: String::from(email),
+            subject: String::from("Verify your email address"),
+            body: format!(
+                "Hi {},\n\n\
+                Please confirm your email address using the link below.\n\n\
+                {}/verify-email?token={}\n",
+                name, self.config.app_url, token
+            ),
+        };
+
+        if let Err(err) = self.mailer.send(message).await {
+            error!(
+                "could not send verification email to user {:?}: {:?}",
+                user_id, err
+            );
+        }
+    }
 }
@@ -0,0 +1,53 @@
+use rand::RngCore;
+
+use super::permission_utils::{
+    ACCOUNTS_READ, ACCOUNTS_WRITE, BUDGETS_READ, BUDGETS_WRITE, CATEGORIES_READ, CATEGORIES_WRITE,
+    TRANSACTIONS_READ, TRANSACTIONS_WRITE,
+};
+use super::token_utils;
+
+/// Marks a bearer token as an API key rather than a JWT.
+pub const API_KEY_PREFIX: &str = "rak_";
+
+/// Every permission an API key can be scoped to.
+pub const SCOPES: [&str; 8] = [
+    ACCOUNTS_READ,
+    ACCOUNTS_WRITE,
+    BUDGETS_READ,
+    BUDGETS_WRITE,
+    CATEGORIES_READ,
+    CATEGORIES_WRITE,
+    TRANSACTIONS_READ,
+    TRANSACTIONS_WRITE,
+];
+
+/// Generates a new API key formatted as `rak_<prefix>_<secret>`, returning the lookup prefix along with the key.
+pub fn generate_api_key() -> (String, String) {
+    let mut bytes = [0u8; 6];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    let prefix: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let key = format!(
+        "{}{}_{}",
+        API_KEY_PREFIX,
+        prefix,
+        token_utils::generate_token()
+    );
+
+    (prefix, key)
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+/// Returns the lookup prefix of a well formed API key.
+pub fn parse_prefix(key: &str) -> Option<&str> {
+    let (prefix, secret) = key.strip_prefix(API_KEY_PREFIX)?.split_once('_')?;
+
+    if prefix.is_empty() || secret.is_empty() {
+        return None;
+    }
+
+    Some(prefix)
+}
@@ -1,7 +1,14 @@
+use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
 
-use argon2::Config;
+use argon2::{Config, Variant, Version};
+use async_trait::async_trait;
+use data_encoding::BASE64_NOPAD;
 use mockall::automock;
+use rand::RngCore;
+use tokio::sync::Semaphore;
 
 use crate::{
     config::AppConfig,
@@ -12,44 +19,170 @@ use crate::{
 pub type DynArgonUtil = Arc<dyn ArgonUtil + Send + Sync>;
 
 #[automock]
+#[async_trait]
 pub trait ArgonUtil {
-    fn hash_password(&self, raw_password: &str) -> AppResult<String>;
+    /// Hashes the password with a fresh random salt and the configured cost parameters.
+    async fn hash_password(&self, raw_password: &str) -> AppResult<String>;
 
-    fn verify_password(&self, stored_password: &str, attempted_password: String)
-        -> AppResult<bool>;
+    async fn verify_password(
+        &self,
+        stored_password: &str,
+        attempted_password: String,
+    ) -> AppResult<bool>;
+
+    /// Whether the hash was made with the legacy global salt or with parameters other than the configured ones.
+    fn needs_rehash(&self, stored_password: &str) -> bool;
 }
 
+/// Length of the random salt generated for every hash, in bytes.
+const SALT_LENGTH: usize = 16;
+
+const VARIANT: Variant = Variant::Argon2id;
+
+/// Hashes passwords on the blocking thread pool, at most `argon_max_concurrency` at once. Hashing is
+/// deliberately slow, running it on the async workers would stall every other request during a burst
+/// of sign ins.
 pub struct ArgonSecurityUtil {
     config: Arc<AppConfig>,
+    permits: Arc<Semaphore>,
 }
 
 impl ArgonSecurityUtil {
     pub fn new(config: Arc<AppConfig>) -> Self {
-        Self { config }
+        let max_concurrency = config.argon_max_concurrency.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(NonZeroUsize::get)
+                .unwrap_or(1)
+        });
+
+        Self {
+            config,
+            permits: Arc::new(Semaphore::new(max_concurrency)),
+        }
+    }
+
+    fn argon_config(&self) -> Config<'static> {
+        Config {
+            variant: VARIANT,
+            version: Version::Version13,
+            mem_cost: self.config.argon_memory_cost,
+            time_cost: self.config.argon_time_cost,
+            lanes: self.config.argon_parallelism,
+            ..Config::default()
+        }
+    }
+
+    /// Waits for a free permit, then runs the job on the blocking pool.
+    async fn run_blocking<T, F>(&self, operation: &'static str, job: F) -> AppResult<T>
+    where
+        F: FnOnce() -> AppResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let queued_at = Instant::now();
+        let queued = GaugeGuard::new("password_hashing_queued");
+
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        drop(queued);
+        metrics::histogram!(
+            "password_hashing_queue_seconds",
+            queued_at.elapsed().as_secs_f64(),
+            "operation" => operation
+        );
+
+        let result = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _in_flight = GaugeGuard::new("password_hashing_in_flight");
+            let started_at = Instant::now();
+
+            let result = job();
+
+            metrics::histogram!(
+                "password_hashing_duration_seconds",
+                started_at.elapsed().as_secs_f64(),
+                "operation" => operation
+            );
+
+            result
+        })
+        .await
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        result
     }
 }
 
+#[async_trait]
 impl ArgonUtil for ArgonSecurityUtil {
-    fn hash_password(&self, raw_password: &str) -> AppResult<String> {
-        let password_bytes = raw_password.as_bytes();
-        let hashed_password = argon2::hash_encoded(
-            password_bytes,
-            self.config.argon_salt.as_bytes(),
-            &Config::default(),
-        )
-        .unwrap();
+    async fn hash_password(&self, raw_password: &str) -> AppResult<String> {
+        let raw_password = String::from(raw_password);
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
 
-        Ok(hashed_password)
+        let config = self.argon_config();
+
+        self.run_blocking("hash", move || {
+            argon2::hash_encoded(raw_password.as_bytes(), &salt, &config)
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
+        })
+        .await
     }
 
-    fn verify_password(
+    async fn verify_password(
         &self,
         stored_password: &str,
         attempted_password: String,
     ) -> AppResult<bool> {
-        let hashes_match = argon2::verify_encoded(stored_password, attempted_password.as_bytes())
-            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        let stored_password = String::from(stored_password);
+
+        self.run_blocking("verify", move || {
+            argon2::verify_encoded(&stored_password, attempted_password.as_bytes())
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
+        })
+        .await
+    }
+
+    fn needs_rehash(&self, stored_password: &str) -> bool {
+        // encoded hashes look like `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`
+        let segments: Vec<&str> = stored_password.split('$').collect();
+
+        let (variant, version, parameters, salt) = match segments.as_slice() {
+            ["", variant, version, parameters, salt, _hash] => {
+                (*variant, *version, *parameters, *salt)
+            }
+            _ => return true,
+        };
+
+        let config = self.argon_config();
+        let expected_parameters = format!(
+            "m={},t={},p={}",
+            config.mem_cost, config.time_cost, config.lanes
+        );
+
+        variant != VARIANT.as_lowercase_str()
+            || version != format!("v={}", config.version.as_u32())
+            || parameters != expected_parameters
+            || salt == BASE64_NOPAD.encode(self.config.argon_salt.as_bytes())
+    }
+}
+
+/// Keeps a gauge raised for as long as it is alive, jobs abandoned while queued are still accounted for.
+struct GaugeGuard(&'static str);
+
+impl GaugeGuard {
+    fn new(name: &'static str) -> Self {
+        metrics::increment_gauge!(name, 1.0);
+        Self(name)
+    }
+}
 
-        Ok(hashes_match)
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        metrics::decrement_gauge!(self.0, 1.0);
     }
 }
@@ -0,0 +1,50 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use ipnet::IpNet;
+
+use crate::config::AppConfig;
+
+/// Resolves the address of the client behind any trusted proxies.
+pub struct ClientIpUtil {
+    config: Arc<AppConfig>,
+}
+
+impl ClientIpUtil {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self { config }
+    }
+
+    /// Walks the reported hops from the closest one, for as long as they were reported by a trusted proxy. Anything
+    /// further along was written by the client itself and cannot be trusted, so the first untrusted hop is the client.
+    pub fn client_ip(&self, headers: &HeaderMap, peer_ip: Option<IpAddr>) -> Option<IpAddr> {
+        let mut client_ip = peer_ip?;
+
+        let mut hops = headers
+            .get_all(self.config.client_ip_header.as_str())
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .into_iter()
+            .rev();
+
+        while self.is_trusted_proxy(&client_ip) {
+            match hops.next().and_then(|hop| hop.parse::<IpAddr>().ok()) {
+                Some(hop) => client_ip = hop,
+                None => break,
+            }
+        }
+
+        Some(client_ip)
+    }
+
+    fn is_trusted_proxy(&self, address: &IpAddr) -> bool {
+        self.config
+            .trusted_proxies
+            .iter()
+            .any(|proxy: &IpNet| proxy.contains(address))
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use ring::constant_time::verify_slices_are_equal;
+use time::Duration;
+
+use crate::config::{AppConfig, CookieSameSite};
+use crate::server::utils::token_utils;
+
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const CSRF_TOKEN_COOKIE: &str = "csrf_token";
+pub const CSRF_TOKEN_HEADER: &str = "x-csrf-token";
+
+/// Builds the session cookies with the attributes configured for the deployment.
+pub struct CookieUtil {
+    config: Arc<AppConfig>,
+}
+
+impl CookieUtil {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self { config }
+    }
+
+    /// Stores the refresh token of a session along with a fresh CSRF token, returning the CSRF token
+    /// so clients on another origin, that cannot read the cookie, can still submit it.
+    pub fn add_session_cookies(
+        &self,
+        jar: CookieJar,
+        refresh_token: String,
+    ) -> (CookieJar, String) {
+        let csrf_token = token_utils::generate_token();
+
+        let mut refresh_cookie = self.build_cookie(
+            REFRESH_TOKEN_COOKIE,
+            refresh_token,
+            &self.config.cookie_path,
+        );
+        refresh_cookie.set_http_only(true);
+
+        // the CSRF cookie has to be readable by the frontend, so it is scoped to the whole site
+        let csrf_cookie = self.build_cookie(CSRF_TOKEN_COOKIE, csrf_token.clone(), "/");
+
+        (jar.add(refresh_cookie).add(csrf_cookie), csrf_token)
+    }
+
+    /// Expires the session cookies, the path and domain have to match for browsers to drop them.
+    pub fn remove_session_cookies(&self, jar: CookieJar) -> CookieJar {
+        let refresh_cookie = self.build_cookie(
+            REFRESH_TOKEN_COOKIE,
+            String::new(),
+            &self.config.cookie_path,
+        );
+        let csrf_cookie = self.build_cookie(CSRF_TOKEN_COOKIE, String::new(), "/");
+
+        jar.remove(refresh_cookie).remove(csrf_cookie)
+    }
+
+    fn build_cookie(&self, name: &'static str, value: String, path: &str) -> Cookie<'static> {
+        let same_site = match self.config.cookie_same_site {
+            CookieSameSite::Strict => SameSite::Strict,
+            CookieSameSite::Lax => SameSite::Lax,
+            CookieSameSite::None => SameSite::None,
+        };
+
+        let mut cookie = Cookie::build(name, value)
+            .path(path.to_owned())
+            .secure(self.config.cookie_secure)
+            .same_site(same_site)
+            .max_age(Duration::minutes(
+                self.config.session_idle_timeout_minutes as i64,
+            ))
+            .finish();
+
+        if let Some(domain) = &self.config.cookie_domain {
+            cookie.set_domain(domain.clone());
+        }
+
+        cookie
+    }
+}
+
+/// Looks up the refresh token among the cookies of a request.
+pub fn get_refresh_token(headers: &HeaderMap) -> Option<String> {
+    CookieJar::from_headers(headers)
+        .get(REFRESH_TOKEN_COOKIE)
+        .map(|cookie| cookie.value().to_owned())
+        .filter(|refresh_token| !refresh_token.is_empty())
+}
+
+/// Checks the double submitted CSRF token, the header has to match the cookie set alongside the refresh token.
+pub fn verify_csrf_token(headers: &HeaderMap) -> bool {
+    let cookie_token = CookieJar::from_headers(headers)
+        .get(CSRF_TOKEN_COOKIE)
+        .map(|cookie| cookie.value().to_owned());
+
+    let header_token = headers
+        .get(CSRF_TOKEN_HEADER)
+        .and_then(|header| header.to_str().ok());
+
+    match (cookie_token, header_token) {
+        (Some(cookie_token), Some(header_token)) if !cookie_token.is_empty() => {
+            verify_slices_are_equal(cookie_token.as_bytes(), header_token.as_bytes()).is_ok()
+        }
+        _ => false,
+    }
+}
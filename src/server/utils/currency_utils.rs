@@ -0,0 +1,63 @@
+use rust_decimal::Decimal;
+use tracing::error;
+
+use crate::server::error::{AppResult, Error};
+
+/// Amounts must stay below this, `numeric(19,4)` leaves fifteen digits before the decimal point.
+const MAX_AMOUNT: i64 = 1_000_000_000_000_000;
+
+/// The active ISO 4217 currency codes, sorted so they can be binary searched. The testing and no currency codes are
+/// left out as they never describe real money.
+pub const ISO_4217_CODES: [&str; 178] = [
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XDR", "XOF",
+    "XPD", "XPF", "XPT", "XSU", "XUA", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+/// Checks a currency is an active ISO 4217 code, returning it in upper case.
+pub fn normalize_currency(currency: &str) -> AppResult<String> {
+    let currency_code = currency.to_ascii_uppercase();
+
+    if ISO_4217_CODES
+        .binary_search(&currency_code.as_str())
+        .is_err()
+    {
+        error!("currency {:?} is invalid", currency);
+        return Err(Error::BadRequest(String::from(
+            "currency must be an ISO 4217 code",
+        )));
+    }
+
+    Ok(currency_code)
+}
+
+/// Amounts are stored as `numeric(19,4)`, anything finer would be rounded away silently and anything larger would
+/// not fit at all. Trailing zeros are ignored, they do not make an amount more precise.
+pub fn ensure_amount_fits(amount: Decimal) -> AppResult<()> {
+    if amount.normalize().scale() > 4 {
+        error!("amount {:?} is too precise", amount);
+        return Err(Error::BadRequest(String::from(
+            "amount must have at most four decimal places",
+        )));
+    }
+
+    if amount.abs() >= Decimal::from(MAX_AMOUNT) {
+        error!("amount {:?} is too large", amount);
+        return Err(Error::BadRequest(String::from(
+            "amount must have at most fifteen digits before the decimal point",
+        )));
+    }
+
+    Ok(())
+}
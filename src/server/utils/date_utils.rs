@@ -0,0 +1,25 @@
+use time::{format_description::FormatItem, macros::format_description, Date};
+
+use crate::server::error::{AppResult, Error};
+
+const ISO_DATE: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+// Serializes calendar dates as `YYYY-MM-DD`, use `iso_date::option` for optional dates.
+time::serde::format_description!(pub iso_date, Date, ISO_DATE);
+
+/// Parses a date formatted as `YYYY-MM-DD`.
+pub fn parse_date(date: &str) -> AppResult<Date> {
+    Date::parse(date, ISO_DATE)
+        .map_err(|_| Error::BadRequest(String::from("date must be formatted as YYYY-MM-DD")))
+}
+
+/// Parses a month formatted as `YYYY-MM` into its first day.
+pub fn parse_month(month: &str) -> AppResult<Date> {
+    Date::parse(&format!("{}-01", month), ISO_DATE)
+        .map_err(|_| Error::BadRequest(String::from("month must be formatted as YYYY-MM")))
+}
+
+/// Formats the month of a date as `YYYY-MM`.
+pub fn format_month(date: Date) -> String {
+    format!("{:04}-{:02}", date.year(), u8::from(date.month()))
+}
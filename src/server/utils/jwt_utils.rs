@@ -2,86 +2,293 @@ use std::ops::Add;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use mockall::automock;
 use serde::{Deserialize, Serialize};
 use sqlx::types::time::OffsetDateTime;
 use uuid::Uuid;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, JwtAlgorithm};
+use crate::database::role::AccessGrants;
 use crate::server::error::{AppResult, Error};
+use crate::server::utils::signing_key_utils::KeyRing;
 
 /// A security service for handling JWT authentication.
 pub type DynJwtUtil = Arc<dyn JwtUtil + Send + Sync>;
 
 #[automock]
 pub trait JwtUtil {
-    fn new_access_token(&self, user_id: Uuid, email: &str) -> AppResult<String>;
-    fn new_refresh_token(&self, sub: Uuid) -> AppResult<String>;
-    fn get_user_id_from_token(&self, token: String) -> AppResult<Uuid>;
-    fn get_session_id_from_token(&self, token: String) -> AppResult<Uuid>;
+    /// Issues an access token embedding the roles and permissions of the user.
+    fn new_access_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        grants: &AccessGrants,
+    ) -> AppResult<String>;
+    /// Issues a short-lived access token letting `actor_id` act as the user, marked with an `act` claim.
+    fn new_impersonation_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        grants: &AccessGrants,
+        actor_id: Uuid,
+    ) -> AppResult<String>;
+    /// Issues a refresh token for the session (token family), expiring along with the session.
+    fn new_refresh_token(&self, sub: Uuid, jti: Uuid, exp: &OffsetDateTime) -> AppResult<String>;
+    /// Returns the user, roles and permissions carried by the token, along with what identifies it for revocation.
+    fn get_access_from_token(&self, token: String) -> AppResult<AccessToken>;
+    /// Returns the session ID (token family) and the refresh token ID carried by the token.
+    fn get_session_id_from_token(&self, token: String) -> AppResult<(Uuid, Uuid)>;
+    /// Issues a short-lived token proving the password step of a sign in succeeded.
+    fn new_mfa_token(&self, user_id: Uuid) -> AppResult<String>;
+    /// Returns the user carried by the MFA token, along with what identifies it so it can only be used once.
+    fn get_mfa_token(&self, token: String) -> AppResult<MfaToken>;
 }
 
+/// Audience of MFA pending tokens, keeps them from being accepted anywhere else.
+const MFA_TOKEN_AUDIENCE: &str = "mfa";
+
 /// Our claims struct, it needs to derive `Serialize` and/or `Deserialize`
 #[derive(Debug, Serialize, Deserialize)]
 struct AccessTokenClaims {
     sub: String,
+    jti: Uuid,
     user_id: Uuid,
+    #[serde(default)]
+    roles: Vec<String>,
+    #[serde(default)]
+    permissions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    act: Option<ActorClaim>,
+    /// Access tokens carry microseconds, so a token issued in the same second as a revocation of every token of the
+    /// user can still be told apart from the ones it revoked.
+    exp: f64,
+    iat: f64,
+}
+
+/// The party acting on behalf of the subject, see https://www.rfc-editor.org/rfc/rfc8693#section-4.1
+#[derive(Debug, Serialize, Deserialize)]
+struct ActorClaim {
+    sub: Uuid,
+}
+
+/// A verified access token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessToken {
+    pub user_id: Uuid,
+    pub jti: Uuid,
+    pub grants: AccessGrants,
+    /// The admin impersonating the user, if the token was issued for impersonation.
+    pub actor_id: Option<Uuid>,
+    pub issued_at: OffsetDateTime,
+    pub expires_at: OffsetDateTime,
+}
+
+/// A verified MFA token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MfaToken {
+    pub user_id: Uuid,
+    pub jti: Uuid,
+    pub expires_at: OffsetDateTime,
+}
+
+/// Our claims struct, it needs to derive `Serialize` and/or `Deserialize`
+#[derive(Debug, Serialize, Deserialize)]
+struct RefreshTokenClaims {
+    sub: Uuid,
+    jti: Uuid,
     exp: usize,
     iat: usize,
 }
 
 /// Our claims struct, it needs to derive `Serialize` and/or `Deserialize`
 #[derive(Debug, Serialize, Deserialize)]
-struct RefreshTokenClaims {
+struct MfaTokenClaims {
     sub: Uuid,
+    jti: Uuid,
+    aud: String,
     exp: usize,
     iat: usize,
 }
 
+/// Signs access tokens with the configured algorithm. Refresh and MFA tokens are only ever read by
+/// this service, so they keep using the shared secrets.
 pub struct JwtTokenUtil {
     config: Arc<AppConfig>,
+    key_ring: Arc<KeyRing>,
 }
 
 impl JwtTokenUtil {
-    pub fn new(config: Arc<AppConfig>) -> Self {
-        Self { config }
+    pub fn new(config: Arc<AppConfig>, key_ring: Arc<KeyRing>) -> Self {
+        Self { config, key_ring }
     }
-}
 
-impl JwtUtil for JwtTokenUtil {
-    fn new_access_token(&self, user_id: Uuid, email: &str) -> AppResult<String> {
-        let from_now = Duration::from_secs(3600); //? expires every 15 min
-        let expired_future_time = SystemTime::now().add(from_now);
-        let exp = OffsetDateTime::from(expired_future_time);
+    fn access_token_signing_key(&self) -> AppResult<(Header, EncodingKey)> {
+        if self.config.jwt_algorithm == JwtAlgorithm::Hs256 {
+            return Ok((
+                Header::default(),
+                EncodingKey::from_secret(self.config.access_token_secret.as_bytes()),
+            ));
+        }
+
+        let (kid, algorithm, encoding_key) = self.key_ring.signing_key().ok_or_else(|| {
+            Error::InternalServerErrorWithContext(String::from(
+                "no active key to sign access tokens with",
+            ))
+        })?;
+
+        let mut header = Header::new(algorithm);
+        header.kid = Some(kid);
+
+        Ok((header, encoding_key))
+    }
+
+    /// Looks the verification key up by the `kid` of the token, the algorithm is taken from the key and never the token.
+    fn access_token_decoding_key(&self, token: &str) -> AppResult<(Validation, DecodingKey)> {
+        let header = decode_header(token)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        match header.kid {
+            Some(kid) => {
+                let (algorithm, decoding_key) =
+                    self.key_ring.decoding_key(&kid).ok_or_else(|| {
+                        Error::InternalServerErrorWithContext(format!(
+                            "token was signed with unknown key {}",
+                            kid
+                        ))
+                    })?;
+
+                Ok((Validation::new(algorithm), decoding_key))
+            }
+            None if self.config.jwt_algorithm == JwtAlgorithm::Hs256 => Ok((
+                Validation::new(Algorithm::HS256),
+                DecodingKey::from_secret(self.config.access_token_secret.as_bytes()),
+            )),
+            None => Err(Error::InternalServerErrorWithContext(String::from(
+                "token does not identify its signing key",
+            ))),
+        }
+    }
+
+    fn encode_access_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        grants: &AccessGrants,
+        actor_id: Option<Uuid>,
+        ttl: Duration,
+    ) -> AppResult<String> {
         let now = OffsetDateTime::now_utc();
+        let exp = now + ttl;
 
         let claims = AccessTokenClaims {
             sub: String::from(email),
+            jti: Uuid::new_v4(),
+            exp: date_time_to_numeric_date(&exp),
+            iat: date_time_to_numeric_date(&now),
+            user_id,
+            roles: grants.roles.clone(),
+            permissions: grants.permissions.clone(),
+            act: actor_id.map(|sub| ActorClaim { sub }),
+        };
+
+        let (header, encoding_key) = self.access_token_signing_key()?;
+
+        let token = encode(&header, &claims, &encoding_key)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        Ok(token)
+    }
+}
+
+impl JwtUtil for JwtTokenUtil {
+    fn new_access_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        grants: &AccessGrants,
+    ) -> AppResult<String> {
+        let ttl = Duration::from_secs(self.config.access_token_ttl_seconds);
+
+        self.encode_access_token(user_id, email, grants, None, ttl)
+    }
+
+    fn new_impersonation_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        grants: &AccessGrants,
+        actor_id: Uuid,
+    ) -> AppResult<String> {
+        let ttl = Duration::from_secs(self.config.impersonation_ttl_seconds);
+
+        self.encode_access_token(user_id, email, grants, Some(actor_id), ttl)
+    }
+
+    fn new_refresh_token(&self, sub: Uuid, jti: Uuid, exp: &OffsetDateTime) -> AppResult<String> {
+        let now = OffsetDateTime::now_utc();
+
+        let claims = RefreshTokenClaims {
+            sub,
+            jti,
             exp: exp.unix_timestamp() as usize,
             iat: now.unix_timestamp() as usize,
-            user_id,
         };
 
         let token = encode(
             &Header::default(),
             &claims,
-            &EncodingKey::from_secret(self.config.access_token_secret.as_bytes()),
+            &EncodingKey::from_secret(self.config.refresh_token_secret.as_bytes()),
         )
         .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
 
         Ok(token)
     }
 
-    fn new_refresh_token(&self, sub: Uuid) -> AppResult<String> {
-        let exp_time = 60 * 60 * 24 * 7 * 4; // expires in 1 month
-        let from_now = Duration::from_secs(exp_time);
+    fn get_access_from_token(&self, token: String) -> AppResult<AccessToken> {
+        let (validation, decoding_key) = self.access_token_decoding_key(&token)?;
+
+        let decoded_token = decode::<AccessTokenClaims>(token.as_str(), &decoding_key, &validation)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        let claims = decoded_token.claims;
+
+        Ok(AccessToken {
+            user_id: claims.user_id,
+            jti: claims.jti,
+            grants: AccessGrants {
+                roles: claims.roles,
+                permissions: claims.permissions,
+            },
+            actor_id: claims.act.map(|actor| actor.sub),
+            issued_at: numeric_date_to_date_time(claims.iat)?,
+            expires_at: numeric_date_to_date_time(claims.exp)?,
+        })
+    }
+
+    fn get_session_id_from_token(&self, token: String) -> AppResult<(Uuid, Uuid)> {
+        let decoded_token = decode::<RefreshTokenClaims>(
+            token.as_str(),
+            &DecodingKey::from_secret(self.config.refresh_token_secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        Ok((decoded_token.claims.sub, decoded_token.claims.jti))
+    }
+
+    fn new_mfa_token(&self, user_id: Uuid) -> AppResult<String> {
+        let from_now = Duration::from_secs(self.config.mfa_token_ttl_seconds);
         let expired_future_time = SystemTime::now().add(from_now);
         let exp = OffsetDateTime::from(expired_future_time);
         let now = OffsetDateTime::now_utc();
 
-        let claims = RefreshTokenClaims {
-            sub,
+        let claims = MfaTokenClaims {
+            sub: user_id,
+            jti: Uuid::new_v4(),
+            aud: String::from(MFA_TOKEN_AUDIENCE),
             exp: exp.unix_timestamp() as usize,
             iat: now.unix_timestamp() as usize,
         };
@@ -89,32 +296,45 @@ impl JwtUtil for JwtTokenUtil {
         let token = encode(
             &Header::default(),
             &claims,
-            &EncodingKey::from_secret(self.config.refresh_token_secret.as_bytes()),
+            &EncodingKey::from_secret(self.config.access_token_secret.as_bytes()),
         )
         .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
 
         Ok(token)
     }
 
-    fn get_user_id_from_token(&self, token: String) -> AppResult<Uuid> {
-        let decoded_token = decode::<AccessTokenClaims>(
+    fn get_mfa_token(&self, token: String) -> AppResult<MfaToken> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[MFA_TOKEN_AUDIENCE]);
+
+        let decoded_token = decode::<MfaTokenClaims>(
             token.as_str(),
             &DecodingKey::from_secret(self.config.access_token_secret.as_bytes()),
-            &Validation::new(Algorithm::HS256),
+            &validation,
         )
         .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
 
-        Ok(decoded_token.claims.user_id)
+        Ok(MfaToken {
+            user_id: decoded_token.claims.sub,
+            jti: decoded_token.claims.jti,
+            expires_at: unix_timestamp_to_date_time(decoded_token.claims.exp)?,
+        })
     }
+}
 
-    fn get_session_id_from_token(&self, token: String) -> AppResult<Uuid> {
-        let decoded_token = decode::<RefreshTokenClaims>(
-            token.as_str(),
-            &DecodingKey::from_secret(self.config.refresh_token_secret.as_bytes()),
-            &Validation::new(Algorithm::HS256),
-        )
-        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+fn unix_timestamp_to_date_time(timestamp: usize) -> AppResult<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
+}
 
-        Ok(decoded_token.claims.sub)
-    }
+/// Whole microseconds since the epoch, the precision Postgres keeps timestamps with.
+fn date_time_to_numeric_date(date_time: &OffsetDateTime) -> f64 {
+    (date_time.unix_timestamp_nanos() / 1000) as f64 / 1_000_000.0
+}
+
+fn numeric_date_to_date_time(numeric_date: f64) -> AppResult<OffsetDateTime> {
+    let micros = (numeric_date * 1_000_000.0).round() as i128;
+
+    OffsetDateTime::from_unix_timestamp_nanos(micros * 1000)
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
 }
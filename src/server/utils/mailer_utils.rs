@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use mockall::automock;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    config::AppConfig,
+    server::error::{AppResult, Error},
+};
+
+/// A mailer service for sending transactional emails, implementations are selected by `MAILER_TRANSPORT`.
+pub type DynMailer = Arc<dyn Mailer + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[automock]
+#[async_trait]
+pub trait Mailer {
+    async fn send(&self, email: Email) -> AppResult<()>;
+}
+
+fn build_message(from: &str, email: &Email) -> AppResult<Message> {
+    Message::builder()
+        .from(from.parse().map_err(|_| {
+            Error::InternalServerErrorWithContext(format!("invalid sender {}", from))
+        })?)
+        .to(email
+            .to
+            .parse()
+            .map_err(|_| Error::BadRequest(format!("invalid recipient {}", email.to)))?)
+        .subject(email.subject.as_str())
+        .header(ContentType::TEXT_PLAIN)
+        .body(email.body.clone())
+        .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))
+}
+
+/// Delivers emails through an SMTP relay using STARTTLS.
+pub struct SmtpMailer {
+    config: Arc<AppConfig>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(config: Arc<AppConfig>) -> anyhow::Result<Self> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .context("SMTP_HOST is required when using the smtp mailer transport")?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .context("could not initialize the SMTP relay")?
+            .port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> AppResult<()> {
+        let message = build_message(&self.config.mail_from, &email)?;
+
+        self.transport
+            .send(message)
+            .await
+            .context("could not deliver email through the SMTP relay")?;
+
+        info!("email {:?} sent to {:?}", email.subject, email.to);
+
+        Ok(())
+    }
+}
+
+/// Writes emails as `.eml` files to `MAIL_DIR`, useful for local development.
+pub struct FileMailer {
+    config: Arc<AppConfig>,
+}
+
+impl FileMailer {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> AppResult<()> {
+        let message = build_message(&self.config.mail_from, &email)?;
+
+        let mail_dir = PathBuf::from(&self.config.mail_dir);
+        tokio::fs::create_dir_all(&mail_dir)
+            .await
+            .context("could not create the mail directory")?;
+
+        let path = mail_dir.join(format!("{}.eml", Uuid::new_v4()));
+        tokio::fs::write(&path, message.formatted())
+            .await
+            .context("could not write the email to the mail directory")?;
+
+        info!("email {:?} written to {:?}", email.subject, path);
+
+        Ok(())
+    }
+}
+
+/// Keeps emails in memory so they can be inspected, meant for tests.
+#[derive(Default)]
+pub struct InMemoryMailer {
+    messages: Mutex<Vec<Email>>,
+}
+
+impl InMemoryMailer {
+    pub fn messages(&self) -> Vec<Email> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for InMemoryMailer {
+    async fn send(&self, email: Email) -> AppResult<()> {
+        info!("email {:?} stored for {:?}", email.subject, email.to);
+
+        self.messages.lock().unwrap().push(email);
+
+        Ok(())
+    }
+}
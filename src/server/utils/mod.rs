@@ -1,2 +1,16 @@
+pub mod api_key_utils;
 pub mod argon_utils;
+pub mod client_ip_utils;
+pub mod cookie_utils;
+pub mod currency_utils;
+pub mod date_utils;
 pub mod jwt_utils;
+pub mod mailer_utils;
+pub mod oidc_utils;
+pub mod permission_utils;
+pub mod recurrence_utils;
+pub mod signing_key_utils;
+pub mod token_utils;
+pub mod totp_utils;
+pub mod user_agent_utils;
+pub mod webauthn_utils;
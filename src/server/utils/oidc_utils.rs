@@ -0,0 +1,303 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use mockall::automock;
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+use crate::{
+    config::AppConfig,
+    server::error::{AppResult, Error},
+};
+
+/// A client for the configured OpenID Connect provider.
+pub type DynOidcClient = Arc<dyn OidcClient + Send + Sync>;
+
+/// The user as identified by a validated ID token.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+#[automock]
+#[async_trait]
+pub trait OidcClient {
+    /// Builds the provider URL that starts an authorization code flow.
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> AppResult<String>;
+
+    /// Redeems an authorization code and validates the returned ID token against the provider keys.
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> AppResult<OidcIdentity>;
+}
+
+/// Derives the `S256` PKCE code challenge of a code verifier.
+pub fn code_challenge(code_verifier: &str) -> String {
+    BASE64URL_NOPAD.encode(&Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// Talks to the provider over HTTP, discovery metadata and signing keys are fetched lazily and cached.
+pub struct HttpOidcClient {
+    config: Arc<AppConfig>,
+    http: reqwest::Client,
+    metadata: RwLock<Option<ProviderMetadata>>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl HttpOidcClient {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    fn client_settings(&self) -> AppResult<(&str, &str)> {
+        match (&self.config.oidc_issuer_url, &self.config.oidc_client_id) {
+            (Some(issuer_url), Some(client_id)) => Ok((issuer_url, client_id)),
+            _ => Err(Error::NotFound(String::from(
+                "OIDC sign in is not configured",
+            ))),
+        }
+    }
+
+    async fn metadata(&self) -> AppResult<ProviderMetadata> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+
+        let (issuer_url, _) = self.client_settings()?;
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer_url.trim_end_matches('/')
+        );
+
+        info!("fetching OIDC provider metadata from {:?}", discovery_url);
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&discovery_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("could not fetch the OIDC discovery document")?
+            .json()
+            .await
+            .context("could not parse the OIDC discovery document")?;
+
+        if metadata.issuer.trim_end_matches('/') != issuer_url.trim_end_matches('/') {
+            return Err(Error::InternalServerErrorWithContext(format!(
+                "OIDC discovery issuer {} does not match {}",
+                metadata.issuer, issuer_url
+            )));
+        }
+
+        *self.metadata.write().await = Some(metadata.clone());
+
+        Ok(metadata)
+    }
+
+    /// Finds the key an ID token was signed with, refetching the key set once for unknown key IDs
+    /// as providers rotate their keys.
+    async fn signing_key(&self, jwks_uri: &str, kid: Option<&str>) -> AppResult<Jwk> {
+        if let Some(jwk) = self
+            .jwks
+            .read()
+            .await
+            .as_ref()
+            .and_then(|jwks| find_key(jwks, kid))
+        {
+            return Ok(jwk);
+        }
+
+        info!("fetching OIDC provider keys from {:?}", jwks_uri);
+        let jwks: JwkSet = self
+            .http
+            .get(jwks_uri)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("could not fetch the OIDC provider keys")?
+            .json()
+            .await
+            .context("could not parse the OIDC provider keys")?;
+
+        let jwk = find_key(&jwks, kid);
+        *self.jwks.write().await = Some(jwks);
+
+        jwk.ok_or_else(|| {
+            error!("no OIDC provider key found for kid {:?}", kid);
+            Error::Unauthorized
+        })
+    }
+}
+
+fn find_key(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
+
+#[async_trait]
+impl OidcClient for HttpOidcClient {
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> AppResult<String> {
+        let (_, client_id) = self.client_settings()?;
+        let metadata = self.metadata().await?;
+
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .context("OIDC authorization endpoint is not a valid URL")?;
+
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", client_id)
+            .append_pair("redirect_uri", &self.config.oidc_redirect_url)
+            .append_pair("scope", &self.config.oidc_scopes)
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url.into())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> AppResult<OidcIdentity> {
+        let (_, client_id) = self.client_settings()?;
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.config.oidc_redirect_url),
+            ("client_id", client_id),
+            ("code_verifier", code_verifier),
+        ];
+
+        if let Some(client_secret) = &self.config.oidc_client_secret {
+            form.push(("client_secret", client_secret));
+        }
+
+        info!("exchanging OIDC authorization code");
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .context("could not reach the OIDC token endpoint")?;
+
+        if !response.status().is_success() {
+            error!(
+                "OIDC token endpoint rejected the authorization code with {:?}",
+                response.status()
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        let token_response: TokenResponse = response
+            .json()
+            .await
+            .context("could not parse the OIDC token response")?;
+
+        let header = decode_header(&token_response.id_token).map_err(|err| {
+            error!("could not decode ID token header: {:?}", err);
+            Error::Unauthorized
+        })?;
+
+        // ID tokens have to be signed with one of the provider's published asymmetric keys
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            error!(
+                "ID token signed with unsupported algorithm {:?}",
+                header.alg
+            );
+            return Err(Error::Unauthorized);
+        }
+
+        let jwk = self
+            .signing_key(&metadata.jwks_uri, header.kid.as_deref())
+            .await?;
+        let decoding_key = DecodingKey::from_jwk(&jwk).map_err(|err| {
+            error!("could not use OIDC provider key: {:?}", err);
+            Error::Unauthorized
+        })?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[client_id]);
+
+        let claims = decode::<IdTokenClaims>(&token_response.id_token, &decoding_key, &validation)
+            .map_err(|err| {
+                error!("could not validate ID token: {:?}", err);
+                Error::Unauthorized
+            })?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            error!("ID token nonce does not match the login state");
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(OidcIdentity {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use axum::http::Method;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        category::{Category, DynCategoriesRepository},
+    },
+    server::error::{AppResult, Error},
+};
+
+pub const ROLE_ADMIN: &str = "admin";
+pub const ROLE_USER: &str = "user";
+
+pub const ACCOUNTS_READ: &str = "accounts:read";
+pub const ACCOUNTS_WRITE: &str = "accounts:write";
+pub const BUDGETS_READ: &str = "budgets:read";
+pub const BUDGETS_WRITE: &str = "budgets:write";
+pub const CATEGORIES_READ: &str = "categories:read";
+pub const CATEGORIES_WRITE: &str = "categories:write";
+pub const TRANSACTIONS_READ: &str = "transactions:read";
+pub const TRANSACTIONS_WRITE: &str = "transactions:write";
+pub const USERS_READ: &str = "users:read";
+pub const USERS_WRITE: &str = "users:write";
+
+/// Permissions required to call the routes of a controller, attached to them as a route layer
+/// extension and enforced by `RequiredAuthentication`. Reads need the `read` permission, every
+/// other method the `write` permission. API keys are only accepted by routes declaring these.
+#[derive(Clone, Copy, Debug)]
+pub struct RoutePermissions {
+    pub read: &'static str,
+    pub write: &'static str,
+}
+
+impl RoutePermissions {
+    pub fn new(read: &'static str, write: &'static str) -> Self {
+        Self { read, write }
+    }
+
+    pub fn required_for(&self, method: &Method) -> &'static str {
+        if method == Method::GET || method == Method::HEAD {
+            self.read
+        } else {
+            self.write
+        }
+    }
+}
+
+/// Rejects access to a resource owned by another user.
+pub fn ensure_owner(owner_id: Uuid, user_id: Uuid) -> AppResult<()> {
+    if owner_id != user_id {
+        return Err(Error::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Looks up an account, rejecting accounts of other users.
+pub async fn find_owned_account(
+    accounts_repository: &DynAccountsRepository,
+    account_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Account> {
+    let account = accounts_repository.get_account_by_id(account_id).await?;
+
+    if let Some(existing_account) = account {
+        ensure_owner(existing_account.user_id, user_id)?;
+
+        return Ok(existing_account);
+    }
+
+    Err(Error::NotFound(String::from("account was not found")))
+}
+
+/// Looks up a category, rejecting categories of other users.
+pub async fn find_owned_category(
+    categories_repository: &DynCategoriesRepository,
+    category_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Category> {
+    let category = categories_repository
+        .get_category_by_id(category_id)
+        .await?;
+
+    if let Some(existing_category) = category {
+        ensure_owner(existing_category.user_id, user_id)?;
+
+        return Ok(existing_category);
+    }
+
+    Err(Error::NotFound(String::from("category was not found")))
+}
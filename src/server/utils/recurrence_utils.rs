@@ -0,0 +1,201 @@
+use time::{Date, Duration, Month};
+use tracing::error;
+
+use crate::database::recurring_transaction::{RecurrenceFrequency, RecurrenceRule};
+use crate::server::error::{AppResult, Error};
+
+/// Parses the `FREQ`, `INTERVAL`, `UNTIL` and `COUNT` parts of an RFC 5545 recurrence rule, e.g.
+/// `FREQ=MONTHLY;INTERVAL=3;COUNT=4`. Any other part is rejected rather than silently ignored.
+pub fn parse_rrule(rrule: &str) -> AppResult<RecurrenceRule> {
+    let rrule = rrule.trim();
+    let rrule = match rrule.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &rrule[6..],
+        _ => rrule,
+    };
+
+    let mut frequency = None;
+    let mut interval = None;
+    let mut until = None;
+    let mut count = None;
+
+    for part in rrule.split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| invalid_rrule(format!("{} is not formatted as NAME=VALUE", part)))?;
+
+        let name = name.to_ascii_uppercase();
+        let duplicate = match name.as_str() {
+            "FREQ" => frequency.replace(parse_frequency(value)?).is_some(),
+            "INTERVAL" => interval.replace(parse_positive(&name, value)?).is_some(),
+            "UNTIL" => until.replace(parse_until(value)?).is_some(),
+            "COUNT" => count.replace(parse_positive(&name, value)?).is_some(),
+            _ => return Err(invalid_rrule(format!("{} is not supported", name))),
+        };
+
+        if duplicate {
+            return Err(invalid_rrule(format!("{} is given more than once", name)));
+        }
+    }
+
+    let frequency = frequency.ok_or_else(|| invalid_rrule(String::from("FREQ is required")))?;
+
+    if until.is_some() && count.is_some() {
+        return Err(invalid_rrule(String::from(
+            "UNTIL and COUNT cannot both be given",
+        )));
+    }
+
+    Ok(RecurrenceRule {
+        frequency,
+        interval: interval.unwrap_or(1),
+        until,
+        count,
+    })
+}
+
+/// Formats a rule back into an RFC 5545 recurrence rule, leaving out the default interval of one.
+pub fn format_rrule(rule: &RecurrenceRule) -> String {
+    let frequency = match rule.frequency {
+        RecurrenceFrequency::Daily => "DAILY",
+        RecurrenceFrequency::Weekly => "WEEKLY",
+        RecurrenceFrequency::Monthly => "MONTHLY",
+        RecurrenceFrequency::Yearly => "YEARLY",
+    };
+
+    let mut rrule = format!("FREQ={}", frequency);
+
+    if rule.interval > 1 {
+        rrule.push_str(&format!(";INTERVAL={}", rule.interval));
+    }
+
+    if let Some(until) = rule.until {
+        rrule.push_str(&format!(
+            ";UNTIL={:04}{:02}{:02}",
+            until.year(),
+            u8::from(until.month()),
+            until.day()
+        ));
+    }
+
+    if let Some(count) = rule.count {
+        rrule.push_str(&format!(";COUNT={}", count));
+    }
+
+    rrule
+}
+
+/// Iterates over the occurrences of a rule, the first one being the start date. As in RFC 5545, monthly and yearly
+/// occurrences falling on a day the month does not have, such as the 31st of April, are skipped.
+pub fn occurrences(rule: &RecurrenceRule, starts_on: Date) -> Occurrences {
+    Occurrences {
+        rule: rule.clone(),
+        starts_on,
+        step: 0,
+        produced: 0,
+    }
+}
+
+pub struct Occurrences {
+    rule: RecurrenceRule,
+    starts_on: Date,
+    step: i64,
+    produced: u32,
+}
+
+impl Iterator for Occurrences {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if matches!(self.rule.count, Some(count) if self.produced >= count) {
+            return None;
+        }
+
+        loop {
+            let steps = self.step * self.rule.interval as i64;
+            self.step += 1;
+
+            let candidate = match self.rule.frequency {
+                RecurrenceFrequency::Daily => {
+                    Some(self.starts_on.checked_add(Duration::days(steps))?)
+                }
+                RecurrenceFrequency::Weekly => {
+                    Some(self.starts_on.checked_add(Duration::weeks(steps))?)
+                }
+                RecurrenceFrequency::Monthly => self.add_months(steps)?,
+                RecurrenceFrequency::Yearly => self.add_months(steps * 12)?,
+            };
+
+            if let Some(date) = candidate {
+                if matches!(self.rule.until, Some(until) if date > until) {
+                    return None;
+                }
+
+                self.produced += 1;
+                return Some(date);
+            }
+        }
+    }
+}
+
+impl Occurrences {
+    /// Returns `None` once past the last representable date, and `Some(None)` for days the month does not have.
+    fn add_months(&self, months: i64) -> Option<Option<Date>> {
+        let months = self.starts_on.year() as i64 * 12 + u8::from(self.starts_on.month()) as i64
+            - 1
+            + months;
+        let year = i32::try_from(months.div_euclid(12)).ok()?;
+        let month = Month::try_from(months.rem_euclid(12) as u8 + 1).ok()?;
+
+        if year > Date::MAX.year() {
+            return None;
+        }
+
+        Some(Date::from_calendar_date(year, month, self.starts_on.day()).ok())
+    }
+}
+
+fn parse_frequency(value: &str) -> AppResult<RecurrenceFrequency> {
+    match value.to_ascii_uppercase().as_str() {
+        "DAILY" => Ok(RecurrenceFrequency::Daily),
+        "WEEKLY" => Ok(RecurrenceFrequency::Weekly),
+        "MONTHLY" => Ok(RecurrenceFrequency::Monthly),
+        "YEARLY" => Ok(RecurrenceFrequency::Yearly),
+        _ => Err(invalid_rrule(String::from(
+            "FREQ must be DAILY, WEEKLY, MONTHLY or YEARLY",
+        ))),
+    }
+}
+
+/// Values are stored as integers, so they are kept within their range.
+fn parse_positive(name: &str, value: &str) -> AppResult<u32> {
+    match value.parse::<u32>() {
+        Ok(number) if number > 0 && number <= i32::MAX as u32 => Ok(number),
+        _ => Err(invalid_rrule(format!("{} must be a positive number", name))),
+    }
+}
+
+/// Accepts a date formatted as `YYYYMMDD`, the time of a `YYYYMMDDTHHMMSSZ` date time is ignored.
+fn parse_until(value: &str) -> AppResult<Date> {
+    let invalid = || invalid_rrule(String::from("UNTIL must be formatted as YYYYMMDD"));
+
+    if value.len() != 8 && value.as_bytes().get(8) != Some(&b'T') {
+        return Err(invalid());
+    }
+
+    let digits = value.get(..8).ok_or_else(invalid)?;
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let year = digits[..4].parse::<i32>().map_err(|_| invalid())?;
+    let month = digits[4..6].parse::<u8>().map_err(|_| invalid())?;
+    let day = digits[6..].parse::<u8>().map_err(|_| invalid())?;
+    let month = Month::try_from(month).map_err(|_| invalid())?;
+
+    Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+}
+
+fn invalid_rrule(reason: String) -> Error {
+    error!("invalid recurrence rule: {}", reason);
+    Error::BadRequest(format!("invalid recurrence rule, {}", reason))
+}
@@ -0,0 +1,196 @@
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use data_encoding::{BASE64, BASE64URL_NOPAD};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use sqlx::types::time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::config::JwtAlgorithm;
+use crate::database::signing_key::SigningKey;
+use crate::server::error::{AppResult, Error};
+
+const RSA_KEY_BITS: usize = 2048;
+
+/// A freshly generated key pair, ready to be stored.
+pub struct GeneratedSigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    /// Base64 encoded DER of the private key, PKCS#8 for EdDSA and PKCS#1 for RSA keys.
+    pub private_key: String,
+    pub public_jwk: Jwk,
+}
+
+/// Generates a key pair for the configured algorithm. RSA key generation is slow, call this off the async runtime.
+pub fn generate_signing_key(algorithm: JwtAlgorithm) -> AppResult<GeneratedSigningKey> {
+    let kid = Uuid::new_v4().to_string();
+
+    let (algorithm, private_key, parameters) = match algorithm {
+        JwtAlgorithm::EdDsa => {
+            let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|_| {
+                Error::InternalServerErrorWithContext(String::from(
+                    "could not generate an Ed25519 key",
+                ))
+            })?;
+            let key_pair = Ed25519KeyPair::from_pkcs8(document.as_ref())
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: BASE64URL_NOPAD.encode(key_pair.public_key().as_ref()),
+            });
+
+            (
+                Algorithm::EdDSA,
+                BASE64.encode(document.as_ref()),
+                parameters,
+            )
+        }
+        JwtAlgorithm::Rs256 => {
+            let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), RSA_KEY_BITS)
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+            let der = private_key
+                .to_pkcs1_der()
+                .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: BASE64URL_NOPAD.encode(&private_key.n().to_bytes_be()),
+                e: BASE64URL_NOPAD.encode(&private_key.e().to_bytes_be()),
+            });
+
+            (Algorithm::RS256, BASE64.encode(der.as_bytes()), parameters)
+        }
+        JwtAlgorithm::Hs256 => {
+            return Err(Error::InternalServerErrorWithContext(String::from(
+                "HS256 tokens are signed with the access token secret",
+            )))
+        }
+    };
+
+    let public_jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            algorithm: Some(algorithm),
+            key_id: Some(kid.clone()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    };
+
+    Ok(GeneratedSigningKey {
+        kid,
+        algorithm,
+        private_key,
+        public_jwk,
+    })
+}
+
+struct LoadedKey {
+    kid: String,
+    algorithm: Algorithm,
+    activates_at: OffsetDateTime,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_jwk: Jwk,
+}
+
+impl TryFrom<SigningKey> for LoadedKey {
+    type Error = Error;
+
+    fn try_from(key: SigningKey) -> AppResult<Self> {
+        let algorithm = Algorithm::from_str(&key.algorithm)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        let der = BASE64
+            .decode(key.private_key.as_bytes())
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        let encoding_key = match algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_der(&der),
+            Algorithm::RS256 => EncodingKey::from_rsa_der(&der),
+            _ => {
+                return Err(Error::InternalServerErrorWithContext(format!(
+                    "signing key {} uses unsupported algorithm {:?}",
+                    key.kid, algorithm
+                )))
+            }
+        };
+
+        let public_jwk: Jwk = serde_json::from_str(&key.public_jwk)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+        let decoding_key = DecodingKey::from_jwk(&public_jwk)
+            .map_err(|err| Error::InternalServerErrorWithContext(err.to_string()))?;
+
+        Ok(Self {
+            kid: key.kid,
+            algorithm,
+            activates_at: key.activates_at,
+            encoding_key,
+            decoding_key,
+            public_jwk,
+        })
+    }
+}
+
+/// The signing keys currently in use, kept in memory so tokens can be signed and verified without a database round trip.
+#[derive(Default)]
+pub struct KeyRing {
+    keys: RwLock<Vec<LoadedKey>>,
+}
+
+impl KeyRing {
+    /// Replaces the keys held by the ring, expects them ordered by activation, the most recent first.
+    pub fn load(&self, keys: Vec<SigningKey>) -> AppResult<()> {
+        let loaded_keys = keys
+            .into_iter()
+            .map(LoadedKey::try_from)
+            .collect::<AppResult<Vec<LoadedKey>>>()?;
+
+        *self.keys.write().unwrap() = loaded_keys;
+
+        Ok(())
+    }
+
+    /// Returns the most recently activated key, keys published ahead of their activation are skipped.
+    pub fn signing_key(&self) -> Option<(String, Algorithm, EncodingKey)> {
+        let now = OffsetDateTime::now_utc();
+
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find(|key| key.activates_at <= now)
+            .map(|key| (key.kid.clone(), key.algorithm, key.encoding_key.clone()))
+    }
+
+    pub fn decoding_key(&self, kid: &str) -> Option<(Algorithm, DecodingKey)> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find(|key| key.kid == kid)
+            .map(|key| (key.algorithm, key.decoding_key.clone()))
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self
+                .keys
+                .read()
+                .unwrap()
+                .iter()
+                .map(|key| key.public_jwk.clone())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Generates a random, URL safe token to be handed out once, e.g. inside an email link.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Hashes a token for storage. Tokens are high entropy, so a fast digest is sufficient here
+/// and we can look the token up by its hash.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Generates a recovery code short enough to type by hand, formatted as `xxxxxx-xxxxxx`.
+pub fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 6];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    let code: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    format!("{}-{}", &code[..6], &code[6..])
+}
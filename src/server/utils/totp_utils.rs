@@ -0,0 +1,85 @@
+//! Time-based one-time passwords as described in RFC 6238, using the parameters
+//! authenticator apps expect by default: HMAC-SHA1, 6 digits and a 30 second step.
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+const STEP_SECONDS: u64 = 30;
+const DIGITS: u32 = 6;
+/// Number of steps either side of the current one that are still accepted, to tolerate clock drift.
+const ALLOWED_DRIFT_STEPS: u64 = 1;
+
+/// Generates a random 160 bit secret, base32 encoded as expected by authenticator apps.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// Builds the `otpauth://` URI authenticator apps consume, usually rendered as a QR code.
+pub fn otpauth_uri(secret: &str, issuer: &str, account: &str) -> String {
+    let issuer = encode_uri_component(issuer);
+
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        issuer,
+        encode_uri_component(account),
+        secret,
+        issuer,
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+/// Computes the code for the step containing `unix_time`.
+pub fn generate_code(secret: &str, unix_time: u64) -> Option<String> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+
+    Some(code_for_step(&key, unix_time / STEP_SECONDS))
+}
+
+/// Verifies `code` against the steps around `unix_time`, returning the matched step so callers
+/// can reject a code that was already used.
+pub fn verify_code(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let current_step = unix_time / STEP_SECONDS;
+
+    (current_step.saturating_sub(ALLOWED_DRIFT_STEPS)..=current_step + ALLOWED_DRIFT_STEPS)
+        .find(|step| code_for_step(&key, *step) == code)
+}
+
+fn code_for_step(key: &[u8], step: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("hmac accepts keys of any size");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // dynamic truncation, see RFC 4226 section 5.3
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+fn encode_uri_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
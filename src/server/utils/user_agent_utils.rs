@@ -0,0 +1,32 @@
+use woothee::parser::Parser;
+
+/// The families of browser, operating system and device a user agent belongs to, unknown ones are left empty.
+#[derive(Debug, Default, PartialEq)]
+pub struct UserAgentInfo {
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device: Option<String>,
+}
+
+pub fn parse_user_agent(user_agent: &str) -> UserAgentInfo {
+    let parsed = match Parser::new().parse(user_agent) {
+        Some(parsed) => parsed,
+        None => return UserAgentInfo::default(),
+    };
+
+    let known = |value: &str| (value != woothee::woothee::VALUE_UNKNOWN).then(|| value.to_string());
+
+    let device = match parsed.category {
+        "pc" => Some("desktop"),
+        "smartphone" | "mobilephone" => Some("mobile"),
+        "crawler" => Some("bot"),
+        "appliance" | "misc" => Some("other"),
+        _ => None,
+    };
+
+    UserAgentInfo {
+        browser: known(parsed.name),
+        os: known(parsed.os),
+        device: device.map(String::from),
+    }
+}
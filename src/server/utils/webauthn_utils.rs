@@ -0,0 +1,277 @@
+//! Relying party checks of the WebAuthn registration and authentication ceremonies, see
+//! https://www.w3.org/TR/webauthn-2/#sctn-rp-operations. We request `none` attestation, so
+//! attestation statements are not verified and any authenticator is trusted.
+
+use std::io::Cursor;
+
+use anyhow::{bail, ensure, Context};
+use ciborium::value::Value;
+use data_encoding::BASE64URL_NOPAD;
+use rand::RngCore;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+pub const REGISTRATION_CEREMONY: &str = "registration";
+pub const AUTHENTICATION_CEREMONY: &str = "authentication";
+
+/// COSE algorithms we accept, in order of preference: ES256, EdDSA and RS256.
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [ES256, EDDSA, RS256];
+
+const ES256: i64 = -7;
+const EDDSA: i64 = -8;
+const RS256: i64 = -257;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// Generates a random 256 bit challenge, base64url encoded as browsers expect it.
+pub fn generate_challenge() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    encode(&bytes)
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    BASE64URL_NOPAD.encode(bytes)
+}
+
+/// Decodes the base64url fields of ceremony responses, tolerating the padding some clients add.
+pub fn decode(value: &str) -> anyhow::Result<Vec<u8>> {
+    BASE64URL_NOPAD
+        .decode(value.trim_end_matches('=').as_bytes())
+        .context("value is not valid base64url")
+}
+
+/// The user handle a passkey is created with, it is handed back when signing in with a discoverable credential.
+pub fn user_handle(user_id: &Uuid) -> String {
+    encode(user_id.as_bytes())
+}
+
+#[derive(Deserialize, Debug)]
+struct ClientData {
+    #[serde(rename = "type")]
+    ceremony_type: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Checks the client data collected by the browser, returning the challenge it was created for.
+pub fn verify_client_data(
+    client_data_json: &[u8],
+    expected_type: &str,
+    expected_origin: &str,
+) -> anyhow::Result<String> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).context("client data is not valid JSON")?;
+
+    ensure!(
+        client_data.ceremony_type == expected_type,
+        "client data is for a {} ceremony",
+        client_data.ceremony_type
+    );
+    ensure!(
+        client_data.origin == expected_origin,
+        "client data was collected on {}",
+        client_data.origin
+    );
+
+    Ok(client_data.challenge)
+}
+
+/// A credential created during registration, ready to be stored.
+#[derive(Debug)]
+pub struct RegisteredCredential {
+    pub credential_id: String,
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+/// Verifies the attestation object of a registration, extracting the new credential.
+pub fn verify_registration(
+    attestation_object: &[u8],
+    raw_credential_id: &[u8],
+    rp_id: &str,
+) -> anyhow::Result<RegisteredCredential> {
+    let attestation: Value = ciborium::de::from_reader(attestation_object)
+        .context("attestation object is not valid CBOR")?;
+
+    let auth_data = map_entry(&attestation, Value::Text(String::from("authData")))
+        .and_then(Value::as_bytes)
+        .context("attestation object is missing the authenticator data")?;
+
+    let authenticator_data = AuthenticatorData::parse(auth_data)?;
+    authenticator_data.verify(rp_id)?;
+
+    let (credential_id, public_key) = authenticator_data
+        .attested_credential
+        .context("authenticator data is missing the attested credential")?;
+
+    ensure!(
+        credential_id == raw_credential_id,
+        "attested credential does not match the credential ID"
+    );
+
+    // refuse keys we would not be able to verify assertions with later on
+    CosePublicKey::parse(&public_key)?;
+
+    Ok(RegisteredCredential {
+        credential_id: encode(&credential_id),
+        public_key,
+        sign_count: authenticator_data.sign_count,
+    })
+}
+
+/// Verifies the signature of an assertion made with a stored credential, returning the authenticator's signature counter.
+pub fn verify_assertion(
+    public_key: &[u8],
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+    rp_id: &str,
+) -> anyhow::Result<u32> {
+    let parsed_authenticator_data = AuthenticatorData::parse(authenticator_data)?;
+    parsed_authenticator_data.verify(rp_id)?;
+
+    let mut signed_data = authenticator_data.to_vec();
+    signed_data.extend_from_slice(&Sha256::digest(client_data_json));
+
+    CosePublicKey::parse(public_key)?.verify(&signed_data, signature)?;
+
+    Ok(parsed_authenticator_data.sign_count)
+}
+
+struct AuthenticatorData {
+    rp_id_hash: Vec<u8>,
+    flags: u8,
+    sign_count: u32,
+    attested_credential: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl AuthenticatorData {
+    fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        ensure!(bytes.len() >= 37, "authenticator data is too short");
+
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes(bytes[33..37].try_into()?);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+            // the AAGUID identifying the authenticator model is skipped, we do not verify attestation
+            ensure!(bytes.len() >= 55, "attested credential data is too short");
+            let credential_id_length = u16::from_be_bytes(bytes[53..55].try_into()?) as usize;
+            let public_key_start = 55 + credential_id_length;
+            ensure!(
+                bytes.len() > public_key_start,
+                "attested credential data is too short"
+            );
+
+            // the key is followed by extensions when present, so only take the bytes of the first CBOR item
+            let mut cursor = Cursor::new(&bytes[public_key_start..]);
+            let _: Value = ciborium::de::from_reader(&mut cursor)
+                .context("credential public key is not valid CBOR")?;
+            let public_key_end = public_key_start + cursor.position() as usize;
+
+            Some((
+                bytes[55..public_key_start].to_vec(),
+                bytes[public_key_start..public_key_end].to_vec(),
+            ))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash: bytes[..32].to_vec(),
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    fn verify(&self, rp_id: &str) -> anyhow::Result<()> {
+        ensure!(
+            self.rp_id_hash == Sha256::digest(rp_id.as_bytes()).to_vec(),
+            "authenticator data is for another relying party"
+        );
+        ensure!(self.flags & FLAG_USER_PRESENT != 0, "user was not present");
+        // passkeys replace the password, so the authenticator has to verify the user itself
+        ensure!(
+            self.flags & FLAG_USER_VERIFIED != 0,
+            "user was not verified"
+        );
+
+        Ok(())
+    }
+}
+
+enum CosePublicKey {
+    Es256 { x: Vec<u8>, y: Vec<u8> },
+    EdDsa { x: Vec<u8> },
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl CosePublicKey {
+    fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        let key: Value =
+            ciborium::de::from_reader(bytes).context("public key is not valid CBOR")?;
+
+        let algorithm = cose_integer(&key, 3).context("public key is missing its algorithm")?;
+        let parameter = |label: i64| {
+            map_entry(&key, Value::Integer(label.into()))
+                .and_then(Value::as_bytes)
+                .cloned()
+                .with_context(|| format!("public key is missing parameter {}", label))
+        };
+
+        match algorithm {
+            ES256 => Ok(Self::Es256 {
+                x: parameter(-2)?,
+                y: parameter(-3)?,
+            }),
+            EDDSA => Ok(Self::EdDsa { x: parameter(-2)? }),
+            RS256 => Ok(Self::Rs256 {
+                n: parameter(-1)?,
+                e: parameter(-2)?,
+            }),
+            _ => bail!("public key algorithm {} is not supported", algorithm),
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> anyhow::Result<()> {
+        let verified = match self {
+            Self::Es256 { x, y } => {
+                let mut point = vec![0x04];
+                point.extend_from_slice(x);
+                point.extend_from_slice(y);
+
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, signature)
+            }
+            Self::EdDsa { x } => {
+                UnparsedPublicKey::new(&signature::ED25519, x).verify(message, signature)
+            }
+            Self::Rs256 { n, e } => RsaPublicKeyComponents { n, e }.verify(
+                &signature::RSA_PKCS1_2048_8192_SHA256,
+                message,
+                signature,
+            ),
+        };
+
+        verified.map_err(|_| anyhow::anyhow!("assertion signature is invalid"))
+    }
+}
+
+fn map_entry(map: &Value, key: Value) -> Option<&Value> {
+    map.as_map()?
+        .iter()
+        .find(|(entry_key, _)| *entry_key == key)
+        .map(|(_, value)| value)
+}
+
+fn cose_integer(map: &Value, label: i64) -> Option<i64> {
+    let value = map_entry(map, Value::Integer(label.into()))?.as_integer()?;
+
+    i64::try_from(value).ok()
+}
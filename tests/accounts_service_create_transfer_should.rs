@@ -0,0 +1,168 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        transaction::{DynTransactionsRepository, Transaction, TransactionType, TransferFields},
+    },
+    mocks::AccountsServiceTestFixture,
+    server::{
+        dtos::account_dto::TransferCreateDto,
+        error::Error,
+        services::account_services::{AccountsService, AccountsServiceTrait},
+    },
+};
+use rust_decimal::Decimal;
+use time::macros::date;
+use uuid::{uuid, Uuid};
+
+const SAVINGS_ACCOUNT_ID: Uuid = uuid!("2c4e6a8b-0d1f-4b3c-9e5a-7c9e1b3d5f7a");
+const TRANSFER_ID: Uuid = uuid!("5a7c9e1b-3d5f-4a7b-8c9d-0e2f4a6b8c0d");
+
+fn build_service(fixture: AccountsServiceTestFixture) -> AccountsService {
+    AccountsService::new(
+        Arc::new(fixture.mock_repository) as DynAccountsRepository,
+        Arc::new(fixture.mock_transactions_repository) as DynTransactionsRepository,
+    )
+}
+
+fn stub_request(to_account_id: Uuid) -> TransferCreateDto {
+    TransferCreateDto {
+        from_account_id: Some(Account::default().id),
+        to_account_id: Some(to_account_id),
+        amount: Some(Decimal::from_str("250.00").unwrap()),
+        date: Some(date!(2023 - 07 - 31)),
+        note: None,
+    }
+}
+
+fn stub_savings_account(currency: &str) -> Account {
+    Account {
+        id: SAVINGS_ACCOUNT_ID,
+        currency: String::from(currency),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn record_both_legs_of_the_transfer() {
+    // arrange
+    let mut fixture = AccountsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_account_by_id()
+        .with(eq(Account::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Account::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_account_by_id()
+        .with(eq(SAVINGS_ACCOUNT_ID))
+        .times(1)
+        .return_once(move |_| Ok(Some(stub_savings_account("USD"))));
+
+    fixture
+        .mock_transactions_repository
+        .expect_create_transfer()
+        .withf(|_, fields: &TransferFields| {
+            fields.from_account_id == Account::default().id
+                && fields.to_account_id == SAVINGS_ACCOUNT_ID
+                && fields.currency == "USD"
+        })
+        .times(1)
+        .return_once(move |_, fields| {
+            Ok((
+                Transaction {
+                    amount: fields.amount,
+                    tx_type: TransactionType::TransferOut,
+                    account_id: Some(fields.from_account_id),
+                    transfer_id: Some(TRANSFER_ID),
+                    ..Default::default()
+                },
+                Transaction {
+                    amount: fields.amount,
+                    tx_type: TransactionType::TransferIn,
+                    account_id: Some(fields.to_account_id),
+                    transfer_id: Some(TRANSFER_ID),
+                    ..Default::default()
+                },
+            ))
+        });
+
+    let accounts_service = build_service(fixture);
+
+    // act
+    let response = accounts_service
+        .create_transfer(Account::default().user_id, stub_request(SAVINGS_ACCOUNT_ID))
+        .await;
+
+    // assert
+    let transfer = response.unwrap();
+    assert_eq!(transfer.transfer_id, TRANSFER_ID);
+    assert_eq!(transfer.outgoing.tx_type, TransactionType::TransferOut);
+    assert_eq!(transfer.incoming.account_id, Some(SAVINGS_ACCOUNT_ID));
+}
+
+#[tokio::test]
+async fn return_bad_request_for_the_same_account() {
+    // arrange
+    let mut fixture = AccountsServiceTestFixture::default();
+
+    fixture.mock_repository.expect_get_account_by_id().never();
+    fixture
+        .mock_transactions_repository
+        .expect_create_transfer()
+        .never();
+
+    let accounts_service = build_service(fixture);
+
+    // act
+    let response = accounts_service
+        .create_transfer(
+            Account::default().user_id,
+            stub_request(Account::default().id),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn return_bad_request_for_accounts_of_different_currencies() {
+    // arrange
+    let mut fixture = AccountsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_account_by_id()
+        .with(eq(Account::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Account::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_account_by_id()
+        .with(eq(SAVINGS_ACCOUNT_ID))
+        .times(1)
+        .return_once(move |_| Ok(Some(stub_savings_account("EUR"))));
+
+    fixture
+        .mock_transactions_repository
+        .expect_create_transfer()
+        .never();
+
+    let accounts_service = build_service(fixture);
+
+    // act
+    let response = accounts_service
+        .create_transfer(Account::default().user_id, stub_request(SAVINGS_ACCOUNT_ID))
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
@@ -0,0 +1,236 @@
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        api_key::{ApiKey, DynApiKeysRepository},
+        role::{DynRolesRepository, Role},
+    },
+    mocks::ApiKeysServiceTestFixture,
+    server::{
+        error::Error,
+        services::api_key_services::{ApiKeysService, ApiKeysServiceTrait},
+        utils::token_utils,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+const STUB_KEY: &str = "rak_0123456789ab_stubsecret";
+
+fn stub_api_key(exp: SystemTime) -> ApiKey {
+    ApiKey {
+        prefix: String::from("0123456789ab"),
+        key_hash: token_utils::hash_token(STUB_KEY),
+        exp: OffsetDateTime::from(exp),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn return_user_id_when_key_has_required_scope() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_api_key_by_prefix()
+        .with(eq("0123456789ab"))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(stub_api_key(
+                SystemTime::now().add(Duration::from_secs(60)),
+            )))
+        });
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_repository
+        .expect_touch_api_key()
+        .with(eq(uuid!("6c1f8e3a-2b7d-4e95-9a0c-4d3b7f1e8a62")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .authenticate(
+            String::from(STUB_KEY),
+            Some(String::from("categories:read")),
+        )
+        .await;
+
+    // assert
+    assert_eq!(
+        response.unwrap(),
+        uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")
+    );
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_secret_does_not_match() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_api_key_by_prefix()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(stub_api_key(
+                SystemTime::now().add(Duration::from_secs(60)),
+            )))
+        });
+
+    fixture.mock_repository.expect_touch_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .authenticate(
+            String::from("rak_0123456789ab_guessedsecret"),
+            Some(String::from("categories:read")),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_key_has_expired() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_api_key_by_prefix()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(stub_api_key(
+                SystemTime::now() - Duration::from_secs(60),
+            )))
+        });
+
+    fixture.mock_repository.expect_touch_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .authenticate(
+            String::from(STUB_KEY),
+            Some(String::from("categories:read")),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_forbidden_when_key_lacks_required_scope() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_api_key_by_prefix()
+        .times(2)
+        .returning(move |_| {
+            Ok(Some(stub_api_key(
+                SystemTime::now().add(Duration::from_secs(60)),
+            )))
+        });
+
+    fixture.mock_repository.expect_touch_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let write_response = api_keys_service
+        .authenticate(
+            String::from(STUB_KEY),
+            Some(String::from("categories:write")),
+        )
+        .await;
+    let unscoped_response = api_keys_service
+        .authenticate(String::from(STUB_KEY), None)
+        .await;
+
+    // assert
+    assert!(matches!(write_response, Err(Error::Forbidden)));
+    assert!(matches!(unscoped_response, Err(Error::Forbidden)));
+}
+
+#[tokio::test]
+async fn return_forbidden_when_owner_no_longer_holds_permission() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_api_key_by_prefix()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(stub_api_key(
+                SystemTime::now().add(Duration::from_secs(60)),
+            )))
+        });
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![Role {
+                permissions: vec![String::from("users:read")],
+                ..Default::default()
+            }])
+        });
+
+    fixture.mock_repository.expect_touch_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .authenticate(
+            String::from(STUB_KEY),
+            Some(String::from("categories:read")),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
@@ -0,0 +1,153 @@
+use std::sync::{Arc, Mutex};
+
+use rest_api::{
+    database::{
+        api_key::{ApiKey, DynApiKeysRepository},
+        role::{DynRolesRepository, Role},
+    },
+    mocks::ApiKeysServiceTestFixture,
+    server::{
+        dtos::api_key_dto::CreateApiKeyDto,
+        error::Error,
+        services::api_key_services::{ApiKeysService, ApiKeysServiceTrait},
+        utils::{api_key_utils, token_utils},
+    },
+};
+
+#[tokio::test]
+async fn store_only_the_hash_of_the_returned_key() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+    let stored_hash = Arc::new(Mutex::new(String::new()));
+    let captured_hash = stored_hash.clone();
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_repository
+        .expect_create_api_key()
+        .times(1)
+        .returning(move |user_id, name, prefix, key_hash, scopes, exp| {
+            *captured_hash.lock().unwrap() = String::from(key_hash);
+
+            Ok(ApiKey {
+                user_id,
+                name: String::from(name),
+                prefix: String::from(prefix),
+                key_hash: String::from(key_hash),
+                scopes,
+                exp: *exp,
+                ..Default::default()
+            })
+        });
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .create_api_key(
+            ApiKey::default().user_id,
+            CreateApiKeyDto {
+                name: Some(String::from("stub name")),
+                scopes: Some(vec![
+                    String::from("categories:write"),
+                    String::from("categories:read"),
+                    String::from("categories:write"),
+                ]),
+                expires_in_days: Some(30),
+            },
+        )
+        .await;
+
+    // assert
+    let created_api_key = response.unwrap();
+    assert_eq!(
+        api_key_utils::parse_prefix(&created_api_key.key),
+        Some(created_api_key.api_key.prefix.as_str())
+    );
+    assert_eq!(
+        created_api_key.api_key.scopes,
+        vec!["categories:read", "categories:write"]
+    );
+    assert_eq!(
+        *stored_hash.lock().unwrap(),
+        token_utils::hash_token(&created_api_key.key)
+    );
+}
+
+#[tokio::test]
+async fn return_bad_request_for_unknown_scope() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture.mock_repository.expect_create_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .create_api_key(
+            ApiKey::default().user_id,
+            CreateApiKeyDto {
+                name: Some(String::from("stub name")),
+                scopes: Some(vec![String::from("users:admin")]),
+                expires_in_days: None,
+            },
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn return_forbidden_for_scope_the_owner_does_not_hold() {
+    // arrange
+    let mut fixture = ApiKeysServiceTestFixture::default();
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![Role {
+                permissions: vec![String::from("categories:read")],
+                ..Default::default()
+            }])
+        });
+
+    fixture.mock_repository.expect_create_api_key().never();
+
+    let api_keys_service = ApiKeysService::new(
+        Arc::new(fixture.mock_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        fixture.config,
+    );
+
+    // act
+    let response = api_keys_service
+        .create_api_key(
+            ApiKey::default().user_id,
+            CreateApiKeyDto {
+                name: Some(String::from("stub name")),
+                scopes: Some(vec![String::from("categories:write")]),
+                expires_in_days: None,
+            },
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
@@ -0,0 +1,115 @@
+use axum::body::Body;
+use axum::http::header::AUTHORIZATION;
+use axum::http::{Request, StatusCode};
+use axum::Extension;
+use rest_api::{
+    database::role::AccessGrants,
+    mocks::ServicesTestFixture,
+    server::{
+        api, dtos::user_dto::ResponseUserDto, extractors::IMPERSONATED_BY_HEADER,
+        utils::jwt_utils::AccessToken,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use tower::ServiceExt;
+use uuid::{uuid, Uuid};
+
+fn stub_access_token(actor_id: Option<Uuid>) -> AccessToken {
+    AccessToken {
+        user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+        jti: uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c"),
+        grants: AccessGrants {
+            roles: vec![],
+            permissions: vec![String::from("categories:read")],
+        },
+        actor_id,
+        issued_at: OffsetDateTime::now_utc(),
+        expires_at: OffsetDateTime::now_utc(),
+    }
+}
+
+fn stub_fixture(access_token: AccessToken) -> ServicesTestFixture {
+    let mut fixture = ServicesTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_access_from_token()
+        .times(1)
+        .return_once(move |_| Ok(access_token));
+
+    fixture
+        .mock_sessions_services
+        .expect_ensure_access_token_active()
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_impersonation_services
+        .expect_record_request()
+        .returning(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_users_services
+        .expect_get_current_user()
+        .times(1)
+        .return_once(move |user_id| {
+            Ok(ResponseUserDto {
+                id: user_id,
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_categories_services
+        .expect_get_categories()
+        .times(1)
+        .return_once(move |_| Ok(vec![]));
+
+    fixture
+}
+
+fn stub_request() -> Request<Body> {
+    Request::builder()
+        .uri("/categories")
+        .header(AUTHORIZATION, "Bearer stub-token")
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn mark_responses_to_impersonation_tokens_with_the_admin() {
+    // arrange
+    let admin_id = uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9");
+    let services = stub_fixture(stub_access_token(Some(admin_id))).into_services();
+
+    // act
+    let response = api::app()
+        .layer(Extension(services))
+        .oneshot(stub_request())
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(IMPERSONATED_BY_HEADER).unwrap(),
+        admin_id.to_string().as_str()
+    );
+}
+
+#[tokio::test]
+async fn leave_responses_to_regular_tokens_unmarked() {
+    // arrange
+    let services = stub_fixture(stub_access_token(None)).into_services();
+
+    // act
+    let response = api::app()
+        .layer(Extension(services))
+        .oneshot(stub_request())
+        .await
+        .unwrap();
+
+    // assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(IMPERSONATED_BY_HEADER).is_none());
+}
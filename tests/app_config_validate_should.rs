@@ -0,0 +1,129 @@
+use rest_api::mocks::stub_config_with;
+
+#[test]
+fn accept_default_lifetimes() {
+    assert!(stub_config_with(&[]).validate().is_ok());
+}
+
+#[test]
+fn accept_idle_timeout_equal_to_max_age() {
+    let config = stub_config_with(&[
+        "--session-idle-timeout-minutes=1440",
+        "--session-max-age-days=1",
+    ]);
+
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn reject_idle_timeout_longer_than_max_age() {
+    let config = stub_config_with(&[
+        "--session-idle-timeout-minutes=1441",
+        "--session-max-age-days=1",
+    ]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn accept_access_token_ttl_equal_to_idle_timeout() {
+    let config = stub_config_with(&[
+        "--access-token-ttl-seconds=60",
+        "--mfa-token-ttl-seconds=30",
+        "--session-idle-timeout-minutes=1",
+    ]);
+
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn reject_access_token_ttl_longer_than_idle_timeout() {
+    let config = stub_config_with(&[
+        "--access-token-ttl-seconds=61",
+        "--session-idle-timeout-minutes=1",
+    ]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn reject_access_token_ttl_of_zero() {
+    let config = stub_config_with(&["--access-token-ttl-seconds=0"]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn accept_mfa_token_ttl_of_half_the_access_token_ttl() {
+    let config = stub_config_with(&[
+        "--access-token-ttl-seconds=600",
+        "--mfa-token-ttl-seconds=300",
+    ]);
+
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn reject_mfa_token_ttl_longer_than_half_the_access_token_ttl() {
+    let config = stub_config_with(&[
+        "--access-token-ttl-seconds=600",
+        "--mfa-token-ttl-seconds=301",
+    ]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn reject_mfa_token_ttl_of_zero() {
+    let config = stub_config_with(&["--mfa-token-ttl-seconds=0"]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn reject_access_token_ttl_outliving_signing_key_grace_period() {
+    let config = stub_config_with(&["--access-token-ttl-seconds=7200", "--jwt-key-grace-hours=1"]);
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn ignore_signing_key_grace_period_for_shared_secret() {
+    let config = stub_config_with(&[
+        "--access-token-ttl-seconds=7200",
+        "--jwt-key-grace-hours=1",
+        "--jwt-algorithm=hs256",
+    ]);
+
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn reject_lifetimes_overflowing_when_converted_to_seconds() {
+    for setting in [
+        "--session-idle-timeout-minutes=18446744073709551615",
+        "--session-max-age-days=18446744073709551615",
+        "--account-deletion-grace-days=18446744073709551615",
+    ] {
+        assert!(
+            stub_config_with(&[setting]).validate().is_err(),
+            "{} should be rejected",
+            setting
+        );
+    }
+}
+
+#[test]
+fn reject_lifetimes_too_long_to_be_added_to_the_current_time() {
+    for setting in [
+        "--session-max-age-days=100000000",
+        "--impersonation-ttl-seconds=1000000000000",
+        "--account-deletion-grace-days=100000000",
+    ] {
+        assert!(
+            stub_config_with(&[setting]).validate().is_err(),
+            "{} should be rejected",
+            setting
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use argon2::{Config, Variant};
+use rest_api::{
+    mocks::stub_config_with,
+    server::utils::argon_utils::{ArgonSecurityUtil, ArgonUtil},
+};
+use tokio::task::JoinSet;
+
+/// Keeps hashing cheap, the parameters themselves are what is under test.
+fn build_util(memory_cost: &str) -> ArgonSecurityUtil {
+    ArgonSecurityUtil::new(stub_config_with(&[
+        &format!("--argon-memory-cost={}", memory_cost),
+        "--argon-time-cost=1",
+    ]))
+}
+
+#[tokio::test]
+async fn hash_same_password_with_different_salts() {
+    // arrange
+    let argon_util = build_util("64");
+
+    // act
+    let first_hash = argon_util.hash_password("stub password").await.unwrap();
+    let second_hash = argon_util.hash_password("stub password").await.unwrap();
+
+    // assert
+    assert_ne!(first_hash, second_hash);
+    assert!(argon_util
+        .verify_password(&first_hash, String::from("stub password"))
+        .await
+        .unwrap());
+    assert!(argon_util
+        .verify_password(&second_hash, String::from("stub password"))
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn not_need_rehash_when_hash_uses_configured_parameters() {
+    // arrange
+    let argon_util = build_util("64");
+    let hash = argon_util.hash_password("stub password").await.unwrap();
+
+    // act
+    let needs_rehash = argon_util.needs_rehash(&hash);
+
+    // assert
+    assert!(!needs_rehash);
+}
+
+#[tokio::test]
+async fn need_rehash_when_cost_parameters_were_raised() {
+    // arrange
+    let hash = build_util("64")
+        .hash_password("stub password")
+        .await
+        .unwrap();
+
+    // act
+    let needs_rehash = build_util("128").needs_rehash(&hash);
+
+    // assert
+    assert!(needs_rehash);
+}
+
+#[tokio::test]
+async fn need_rehash_when_hash_uses_legacy_salt() {
+    // arrange
+    let argon_util = build_util("64");
+    let config = Config {
+        variant: Variant::Argon2id,
+        mem_cost: 64,
+        time_cost: 1,
+        ..Config::default()
+    };
+    let hash = argon2::hash_encoded(b"stub password", b"stubsaltstubsalt", &config).unwrap();
+
+    // act
+    let needs_rehash = argon_util.needs_rehash(&hash);
+
+    // assert
+    assert!(argon_util
+        .verify_password(&hash, String::from("stub password"))
+        .await
+        .unwrap());
+    assert!(needs_rehash);
+}
+
+#[tokio::test]
+async fn complete_every_hash_queued_beyond_max_concurrency() {
+    // arrange
+    let argon_util = Arc::new(ArgonSecurityUtil::new(stub_config_with(&[
+        "--argon-memory-cost=64",
+        "--argon-time-cost=1",
+        "--argon-max-concurrency=1",
+    ])));
+    let mut hashes = JoinSet::new();
+
+    // act
+    for _ in 0..4 {
+        let argon_util = argon_util.clone();
+        hashes.spawn(async move { argon_util.hash_password("stub password").await });
+    }
+
+    // assert
+    let mut completed = 0;
+    while let Some(hash) = hashes.join_next().await {
+        assert!(hash.unwrap().is_ok());
+        completed += 1;
+    }
+    assert_eq!(completed, 4);
+}
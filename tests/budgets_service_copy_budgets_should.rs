@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        budget::{Budget, DynBudgetsRepository},
+        category::DynCategoriesRepository,
+        user::DynUsersRepository,
+    },
+    mocks::BudgetsServiceTestFixture,
+    server::{
+        dtos::budget_dto::BudgetCopyDto,
+        error::Error,
+        services::budget_services::{BudgetsService, BudgetsServiceTrait},
+    },
+};
+use time::macros::date;
+
+fn build_service(fixture: BudgetsServiceTestFixture) -> BudgetsService {
+    BudgetsService::new(
+        Arc::new(fixture.mock_repository) as DynBudgetsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+    )
+}
+
+#[tokio::test]
+async fn copy_budgets_and_return_those_of_the_target_month() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+    let user_id = Budget::default().user_id;
+
+    fixture
+        .mock_repository
+        .expect_copy_budgets()
+        .with(
+            eq(user_id),
+            eq(date!(2023 - 06 - 01)),
+            eq(date!(2023 - 07 - 01)),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(1));
+
+    fixture
+        .mock_repository
+        .expect_get_budgets()
+        .with(eq(user_id), eq(date!(2023 - 07 - 01)))
+        .times(1)
+        .return_once(move |_, _| Ok(vec![Budget::default()]));
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .copy_budgets(
+            user_id,
+            String::from("2023-07"),
+            BudgetCopyDto {
+                from_month: Some(String::from("2023-06")),
+            },
+        )
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn return_bad_request_when_copying_onto_the_same_month() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture.mock_repository.expect_copy_budgets().never();
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .copy_budgets(
+            Budget::default().user_id,
+            String::from("2023-07"),
+            BudgetCopyDto {
+                from_month: Some(String::from("2023-07")),
+            },
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
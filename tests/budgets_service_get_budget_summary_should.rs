@@ -0,0 +1,136 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        budget::{BudgetLine, BudgetTotal, DynBudgetsRepository},
+        category::{CategoryType, DynCategoriesRepository},
+        user::{DynUsersRepository, User},
+    },
+    mocks::BudgetsServiceTestFixture,
+    server::services::budget_services::{BudgetsService, BudgetsServiceTrait},
+};
+use rust_decimal::Decimal;
+use time::macros::date;
+use uuid::{uuid, Uuid};
+
+const USER_ID: Uuid = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+
+fn build_service(fixture: BudgetsServiceTestFixture) -> BudgetsService {
+    BudgetsService::new(
+        Arc::new(fixture.mock_repository) as DynBudgetsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+    )
+}
+
+#[tokio::test]
+async fn leave_figures_empty_when_an_expense_has_no_exchange_rate() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(USER_ID))
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                id: USER_ID,
+                base_currency: String::from("USD"),
+                ..Default::default()
+            })
+        });
+
+    // one of the food expenses of the month is in yen, which has no rate into dollars
+    fixture
+        .mock_repository
+        .expect_get_budget_lines()
+        .with(eq(USER_ID), eq(date!(2023 - 08 - 01)))
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(vec![
+                BudgetLine {
+                    name: String::from("Food"),
+                    cat_type: CategoryType::Essential,
+                    budget_limit: Some(Decimal::from_str("100.0000").unwrap()),
+                    spent: None,
+                    remaining: None,
+                    percent_used: None,
+                    ..Default::default()
+                },
+                BudgetLine {
+                    name: String::from("Fun"),
+                    cat_type: CategoryType::NonEssential,
+                    budget_limit: Some(Decimal::from_str("50.0000").unwrap()),
+                    spent: Some(Decimal::from_str("20.0000").unwrap()),
+                    remaining: Some(Decimal::from_str("30.0000").unwrap()),
+                    percent_used: Some(Decimal::from_str("40.00").unwrap()),
+                    ..Default::default()
+                },
+            ])
+        });
+
+    fixture
+        .mock_repository
+        .expect_get_budget_totals()
+        .with(eq(USER_ID), eq(date!(2023 - 08 - 01)))
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(vec![
+                BudgetTotal {
+                    cat_type: CategoryType::Essential,
+                    budget_limit: Some(Decimal::from_str("100.0000").unwrap()),
+                    spent: None,
+                    remaining: None,
+                    percent_used: None,
+                },
+                BudgetTotal {
+                    cat_type: CategoryType::NonEssential,
+                    budget_limit: Some(Decimal::from_str("50.0000").unwrap()),
+                    spent: Some(Decimal::from_str("20.0000").unwrap()),
+                    remaining: Some(Decimal::from_str("30.0000").unwrap()),
+                    percent_used: Some(Decimal::from_str("40.00").unwrap()),
+                },
+            ])
+        });
+
+    fixture
+        .mock_repository
+        .expect_get_unconverted_currencies()
+        .with(eq(USER_ID), eq(date!(2023 - 08 - 01)))
+        .times(1)
+        .return_once(move |_, _| Ok(vec![String::from("JPY")]));
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .get_budget_summary(USER_ID, String::from("2023-08"))
+        .await;
+
+    // assert
+    let summary = serde_json::to_value(response.unwrap()).unwrap();
+    assert_eq!(summary["unconverted_currencies"][0], "JPY");
+
+    let food = &summary["categories"][0];
+    assert_eq!(food["limit"], "100");
+    assert!(food["spent"].is_null());
+    assert!(food["remaining"].is_null());
+    assert!(food["percent_used"].is_null());
+
+    let fun = &summary["categories"][1];
+    assert_eq!(fun["spent"], "20");
+    assert_eq!(fun["remaining"], "30");
+
+    let essential = &summary["totals"][0];
+    assert_eq!(essential["limit"], "100");
+    assert!(essential["spent"].is_null());
+    assert!(essential["remaining"].is_null());
+    assert!(essential["percent_used"].is_null());
+
+    let non_essential = &summary["totals"][1];
+    assert_eq!(non_essential["spent"], "20");
+    assert_eq!(non_essential["percent_used"], "40");
+}
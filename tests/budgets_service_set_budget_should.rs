@@ -0,0 +1,199 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        budget::{Budget, DynBudgetsRepository},
+        category::{Category, DynCategoriesRepository},
+        user::{DynUsersRepository, User},
+    },
+    mocks::BudgetsServiceTestFixture,
+    server::{
+        dtos::budget_dto::BudgetSetDto,
+        error::Error,
+        services::budget_services::{BudgetsService, BudgetsServiceTrait},
+    },
+};
+use rust_decimal::Decimal;
+use time::macros::date;
+use uuid::uuid;
+
+fn build_service(fixture: BudgetsServiceTestFixture) -> BudgetsService {
+    BudgetsService::new(
+        Arc::new(fixture.mock_repository) as DynBudgetsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+    )
+}
+
+fn stub_request(amount: &str) -> BudgetSetDto {
+    BudgetSetDto {
+        amount: Some(Decimal::from_str(amount).unwrap()),
+        currency: None,
+    }
+}
+
+#[tokio::test]
+async fn store_budget_on_first_day_of_month() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Category::default())));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(Category::default().user_id))
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                base_currency: String::from("EUR"),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_upsert_budget()
+        .with(
+            eq(Category::default().user_id),
+            eq(Category::default().id),
+            eq(date!(2023 - 07 - 01)),
+            eq(Decimal::from_str("500.00").unwrap()),
+            eq(String::from("EUR")),
+        )
+        .times(1)
+        .return_once(move |_, _, _, _, _| Ok(Budget::default()));
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .set_budget(
+            Category::default().user_id,
+            String::from("2023-07"),
+            Category::default().id,
+            stub_request("500.00"),
+        )
+        .await;
+
+    // assert
+    let budget = response.unwrap();
+    assert_eq!(budget.month, "2023-07");
+    assert_eq!(budget.amount.to_string(), "500");
+}
+
+#[tokio::test]
+async fn return_forbidden_for_category_of_another_user() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(Category {
+                user_id: uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9"),
+                ..Default::default()
+            }))
+        });
+
+    fixture.mock_repository.expect_upsert_budget().never();
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .set_budget(
+            Category::default().user_id,
+            String::from("2023-07"),
+            Category::default().id,
+            stub_request("500.00"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
+
+#[tokio::test]
+async fn return_bad_request_for_malformed_month() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .never();
+
+    fixture.mock_repository.expect_upsert_budget().never();
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .set_budget(
+            Category::default().user_id,
+            String::from("2023-13"),
+            Category::default().id,
+            stub_request("500.00"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn store_budget_in_requested_currency() {
+    // arrange
+    let mut fixture = BudgetsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Category::default())));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .never();
+
+    fixture
+        .mock_repository
+        .expect_upsert_budget()
+        .withf(|_, _, _, _, currency: &String| currency == "GBP")
+        .times(1)
+        .return_once(move |_, _, _, amount, currency| {
+            Ok(Budget {
+                amount,
+                currency,
+                ..Default::default()
+            })
+        });
+
+    let budgets_service = build_service(fixture);
+
+    // act
+    let response = budgets_service
+        .set_budget(
+            Category::default().user_id,
+            String::from("2023-07"),
+            Category::default().id,
+            BudgetSetDto {
+                currency: Some(String::from("gbp")),
+                ..stub_request("250")
+            },
+        )
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap().currency, "GBP");
+}
@@ -0,0 +1,85 @@
+use std::net::IpAddr;
+
+use axum::http::{HeaderMap, HeaderValue};
+use rest_api::mocks::{stub_config, stub_config_with};
+use rest_api::server::utils::client_ip_utils::ClientIpUtil;
+
+fn stub_headers(name: &'static str, values: &[&str]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for value in values {
+        headers.append(name, HeaderValue::from_str(value).unwrap());
+    }
+
+    headers
+}
+
+fn ip(value: &str) -> Option<IpAddr> {
+    Some(value.parse().unwrap())
+}
+
+#[test]
+fn ignore_forwarded_header_when_no_proxy_is_trusted() {
+    // arrange
+    let client_ip_util = ClientIpUtil::new(stub_config());
+    let headers = stub_headers("x-forwarded-for", &["203.0.113.7"]);
+
+    // act
+    let client_ip = client_ip_util.client_ip(&headers, ip("198.51.100.1"));
+
+    // assert
+    assert_eq!(client_ip, ip("198.51.100.1"));
+}
+
+#[test]
+fn return_first_untrusted_hop_behind_trusted_proxies() {
+    // arrange
+    let client_ip_util = ClientIpUtil::new(stub_config_with(&[
+        "--trusted-proxies=10.0.0.0/8,127.0.0.1",
+    ]));
+    let headers = stub_headers("x-forwarded-for", &["192.0.2.99, 203.0.113.7", "10.1.2.3"]);
+
+    // act
+    let client_ip = client_ip_util.client_ip(&headers, ip("127.0.0.1"));
+
+    // assert
+    assert_eq!(client_ip, ip("203.0.113.7"));
+}
+
+#[test]
+fn read_single_address_from_configured_header() {
+    // arrange
+    let client_ip_util = ClientIpUtil::new(stub_config_with(&[
+        "--trusted-proxies=127.0.0.1",
+        "--client-ip-header=x-real-ip",
+    ]));
+    let mut headers = stub_headers("x-real-ip", &["2001:db8::7"]);
+    headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7"));
+
+    // act
+    let client_ip = client_ip_util.client_ip(&headers, ip("127.0.0.1"));
+
+    // assert
+    assert_eq!(client_ip, ip("2001:db8::7"));
+}
+
+#[test]
+fn stop_at_malformed_hop() {
+    // arrange
+    let client_ip_util = ClientIpUtil::new(stub_config_with(&["--trusted-proxies=10.0.0.0/8"]));
+    let headers = stub_headers("x-forwarded-for", &["203.0.113.7, unknown, 10.0.0.2"]);
+
+    // act
+    let client_ip = client_ip_util.client_ip(&headers, ip("10.0.0.1"));
+
+    // assert
+    assert_eq!(client_ip, ip("10.0.0.2"));
+}
+
+#[test]
+fn return_none_without_connection_info() {
+    let client_ip_util = ClientIpUtil::new(stub_config_with(&["--trusted-proxies=10.0.0.0/8"]));
+    let headers = stub_headers("x-forwarded-for", &["203.0.113.7"]);
+
+    assert_eq!(client_ip_util.client_ip(&headers, None), None);
+}
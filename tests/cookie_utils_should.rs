@@ -0,0 +1,122 @@
+use axum::http::header::COOKIE;
+use axum::http::{HeaderMap, HeaderValue};
+use axum_extra::extract::cookie::{CookieJar, SameSite};
+use rest_api::mocks::{stub_config, stub_config_with};
+use rest_api::server::utils::cookie_utils::{
+    self, CookieUtil, CSRF_TOKEN_COOKIE, CSRF_TOKEN_HEADER, REFRESH_TOKEN_COOKIE,
+};
+use time::Duration;
+
+fn stub_headers(cookies: &[&str], csrf_header: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for cookie in cookies {
+        headers.append(COOKIE, HeaderValue::from_str(cookie).unwrap());
+    }
+
+    if let Some(csrf_header) = csrf_header {
+        headers.insert(
+            CSRF_TOKEN_HEADER,
+            HeaderValue::from_str(csrf_header).unwrap(),
+        );
+    }
+
+    headers
+}
+
+#[test]
+fn set_refresh_token_cookie_with_configured_attributes() {
+    // arrange
+    let cookie_util = CookieUtil::new(stub_config_with(&[
+        "--cookie-same-site=lax",
+        "--cookie-domain=example.com",
+    ]));
+
+    // act
+    let (jar, _) =
+        cookie_util.add_session_cookies(CookieJar::new(), String::from("stub refresh token"));
+
+    // assert
+    let cookie = jar.get(REFRESH_TOKEN_COOKIE).unwrap();
+    assert_eq!(cookie.value(), "stub refresh token");
+    assert_eq!(cookie.http_only(), Some(true));
+    assert_eq!(cookie.secure(), Some(true));
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_eq!(cookie.path(), Some("/api/v1/users"));
+    assert_eq!(cookie.domain(), Some("example.com"));
+    assert_eq!(cookie.max_age(), Some(Duration::minutes(10080)));
+}
+
+#[test]
+fn set_readable_csrf_cookie_matching_returned_token() {
+    // arrange
+    let cookie_util = CookieUtil::new(stub_config());
+
+    // act
+    let (jar, csrf_token) =
+        cookie_util.add_session_cookies(CookieJar::new(), String::from("stub refresh token"));
+
+    // assert
+    let cookie = jar.get(CSRF_TOKEN_COOKIE).unwrap();
+    assert_eq!(cookie.value(), csrf_token);
+    assert_ne!(cookie.http_only(), Some(true));
+    assert_eq!(cookie.path(), Some("/"));
+}
+
+#[test]
+fn find_refresh_token_among_other_cookies() {
+    // arrange
+    let headers = stub_headers(
+        &[
+            "theme=dark; refresh_token=stub refresh token",
+            "csrf_token=stub csrf token",
+        ],
+        None,
+    );
+
+    // act
+    let refresh_token = cookie_utils::get_refresh_token(&headers);
+
+    // assert
+    assert_eq!(refresh_token, Some(String::from("stub refresh token")));
+}
+
+#[test]
+fn return_none_when_refresh_token_cookie_is_missing() {
+    // arrange
+    let headers = stub_headers(&["theme=dark", "not a cookie"], None);
+
+    // act
+    let refresh_token = cookie_utils::get_refresh_token(&headers);
+
+    // assert
+    assert_eq!(refresh_token, None);
+}
+
+#[test]
+fn accept_csrf_header_matching_cookie() {
+    let headers = stub_headers(&["csrf_token=stub csrf token"], Some("stub csrf token"));
+
+    assert!(cookie_utils::verify_csrf_token(&headers));
+}
+
+#[test]
+fn reject_csrf_header_not_matching_cookie() {
+    let headers = stub_headers(&["csrf_token=stub csrf token"], Some("other csrf token"));
+
+    assert!(!cookie_utils::verify_csrf_token(&headers));
+}
+
+#[test]
+fn reject_missing_csrf_header() {
+    let headers = stub_headers(&["csrf_token=stub csrf token"], None);
+
+    assert!(!cookie_utils::verify_csrf_token(&headers));
+}
+
+#[test]
+fn reject_empty_csrf_cookie() {
+    let headers = stub_headers(&["csrf_token="], Some(""));
+
+    assert!(!cookie_utils::verify_csrf_token(&headers));
+}
@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use rest_api::server::{error::Error, utils::currency_utils};
+use rust_decimal::Decimal;
+
+#[test]
+fn normalize_currency_to_upper_case() {
+    assert_eq!(currency_utils::normalize_currency("eur").unwrap(), "EUR");
+    assert!(matches!(
+        currency_utils::normalize_currency("XYZ"),
+        Err(Error::BadRequest(_))
+    ));
+}
+
+#[test]
+fn accept_amounts_that_fit_the_column() {
+    for amount in ["0", "12.3456", "-999999999999999.9999"] {
+        assert!(
+            currency_utils::ensure_amount_fits(Decimal::from_str(amount).unwrap()).is_ok(),
+            "expected {} to be accepted",
+            amount
+        );
+    }
+}
+
+#[test]
+fn accept_amounts_with_trailing_zeros() {
+    for amount in ["1.50000", "10.000000", "-0.12340000"] {
+        assert!(
+            currency_utils::ensure_amount_fits(Decimal::from_str(amount).unwrap()).is_ok(),
+            "expected {} to be accepted",
+            amount
+        );
+    }
+}
+
+#[test]
+fn reject_amounts_that_do_not_fit_the_column() {
+    for amount in ["0.00001", "1000000000000000", "-1000000000000000"] {
+        assert!(
+            matches!(
+                currency_utils::ensure_amount_fits(Decimal::from_str(amount).unwrap()),
+                Err(Error::BadRequest(_))
+            ),
+            "expected {} to be rejected",
+            amount
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    config::ExchangeRatesFormat,
+    database::exchange_rate::{DynExchangeRatesRepository, ExchangeRateFields},
+    mocks::ExchangeRatesServiceTestFixture,
+    server::{
+        error::Error,
+        services::exchange_rate_services::{ExchangeRatesService, ExchangeRatesServiceTrait},
+    },
+};
+use rust_decimal::Decimal;
+use time::macros::date;
+
+fn build_service(fixture: ExchangeRatesServiceTestFixture) -> ExchangeRatesService {
+    ExchangeRatesService::new(Arc::new(fixture.mock_repository) as DynExchangeRatesRepository)
+}
+
+fn stub_rates() -> Vec<ExchangeRateFields> {
+    vec![
+        ExchangeRateFields {
+            base_currency: String::from("EUR"),
+            quote_currency: String::from("USD"),
+            rate: Decimal::from_str("1.0987").unwrap(),
+            effective_on: date!(2023 - 07 - 03),
+        },
+        ExchangeRateFields {
+            base_currency: String::from("GBP"),
+            quote_currency: String::from("USD"),
+            rate: Decimal::from_str("1.2701").unwrap(),
+            effective_on: date!(2023 - 07 - 03),
+        },
+    ]
+}
+
+#[tokio::test]
+async fn store_rates_read_from_csv() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .with(eq(stub_rates()))
+        .times(1)
+        .return_once(|_| Ok(2));
+
+    let service = build_service(fixture);
+    let contents = "effective_on,base_currency,quote_currency,rate\n\
+                    2023-07-03,eur,usd,1.0987\n\
+                    \n\
+                    \"2023-07-03\", \"GBP\", \"USD\", \"1.2701\"\n";
+
+    // act
+    let response = service
+        .import_exchange_rates(contents, ExchangeRatesFormat::Csv)
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn keep_commas_and_quotes_inside_quoted_fields() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .with(eq(stub_rates()))
+        .times(1)
+        .return_once(|_| Ok(2));
+
+    let service = build_service(fixture);
+    let contents = "effective_on,base_currency,quote_currency,rate,source\n\
+                    2023-07-03,EUR,USD,1.0987,\"ECB, Frankfurt\"\n\
+                    2023-07-03,GBP,USD,1.2701,\"the \"\"Old Lady\"\", London\"\n";
+
+    // act
+    let response = service
+        .import_exchange_rates(contents, ExchangeRatesFormat::Csv)
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn accept_rates_with_trailing_zeros() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .with(eq(stub_rates()))
+        .times(1)
+        .return_once(|_| Ok(2));
+
+    let service = build_service(fixture);
+    let contents = "effective_on,base_currency,quote_currency,rate\n\
+                    2023-07-03,EUR,USD,1.09870000000\n\
+                    2023-07-03,GBP,USD,1.270100000000000\n";
+
+    // act
+    let response = service
+        .import_exchange_rates(contents, ExchangeRatesFormat::Csv)
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn store_rates_read_from_json() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .with(eq(stub_rates()))
+        .times(1)
+        .return_once(|_| Ok(2));
+
+    let service = build_service(fixture);
+    let contents = r#"[
+        { "base_currency": "EUR", "quote_currency": "USD", "rate": "1.0987", "effective_on": "2023-07-03" },
+        { "base_currency": "gbp", "quote_currency": "usd", "rate": "1.2701", "effective_on": "2023-07-03" }
+    ]"#;
+
+    // act
+    let response = service
+        .import_exchange_rates(contents, ExchangeRatesFormat::Json)
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn reject_json_rates_that_are_not_strings() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .never();
+
+    let service = build_service(fixture);
+    let contents = r#"[
+        { "base_currency": "EUR", "quote_currency": "USD", "rate": 1.0987, "effective_on": "2023-07-03" }
+    ]"#;
+
+    // act
+    let response = service
+        .import_exchange_rates(contents, ExchangeRatesFormat::Json)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn reject_the_whole_file_when_a_row_is_invalid() {
+    // arrange
+    let mut fixture = ExchangeRatesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_upsert_exchange_rates()
+        .never();
+
+    let service = build_service(fixture);
+    let rows = [
+        (
+            "2023-07-03,EUR,XYZ,1.0987",
+            "line 3: currency must be an ISO 4217 code",
+        ),
+        (
+            "2023-07-03,EUR,EUR,1",
+            "line 3: a rate needs two different currencies",
+        ),
+        (
+            "2023-07-03,EUR,USD,0",
+            "line 3: rate must be greater than zero",
+        ),
+        (
+            "03/07/2023,EUR,USD,1.0987",
+            "line 3: effective_on must be formatted as YYYY-MM-DD",
+        ),
+        (
+            "2023-07-03,EUR,USD,\"1.0987",
+            "line 3: fields have unbalanced quotes",
+        ),
+        (
+            "2023-07-03,\"EUR\"O,USD,1.0987",
+            "line 3: fields have unbalanced quotes",
+        ),
+        (
+            "2023-07-03,E\"UR,USD,1.0987",
+            "line 3: fields have unbalanced quotes",
+        ),
+    ];
+
+    for (row, expected) in rows {
+        let contents = format!(
+            "effective_on,base_currency,quote_currency,rate\n2023-07-03,GBP,USD,1.2701\n{}\n",
+            row
+        );
+
+        // act
+        let response = service
+            .import_exchange_rates(&contents, ExchangeRatesFormat::Csv)
+            .await;
+
+        // assert
+        match response {
+            Err(Error::BadRequest(message)) => assert_eq!(message, expected),
+            _ => panic!("expected {:?} to be rejected", row),
+        }
+    }
+}
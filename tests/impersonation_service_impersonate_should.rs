@@ -0,0 +1,170 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        impersonation_event::{DynImpersonationEventsRepository, ImpersonationEvent},
+        role::{DynRolesRepository, Role},
+        user::{DynUsersRepository, User},
+    },
+    mocks::ImpersonationServiceTestFixture,
+    server::{
+        error::Error,
+        services::impersonation_services::{
+            ImpersonationService, ImpersonationServiceTrait, IMPERSONATION_STARTED,
+        },
+        utils::jwt_utils::{AccessToken, DynJwtUtil},
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn build_service(fixture: ImpersonationServiceTestFixture) -> ImpersonationService {
+    ImpersonationService::new(
+        Arc::new(fixture.mock_repository) as DynImpersonationEventsRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+    )
+}
+
+#[tokio::test]
+async fn issue_impersonation_token_and_record_the_start() {
+    // arrange
+    let mut fixture = ImpersonationServiceTestFixture::default();
+    let admin_id = uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40");
+    let user_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+    let jti = uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c");
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(user_id))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_impersonation_token()
+        .withf(move |_, _, _, actor_id| *actor_id == admin_id)
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(String::from("stub impersonation token")));
+
+    fixture
+        .mock_jwt_util
+        .expect_get_access_from_token()
+        .times(1)
+        .return_once(move |_| {
+            Ok(AccessToken {
+                user_id,
+                jti,
+                grants: Default::default(),
+                actor_id: Some(admin_id),
+                issued_at: OffsetDateTime::from(SystemTime::now()),
+                expires_at: OffsetDateTime::from(SystemTime::now()),
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_create_impersonation_event()
+        .withf(move |actor, user, event, token_id, _, _| {
+            *actor == admin_id
+                && *user == user_id
+                && event == IMPERSONATION_STARTED
+                && *token_id == Some(jti)
+        })
+        .times(1)
+        .return_once(move |_, _, _, _, _, _| Ok(ImpersonationEvent::default()));
+
+    let impersonation_service = build_service(fixture);
+
+    // act
+    let response = impersonation_service.impersonate(admin_id, user_id).await;
+
+    // assert
+    let user = response.unwrap();
+    assert_eq!(
+        user.access_token,
+        Some(String::from("stub impersonation token"))
+    );
+    assert_eq!(user.impersonated_by, Some(admin_id));
+}
+
+#[tokio::test]
+async fn return_forbidden_when_impersonating_an_admin() {
+    // arrange
+    let mut fixture = ImpersonationServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![Role {
+                name: String::from("admin"),
+                ..Default::default()
+            }])
+        });
+
+    fixture
+        .mock_jwt_util
+        .expect_new_impersonation_token()
+        .never();
+
+    fixture
+        .mock_repository
+        .expect_create_impersonation_event()
+        .never();
+
+    let impersonation_service = build_service(fixture);
+
+    // act
+    let response = impersonation_service
+        .impersonate(
+            uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40"),
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
+
+#[tokio::test]
+async fn return_bad_request_when_admin_impersonates_themselves() {
+    // arrange
+    let mut fixture = ImpersonationServiceTestFixture::default();
+    let admin_id = uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40");
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .never();
+
+    fixture
+        .mock_jwt_util
+        .expect_new_impersonation_token()
+        .never();
+
+    let impersonation_service = build_service(fixture);
+
+    // act
+    let response = impersonation_service.impersonate(admin_id, admin_id).await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
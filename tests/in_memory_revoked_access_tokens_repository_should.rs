@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use rest_api::database::revoked_access_token::{
+    InMemoryRevokedAccessTokensRepository, RevokedAccessTokensRepository,
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+#[tokio::test]
+async fn deny_revoked_token_until_it_expires() {
+    // arrange
+    let repository = InMemoryRevokedAccessTokensRepository::default();
+    let now = OffsetDateTime::now_utc();
+    let jti = uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c");
+    let user_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+
+    repository
+        .revoke_access_token(jti, &(now + Duration::from_secs(60)))
+        .await
+        .unwrap();
+
+    // act
+    let revoked = repository
+        .is_access_token_revoked(jti, user_id, &now)
+        .await
+        .unwrap();
+    let other_revoked = repository
+        .is_access_token_revoked(uuid!("0a4c6e8b-2d1f-4a3c-9e5b-7d9f1b3d5a7c"), user_id, &now)
+        .await
+        .unwrap();
+
+    // assert
+    assert!(revoked);
+    assert!(!other_revoked);
+}
+
+#[tokio::test]
+async fn deny_only_user_tokens_issued_before_revocation() {
+    // arrange
+    let repository = InMemoryRevokedAccessTokensRepository::default();
+    let now = OffsetDateTime::now_utc();
+    let jti = uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c");
+    let user_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+
+    repository
+        .revoke_user_access_tokens(user_id, &now, &(now + Duration::from_secs(60)))
+        .await
+        .unwrap();
+
+    // act
+    let issued_before_revoked = repository
+        .is_access_token_revoked(jti, user_id, &(now - Duration::from_secs(1)))
+        .await
+        .unwrap();
+    let issued_after_revoked = repository
+        .is_access_token_revoked(jti, user_id, &now)
+        .await
+        .unwrap();
+
+    // assert
+    assert!(issued_before_revoked);
+    assert!(!issued_after_revoked);
+}
+
+#[tokio::test]
+async fn evict_revocations_once_tokens_have_expired() {
+    // arrange
+    let repository = InMemoryRevokedAccessTokensRepository::default();
+    let now = OffsetDateTime::now_utc();
+    let jti = uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c");
+    let user_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+    let expired_at = now - Duration::from_secs(1);
+
+    repository
+        .revoke_access_token(jti, &expired_at)
+        .await
+        .unwrap();
+    repository
+        .revoke_user_access_tokens(user_id, &now, &expired_at)
+        .await
+        .unwrap();
+
+    // act
+    repository
+        .delete_expired_revoked_access_tokens()
+        .await
+        .unwrap();
+
+    // assert
+    let revoked = repository
+        .is_access_token_revoked(jti, user_id, &(now - Duration::from_secs(60)))
+        .await
+        .unwrap();
+    assert!(!revoked);
+}
+
+#[tokio::test]
+async fn consume_token_only_once() {
+    // arrange
+    let repository = InMemoryRevokedAccessTokensRepository::default();
+    let expires_at = OffsetDateTime::now_utc() + Duration::from_secs(60);
+    let jti = uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c");
+
+    // act
+    let first_attempt = repository.consume_token(jti, &expires_at).await.unwrap();
+    let second_attempt = repository.consume_token(jti, &expires_at).await.unwrap();
+
+    // assert
+    assert!(first_attempt);
+    assert!(!second_attempt);
+}
@@ -0,0 +1,339 @@
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use data_encoding::{BASE64, BASE64URL_NOPAD};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, Jwk, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{decode_header, encode, Algorithm, EncodingKey, Header};
+use rest_api::{
+    config::{AppConfig, JwtAlgorithm},
+    database::{role::AccessGrants, signing_key::SigningKey},
+    mocks::stub_config_with,
+    server::utils::{
+        jwt_utils::{JwtTokenUtil, JwtUtil},
+        signing_key_utils::{self, KeyRing},
+    },
+};
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+const RSA_KEY: &str = include_str!("fixtures/oidc_test_key.pem");
+
+fn stored_key(activates_at: SystemTime) -> SigningKey {
+    let generated_key = signing_key_utils::generate_signing_key(JwtAlgorithm::EdDsa).unwrap();
+
+    SigningKey {
+        kid: generated_key.kid,
+        algorithm: String::from("EdDSA"),
+        private_key: generated_key.private_key,
+        public_jwk: serde_json::to_string(&generated_key.public_jwk).unwrap(),
+        activates_at: OffsetDateTime::from(activates_at),
+        ..Default::default()
+    }
+}
+
+fn stored_rsa_key() -> SigningKey {
+    let private_key = RsaPrivateKey::from_pkcs8_pem(RSA_KEY).unwrap();
+    let public_jwk = Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            algorithm: Some(Algorithm::RS256),
+            key_id: Some(String::from("stub rsa kid")),
+            ..Default::default()
+        },
+        algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: BASE64URL_NOPAD.encode(&private_key.n().to_bytes_be()),
+            e: BASE64URL_NOPAD.encode(&private_key.e().to_bytes_be()),
+        }),
+    };
+
+    SigningKey {
+        kid: String::from("stub rsa kid"),
+        algorithm: String::from("RS256"),
+        private_key: BASE64.encode(private_key.to_pkcs1_der().unwrap().as_bytes()),
+        public_jwk: serde_json::to_string(&public_jwk).unwrap(),
+        activates_at: OffsetDateTime::from(SystemTime::now() - Duration::from_secs(60)),
+        ..Default::default()
+    }
+}
+
+fn build_util(config: Arc<AppConfig>, keys: Vec<SigningKey>) -> JwtTokenUtil {
+    let key_ring = Arc::new(KeyRing::default());
+    key_ring.load(keys).unwrap();
+
+    JwtTokenUtil::new(config, key_ring)
+}
+
+#[test]
+fn sign_access_token_with_newest_active_key() {
+    // arrange
+    let pending_key = stored_key(SystemTime::now().add(Duration::from_secs(600)));
+    let active_key = stored_key(SystemTime::now() - Duration::from_secs(60));
+    let retired_key = stored_key(SystemTime::now() - Duration::from_secs(3600));
+    let active_kid = active_key.kid.clone();
+
+    let jwt_util = build_util(
+        stub_config_with(&[]),
+        vec![pending_key, active_key, retired_key],
+    );
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let header = decode_header(&token).unwrap();
+    assert_eq!(header.alg, Algorithm::EdDSA);
+    assert_eq!(header.kid, Some(active_kid));
+
+    let access_token = jwt_util.get_access_from_token(token).unwrap();
+    assert_eq!(
+        access_token.user_id,
+        uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")
+    );
+}
+
+#[test]
+fn accept_tokens_signed_by_replaced_key_during_grace_period() {
+    // arrange
+    let old_key = stored_key(SystemTime::now() - Duration::from_secs(3600));
+    let old_jwt_util = build_util(stub_config_with(&[]), vec![old_key.clone()]);
+
+    let token = old_jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    let new_key = stored_key(SystemTime::now() - Duration::from_secs(60));
+    let rotated_jwt_util = build_util(stub_config_with(&[]), vec![new_key.clone(), old_key]);
+    let expired_jwt_util = build_util(stub_config_with(&[]), vec![new_key]);
+
+    // act
+    let during_grace_response = rotated_jwt_util.get_access_from_token(token.clone());
+    let after_grace_response = expired_jwt_util.get_access_from_token(token);
+
+    // assert
+    assert!(during_grace_response.is_ok());
+    assert!(after_grace_response.is_err());
+}
+
+#[test]
+fn reject_tokens_signed_with_shared_secret_when_using_asymmetric_keys() {
+    // arrange
+    let jwt_util = build_util(
+        stub_config_with(&[]),
+        vec![stored_key(SystemTime::now() - Duration::from_secs(60))],
+    );
+
+    let forged_token = encode(
+        &Header::default(),
+        &serde_json::json!({
+            "sub": "stub email",
+            "user_id": "f3f898aa-ffa3-4b58-91b0-612a1c801a5e",
+            "exp": OffsetDateTime::now_utc().unix_timestamp() + 3600,
+            "iat": OffsetDateTime::now_utc().unix_timestamp(),
+        }),
+        &EncodingKey::from_secret("stub access secret".as_bytes()),
+    )
+    .unwrap();
+
+    // act
+    let response = jwt_util.get_access_from_token(forged_token);
+
+    // assert
+    assert!(response.is_err());
+}
+
+#[test]
+fn sign_and_verify_rs256_tokens() {
+    // arrange
+    let jwt_util = build_util(
+        stub_config_with(&["--jwt-algorithm=rs256"]),
+        vec![stored_rsa_key()],
+    );
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let header = decode_header(&token).unwrap();
+    assert_eq!(header.alg, Algorithm::RS256);
+    assert_eq!(header.kid.as_deref(), Some("stub rsa kid"));
+    assert!(jwt_util.get_access_from_token(token).is_ok());
+}
+
+#[test]
+fn sign_with_shared_secret_when_configured_for_hs256() {
+    // arrange
+    let jwt_util = build_util(stub_config_with(&["--jwt-algorithm=hs256"]), vec![]);
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let header = decode_header(&token).unwrap();
+    assert_eq!(header.alg, Algorithm::HS256);
+    assert!(header.kid.is_none());
+    assert!(jwt_util.get_access_from_token(token).is_ok());
+}
+
+#[test]
+fn expire_access_token_after_configured_ttl() {
+    // arrange
+    let jwt_util = build_util(
+        stub_config_with(&["--access-token-ttl-seconds=120"]),
+        vec![stored_key(SystemTime::now() - Duration::from_secs(60))],
+    );
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let access_token = jwt_util.get_access_from_token(token).unwrap();
+    assert_eq!(
+        access_token.expires_at - access_token.issued_at,
+        Duration::from_secs(120)
+    );
+}
+
+#[test]
+fn carry_the_microsecond_the_access_token_was_issued_at() {
+    // arrange
+    let jwt_util = build_util(stub_config_with(&["--jwt-algorithm=hs256"]), vec![]);
+    let now = OffsetDateTime::now_utc();
+    let issued_after = now - Duration::from_nanos(u64::from(now.nanosecond() % 1000));
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let access_token = jwt_util.get_access_from_token(token).unwrap();
+    assert!(access_token.issued_at >= issued_after);
+    assert!(access_token.issued_at <= OffsetDateTime::now_utc());
+    assert_eq!(access_token.issued_at.nanosecond() % 1000, 0);
+}
+
+#[test]
+fn carry_the_impersonating_admin_with_its_own_ttl() {
+    // arrange
+    let jwt_util = build_util(
+        stub_config_with(&["--impersonation-ttl-seconds=300"]),
+        vec![stored_key(SystemTime::now() - Duration::from_secs(60))],
+    );
+    let admin_id = uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40");
+
+    // act
+    let token = jwt_util
+        .new_impersonation_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+            admin_id,
+        )
+        .unwrap();
+
+    // assert
+    let access_token = jwt_util.get_access_from_token(token).unwrap();
+    assert_eq!(access_token.actor_id, Some(admin_id));
+    assert_eq!(
+        access_token.expires_at - access_token.issued_at,
+        Duration::from_secs(300)
+    );
+}
+
+#[test]
+fn issue_access_tokens_without_an_actor() {
+    // arrange
+    let jwt_util = build_util(stub_config_with(&["--jwt-algorithm=hs256"]), vec![]);
+
+    // act
+    let token = jwt_util
+        .new_access_token(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            "stub email",
+            &AccessGrants::default(),
+        )
+        .unwrap();
+
+    // assert
+    let access_token = jwt_util.get_access_from_token(token).unwrap();
+    assert!(access_token.actor_id.is_none());
+}
+
+#[test]
+fn expire_mfa_token_after_configured_ttl() {
+    // arrange
+    let jwt_util = build_util(
+        stub_config_with(&["--jwt-algorithm=hs256", "--mfa-token-ttl-seconds=120"]),
+        vec![],
+    );
+    let issued_at = OffsetDateTime::now_utc();
+
+    // act
+    let token = jwt_util
+        .new_mfa_token(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        .unwrap();
+
+    // assert
+    let mfa_token = jwt_util.get_mfa_token(token).unwrap();
+    let ttl = mfa_token.expires_at - issued_at;
+    assert!(ttl > time::Duration::seconds(118) && ttl <= time::Duration::seconds(121));
+}
+
+#[test]
+fn expire_refresh_token_with_its_session() {
+    // arrange
+    let jwt_util = build_util(stub_config_with(&[]), vec![]);
+    let session_exp = OffsetDateTime::from(SystemTime::now() - Duration::from_secs(3600));
+
+    // act
+    let token = jwt_util
+        .new_refresh_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("2e3d5d1e-6f0f-4b0e-9d54-5b1a3f3f7c11"),
+            &session_exp,
+        )
+        .unwrap();
+
+    // assert
+    assert!(jwt_util.get_session_id_from_token(token).is_err());
+}
@@ -0,0 +1,88 @@
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        lockout_event::DynLockoutEventsRepository,
+        login_throttle::{DynLoginThrottlesRepository, LoginThrottle},
+        user::DynUsersRepository,
+    },
+    mocks::LoginThrottlesServiceTestFixture,
+    server::{
+        error::Error,
+        services::login_throttle_services::{LoginThrottlesService, LoginThrottlesServiceTrait},
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+
+fn build_service(fixture: LoginThrottlesServiceTestFixture) -> LoginThrottlesService {
+    LoginThrottlesService::new(
+        Arc::new(fixture.mock_repository) as DynLoginThrottlesRepository,
+        Arc::new(fixture.mock_lockout_events_repository) as DynLockoutEventsRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn return_too_many_login_attempts_while_account_is_locked() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_login_throttle()
+        .with(eq("account"), eq("stub email"))
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(Some(LoginThrottle {
+                failed_attempts: 5,
+                locked_until: Some(OffsetDateTime::from(
+                    SystemTime::now().add(Duration::from_secs(60)),
+                )),
+                ..Default::default()
+            }))
+        });
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .ensure_allowed("stub email", None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::TooManyLoginAttempts)));
+}
+
+#[tokio::test]
+async fn allow_attempt_once_lockout_has_expired() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_login_throttle()
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(Some(LoginThrottle {
+                failed_attempts: 5,
+                locked_until: Some(OffsetDateTime::from(
+                    SystemTime::now() - Duration::from_secs(60),
+                )),
+                ..Default::default()
+            }))
+        });
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .ensure_allowed("stub email", None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
@@ -0,0 +1,218 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        lockout_event::{DynLockoutEventsRepository, LockoutEvent},
+        login_throttle::{DynLoginThrottlesRepository, LoginThrottle},
+        user::DynUsersRepository,
+    },
+    mocks::LoginThrottlesServiceTestFixture,
+    server::services::login_throttle_services::{
+        lockout_duration, LoginThrottlesService, LoginThrottlesServiceTrait,
+    },
+};
+use uuid::uuid;
+
+fn build_service(fixture: LoginThrottlesServiceTestFixture) -> LoginThrottlesService {
+    LoginThrottlesService::new(
+        Arc::new(fixture.mock_repository) as DynLoginThrottlesRepository,
+        Arc::new(fixture.mock_lockout_events_repository) as DynLockoutEventsRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn not_lock_account_with_attempts_remaining() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_record_failed_login()
+        .with(eq("account"), eq("stub email"), always())
+        .times(1)
+        .return_once(move |_, _, _| {
+            Ok(LoginThrottle {
+                failed_attempts: 4,
+                ..Default::default()
+            })
+        });
+
+    fixture.mock_repository.expect_lock_login_throttle().never();
+    fixture
+        .mock_lockout_events_repository
+        .expect_create_lockout_event()
+        .never();
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .record_failure("Stub Email", None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn lock_account_and_record_event_when_attempts_run_out() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_record_failed_login()
+        .with(eq("account"), eq("stub email"), always())
+        .times(1)
+        .return_once(move |_, _, _| {
+            Ok(LoginThrottle {
+                failed_attempts: 5,
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_lock_login_throttle()
+        .with(eq("account"), eq("stub email"), always())
+        .times(1)
+        .return_once(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_lockout_events_repository
+        .expect_create_lockout_event()
+        .withf(|scope, subject, event, failed_attempts, locked_until| {
+            scope == "account"
+                && subject == "stub email"
+                && event == "locked"
+                && *failed_attempts == 5
+                && locked_until.is_some()
+        })
+        .times(1)
+        .return_once(move |_, _, _, _, _| Ok(LockoutEvent::default()));
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .record_failure("stub email", None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn track_client_ip_against_its_own_limit() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+    let ip_address = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+    fixture
+        .mock_repository
+        .expect_record_failed_login()
+        .with(eq("account"), always(), always())
+        .times(1)
+        .return_once(move |_, _, _| Ok(LoginThrottle::default()));
+
+    fixture
+        .mock_repository
+        .expect_record_failed_login()
+        .with(eq("ip"), eq("203.0.113.7"), always())
+        .times(1)
+        .return_once(move |scope, subject, _| {
+            Ok(LoginThrottle {
+                scope: String::from(scope),
+                subject: String::from(subject),
+                failed_attempts: 20,
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_lock_login_throttle()
+        .with(eq("ip"), eq("203.0.113.7"), always())
+        .times(1)
+        .return_once(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_lockout_events_repository
+        .expect_create_lockout_event()
+        .times(1)
+        .return_once(move |_, _, _, _, _| Ok(LockoutEvent::default()));
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .record_failure("stub email", Some(ip_address))
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[test]
+fn double_lockout_for_each_attempt_over_the_limit_up_to_the_maximum() {
+    assert_eq!(lockout_duration(0, 30, 3600), Duration::from_secs(30));
+    assert_eq!(lockout_duration(1, 30, 3600), Duration::from_secs(60));
+    assert_eq!(lockout_duration(3, 30, 3600), Duration::from_secs(240));
+    assert_eq!(lockout_duration(7, 30, 3600), Duration::from_secs(3600));
+    assert_eq!(lockout_duration(64, 30, 3600), Duration::from_secs(3600));
+}
+
+#[tokio::test]
+async fn lock_user_when_mfa_attempts_run_out() {
+    // arrange
+    let mut fixture = LoginThrottlesServiceTestFixture::default();
+    let user_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+
+    fixture
+        .mock_repository
+        .expect_record_failed_login()
+        .with(eq("mfa"), eq(user_id.to_string()), always())
+        .times(1)
+        .return_once(move |_, _, _| {
+            Ok(LoginThrottle {
+                scope: String::from("mfa"),
+                subject: user_id.to_string(),
+                failed_attempts: 5,
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_lock_login_throttle()
+        .with(eq("mfa"), eq(user_id.to_string()), always())
+        .times(1)
+        .return_once(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_lockout_events_repository
+        .expect_create_lockout_event()
+        .withf(move |scope, subject, event, _, locked_until| {
+            scope == "mfa"
+                && subject == user_id.to_string()
+                && event == "locked"
+                && locked_until.is_some()
+        })
+        .times(1)
+        .return_once(move |_, _, _, _, _| Ok(LockoutEvent::default()));
+
+    let login_throttles_service = build_service(fixture);
+
+    // act
+    let response = login_throttles_service
+        .record_mfa_failure(user_id, None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
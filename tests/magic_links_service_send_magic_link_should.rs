@@ -0,0 +1,158 @@
+use std::sync::{Arc, Mutex};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        magic_link::{DynMagicLinksRepository, MagicLinkToken},
+        user::{DynUsersRepository, User},
+    },
+    mocks::MagicLinksServiceTestFixture,
+    server::{
+        dtos::user_dto::MagicLinkDto,
+        services::{
+            magic_link_services::{MagicLinksService, MagicLinksServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::DynJwtUtil,
+            mailer_utils::{DynMailer, InMemoryMailer},
+            token_utils,
+        },
+    },
+};
+use uuid::uuid;
+
+fn stub_request() -> MagicLinkDto {
+    MagicLinkDto {
+        email: Some(String::from("stub email")),
+    }
+}
+
+#[tokio::test]
+async fn send_link_with_token_matching_stored_hash() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+    let mailer = Arc::new(InMemoryMailer::default());
+    let stored_hash = Arc::new(Mutex::new(String::new()));
+    let captured_hash = stored_hash.clone();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_repository
+        .expect_count_magic_links_since()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")), always())
+        .times(1)
+        .return_once(move |_, _| Ok(2));
+
+    fixture
+        .mock_repository
+        .expect_create_magic_link()
+        .times(1)
+        .return_once(move |_, token_hash, _| {
+            *captured_hash.lock().unwrap() = token_hash.to_string();
+            Ok(MagicLinkToken::default())
+        });
+
+    let magic_links_service = MagicLinksService::new(
+        Arc::new(fixture.mock_repository) as DynMagicLinksRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        mailer.clone() as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = magic_links_service.send_magic_link(stub_request()).await;
+
+    // assert
+    assert!(response.is_ok());
+
+    let messages = mailer.messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].to, "stub email");
+
+    let token = messages[0]
+        .body
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap();
+    assert_eq!(token_utils::hash_token(token), *stored_hash.lock().unwrap());
+}
+
+#[tokio::test]
+async fn return_success_without_email_when_limit_is_reached() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_repository
+        .expect_count_magic_links_since()
+        .times(1)
+        .return_once(move |_, _| Ok(3));
+
+    fixture.mock_repository.expect_create_magic_link().times(0);
+    fixture.mock_mailer.expect_send().times(0);
+
+    let magic_links_service = MagicLinksService::new(
+        Arc::new(fixture.mock_repository) as DynMagicLinksRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = magic_links_service.send_magic_link(stub_request()).await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_success_without_email_when_user_does_not_exist() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_repository.expect_create_magic_link().times(0);
+    fixture.mock_mailer.expect_send().times(0);
+
+    let magic_links_service = MagicLinksService::new(
+        Arc::new(fixture.mock_repository) as DynMagicLinksRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = magic_links_service.send_magic_link(stub_request()).await;
+
+    // assert
+    assert!(response.is_ok());
+}
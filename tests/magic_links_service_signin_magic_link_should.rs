@@ -0,0 +1,252 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        magic_link::{DynMagicLinksRepository, MagicLinkToken},
+        user::{DynUsersRepository, User},
+    },
+    mocks::MagicLinksServiceTestFixture,
+    server::{
+        dtos::{
+            session_dto::{NewSessionDto, SessionResponseDto},
+            user_dto::{SignInMagicLinkDto, SignInOutcome},
+        },
+        error::Error,
+        services::{
+            magic_link_services::{MagicLinksService, MagicLinksServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::{
+            argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil, mailer_utils::DynMailer, token_utils,
+        },
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn stub_request() -> SignInMagicLinkDto {
+    SignInMagicLinkDto {
+        token: Some(String::from("stub token")),
+    }
+}
+
+fn build_service(fixture: MagicLinksServiceTestFixture) -> MagicLinksService {
+    MagicLinksService::new(
+        Arc::new(fixture.mock_repository) as DynMagicLinksRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn verify_email_and_replace_password_of_unverified_user_for_valid_link() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_consume_magic_link()
+        .with(eq(token_utils::hash_token("stub token")))
+        .times(1)
+        .return_once(move |_| Ok(Some(MagicLinkToken::default())));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    // whoever registered the address first may know the password, so it must not survive the verification
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .withf(|password: &str| password != "stub password")
+        .times(1)
+        .return_once(move |_| Ok(String::from("random hashed password")));
+
+    fixture
+        .mock_users_repository
+        .expect_update_password()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("random hashed password"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_users_repository
+        .expect_verify_email()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("stub email"),
+        )
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(User {
+                verified_at: Some(OffsetDateTime::from(SystemTime::now())),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .withf(|request: &NewSessionDto| {
+            request.user_id == Some(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        })
+        .times(1)
+        .return_once(move |_| {
+            Ok(SessionResponseDto {
+                access_token: String::from("stub access token"),
+                refresh_token: String::from("stub refresh token"),
+            })
+        });
+
+    let magic_links_service = build_service(fixture);
+
+    // act
+    let response = magic_links_service
+        .signin_magic_link(stub_request(), Some(String::from("stub user agent")), None)
+        .await;
+
+    // assert
+    assert!(matches!(
+        response,
+        Ok(SignInOutcome::Authenticated(_, refresh_token)) if refresh_token == "stub refresh token"
+    ));
+}
+
+#[tokio::test]
+async fn cancel_scheduled_deletion_when_user_signs_in_during_grace_period() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_consume_magic_link()
+        .times(1)
+        .return_once(move |_| Ok(Some(MagicLinkToken::default())));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                verified_at: Some(OffsetDateTime::from(SystemTime::now())),
+                delete_after: Some(OffsetDateTime::from(SystemTime::now())),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_users_repository
+        .expect_cancel_user_deletion()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let magic_links_service = build_service(fixture);
+
+    // act
+    let response = magic_links_service
+        .signin_magic_link(stub_request(), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Ok(SignInOutcome::Authenticated(_, _))));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_link_is_used_or_expired() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_consume_magic_link()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .never();
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let magic_links_service = build_service(fixture);
+
+    // act
+    let response = magic_links_service
+        .signin_magic_link(stub_request(), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn require_mfa_when_enabled() {
+    // arrange
+    let mut fixture = MagicLinksServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_consume_magic_link()
+        .times(1)
+        .return_once(move |_| Ok(Some(MagicLinkToken::default())));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                verified_at: Some(OffsetDateTime::from(SystemTime::now())),
+                mfa_enabled_at: Some(OffsetDateTime::from(SystemTime::now())),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_jwt_util
+        .expect_new_mfa_token()
+        .times(1)
+        .return_once(move |_| Ok(String::from("stub mfa token")));
+
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let magic_links_service = build_service(fixture);
+
+    // act
+    let response = magic_links_service
+        .signin_magic_link(stub_request(), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(
+        response,
+        Ok(SignInOutcome::MfaRequired(mfa_token)) if mfa_token == "stub mfa token"
+    ));
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::Url;
+use rest_api::{
+    mocks::stub_config_with,
+    server::{
+        error::Error,
+        utils::oidc_utils::{self, HttpOidcClient, OidcClient},
+    },
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Private half of the key the fake provider signs ID tokens with, only ever used by these tests.
+const SIGNING_KEY: &str = include_str!("fixtures/oidc_test_key.pem");
+
+const SIGNING_KEY_MODULUS: &str = "pIbfltNReJkSf_zdZbf-J_D2sPVZai-bQRHfM4_HxTwnUpXi1buGtbnZiCvxiAEFGZOls092YwB4rW93jRPFi5MUyc9eYYolT_gmXDn3MSjBZLGIUHbLOHauVst3b6xQLGL0j50MZdtMDn34R1jkyTOKGoGn6B2HF69fEXOoCzjDLjuJ9oV7taOm_psdv8ISv6FoFZ0n3mizGnW1q1KhnONYFTgeVN07fqvAR64tFfS80XLdGZGgppgI3hiK_3_8_8JGn_PkBeoLP5FIkzJSupbXu0_3Wjb-20f94uvHEwXgYtwnl-NPM2Mx6g6HEd8bJiz7v6zipBRJpmJVxUHXzw";
+
+const CLIENT_ID: &str = "stub client";
+
+struct ProviderState {
+    issuer: String,
+    kid: String,
+    audience: String,
+    /// Pending authorizations by code, holding the PKCE challenge and nonce of the request.
+    authorizations: HashMap<String, (String, String)>,
+}
+
+/// A minimal OpenID Connect provider serving discovery, keys and the token endpoint on a local port.
+struct FakeProvider {
+    issuer: String,
+    state: Arc<Mutex<ProviderState>>,
+}
+
+impl FakeProvider {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = Arc::new(Mutex::new(ProviderState {
+            issuer: issuer.clone(),
+            kid: String::from("key-1"),
+            audience: String::from(CLIENT_ID),
+            authorizations: HashMap::new(),
+        }));
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(state.clone());
+
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        Self { issuer, state }
+    }
+
+    /// Plays the user consenting on the provider's authorization page, returning the issued code.
+    fn authorize(&self, authorization_url: &str) -> String {
+        let url = Url::parse(authorization_url).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["client_id"], CLIENT_ID);
+
+        let code = format!("code-{}", params["state"]);
+        self.state.lock().unwrap().authorizations.insert(
+            code.clone(),
+            (params["code_challenge"].clone(), params["nonce"].clone()),
+        );
+
+        code
+    }
+
+    fn client(&self) -> HttpOidcClient {
+        HttpOidcClient::new(stub_config_with(&[
+            &format!("--oidc-issuer-url={}", self.issuer),
+            &format!("--oidc-client-id={}", CLIENT_ID),
+        ]))
+    }
+}
+
+async fn discovery(State(state): State<Arc<Mutex<ProviderState>>>) -> Json<Value> {
+    let issuer = state.lock().unwrap().issuer.clone();
+
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+    }))
+}
+
+async fn jwks(State(state): State<Arc<Mutex<ProviderState>>>) -> Json<Value> {
+    let kid = state.lock().unwrap().kid.clone();
+
+    Json(json!({
+        "keys": [{
+            "kty": "RSA",
+            "use": "sig",
+            "alg": "RS256",
+            "kid": kid,
+            "n": SIGNING_KEY_MODULUS,
+            "e": "AQAB",
+        }]
+    }))
+}
+
+async fn token(
+    State(state): State<Arc<Mutex<ProviderState>>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<Json<Value>, StatusCode> {
+    let mut state = state.lock().unwrap();
+
+    let (code_challenge, nonce) = state
+        .authorizations
+        .remove(&form["code"])
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let expected_challenge =
+        BASE64URL_NOPAD.encode(&Sha256::digest(form["code_verifier"].as_bytes()));
+
+    if form["grant_type"] != "authorization_code" || expected_challenge != code_challenge {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let claims = json!({
+        "iss": state.issuer,
+        "sub": "stub subject",
+        "aud": state.audience,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": nonce,
+        "email": "stub email",
+        "email_verified": true,
+        "name": "stub name",
+    });
+
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(state.kid.clone());
+
+    let id_token = encode(
+        &header,
+        &claims,
+        &EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).unwrap(),
+    )
+    .unwrap();
+
+    Ok(Json(json!({
+        "access_token": "stub access token",
+        "token_type": "Bearer",
+        "id_token": id_token,
+    })))
+}
+
+#[tokio::test]
+async fn return_identity_for_valid_code() {
+    // arrange
+    let provider = FakeProvider::start();
+    let client = provider.client();
+
+    let authorization_url = client
+        .authorization_url(
+            "stub state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+
+    // act
+    let response = client
+        .exchange_code(&code, "stub verifier", "stub nonce")
+        .await;
+
+    // assert
+    assert!(authorization_url.starts_with(&format!("{}/authorize?", provider.issuer)));
+    let identity = response.unwrap();
+    assert_eq!(identity.subject, "stub subject");
+    assert_eq!(identity.email, Some(String::from("stub email")));
+    assert!(identity.email_verified);
+    assert_eq!(identity.name, Some(String::from("stub name")));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_code_verifier_does_not_match() {
+    // arrange
+    let provider = FakeProvider::start();
+    let client = provider.client();
+
+    let authorization_url = client
+        .authorization_url(
+            "stub state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+
+    // act
+    let response = client
+        .exchange_code(&code, "stolen verifier", "stub nonce")
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_nonce_does_not_match() {
+    // arrange
+    let provider = FakeProvider::start();
+    let client = provider.client();
+
+    let authorization_url = client
+        .authorization_url(
+            "stub state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+
+    // act
+    let response = client
+        .exchange_code(&code, "stub verifier", "another nonce")
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_token_is_for_another_client() {
+    // arrange
+    let provider = FakeProvider::start();
+    let client = provider.client();
+    provider.state.lock().unwrap().audience = String::from("another client");
+
+    let authorization_url = client
+        .authorization_url(
+            "stub state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+
+    // act
+    let response = client
+        .exchange_code(&code, "stub verifier", "stub nonce")
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn refetch_keys_when_provider_rotates_them() {
+    // arrange
+    let provider = FakeProvider::start();
+    let client = provider.client();
+
+    let authorization_url = client
+        .authorization_url(
+            "stub state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+    client
+        .exchange_code(&code, "stub verifier", "stub nonce")
+        .await
+        .unwrap();
+
+    provider.state.lock().unwrap().kid = String::from("key-2");
+
+    let authorization_url = client
+        .authorization_url(
+            "another state",
+            "stub nonce",
+            &oidc_utils::code_challenge("stub verifier"),
+        )
+        .await
+        .unwrap();
+    let code = provider.authorize(&authorization_url);
+
+    // act
+    let response = client
+        .exchange_code(&code, "stub verifier", "stub nonce")
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
@@ -0,0 +1,405 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        oidc_login_state::{DynOidcLoginStatesRepository, OidcLoginState},
+        user::{DynUsersRepository, User},
+        user_identity::{DynUserIdentitiesRepository, UserIdentity},
+    },
+    mocks::OidcServiceTestFixture,
+    server::{
+        dtos::{
+            oidc_dto::OidcCallbackDto, session_dto::SessionResponseDto, user_dto::SignInOutcome,
+        },
+        error::Error,
+        services::{
+            oidc_services::{OidcService, OidcServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::DynJwtUtil,
+            oidc_utils::{DynOidcClient, OidcIdentity},
+            token_utils,
+        },
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn stub_callback() -> OidcCallbackDto {
+    OidcCallbackDto {
+        code: Some(String::from("stub code")),
+        state: Some(String::from("stub state")),
+    }
+}
+
+fn stub_identity(email_verified: bool) -> OidcIdentity {
+    OidcIdentity {
+        subject: String::from("stub subject"),
+        email: Some(String::from("stub email")),
+        email_verified,
+        name: Some(String::from("stub name")),
+    }
+}
+
+/// Expects the login state to be consumed and the code exchanged for the given identity.
+fn expect_code_exchange(fixture: &mut OidcServiceTestFixture, identity: OidcIdentity) {
+    fixture
+        .mock_login_states_repository
+        .expect_consume_oidc_login_state()
+        .with(eq(token_utils::hash_token("stub state")))
+        .times(1)
+        .return_once(move |_| Ok(Some(OidcLoginState::default())));
+
+    fixture
+        .mock_oidc_client
+        .expect_exchange_code()
+        .with(eq("stub code"), eq("stub code verifier"), eq("stub nonce"))
+        .times(1)
+        .return_once(move |_, _, _| Ok(identity));
+}
+
+fn expect_new_session(fixture: &mut OidcServiceTestFixture) {
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| {
+            Ok(SessionResponseDto {
+                access_token: String::from("stub access token"),
+                refresh_token: String::from("stub refresh token"),
+            })
+        });
+}
+
+fn build_service(fixture: OidcServiceTestFixture) -> OidcService {
+    OidcService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_identities_repository) as DynUserIdentitiesRepository,
+        Arc::new(fixture.mock_login_states_repository) as DynOidcLoginStatesRepository,
+        Arc::new(fixture.mock_oidc_client) as DynOidcClient,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn return_bad_request_when_state_is_unknown() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    fixture
+        .mock_login_states_repository
+        .expect_consume_oidc_login_state()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_oidc_client.expect_exchange_code().never();
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn sign_in_user_linked_to_subject() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(true));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .with(eq("oidc"), eq("stub subject"))
+        .times(1)
+        .return_once(move |_, _| Ok(Some(UserIdentity::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture.mock_repository.expect_get_user_by_email().never();
+    fixture
+        .mock_identities_repository
+        .expect_create_user_identity()
+        .never();
+
+    expect_new_session(&mut fixture);
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(
+        response,
+        Ok(SignInOutcome::Authenticated(_, refresh_token)) if refresh_token == "stub refresh token"
+    ));
+}
+
+#[tokio::test]
+async fn cancel_scheduled_deletion_when_user_signs_in_during_grace_period() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(true));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .with(eq("oidc"), eq("stub subject"))
+        .times(1)
+        .return_once(move |_, _| Ok(Some(UserIdentity::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                delete_after: Some(OffsetDateTime::now_utc()),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_cancel_user_deletion()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    expect_new_session(&mut fixture);
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(response, Ok(SignInOutcome::Authenticated(_, _))));
+}
+
+#[tokio::test]
+async fn link_existing_user_when_provider_verified_email() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(true));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .times(1)
+        .return_once(move |_, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(User {
+                verified_at: Some(OffsetDateTime::from(SystemTime::now())),
+                ..Default::default()
+            }))
+        });
+
+    fixture.mock_repository.expect_create_user().never();
+    fixture.mock_repository.expect_update_password().never();
+
+    fixture
+        .mock_identities_repository
+        .expect_create_user_identity()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("oidc"),
+            eq("stub subject"),
+            eq(Some(String::from("stub email"))),
+        )
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(UserIdentity::default()));
+
+    expect_new_session(&mut fixture);
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(response, Ok(SignInOutcome::Authenticated(_, _))));
+}
+
+#[tokio::test]
+async fn reset_password_when_linking_unverified_user() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(true));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .times(1)
+        .return_once(move |_, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .times(1)
+        .return_once(move |_| Ok(String::from("stub hash")));
+
+    fixture
+        .mock_repository
+        .expect_update_password()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("stub hash"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_verify_email()
+        .times(1)
+        .return_once(move |_, _| Ok(User::default()));
+
+    fixture
+        .mock_identities_repository
+        .expect_create_user_identity()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(UserIdentity::default()));
+
+    expect_new_session(&mut fixture);
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_conflict_when_unverified_email_matches_existing_user() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(false));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .times(1)
+        .return_once(move |_, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_identities_repository
+        .expect_create_user_identity()
+        .never();
+
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(response, Err(Error::ObjectConflict(_))));
+}
+
+#[tokio::test]
+async fn create_user_on_first_sign_in() {
+    // arrange
+    let mut fixture = OidcServiceTestFixture::default();
+
+    expect_code_exchange(&mut fixture, stub_identity(true));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identity()
+        .times(1)
+        .return_once(move |_, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .times(1)
+        .return_once(move |_| Ok(String::from("stub hash")));
+
+    fixture
+        .mock_repository
+        .expect_create_user()
+        .with(eq("stub email"), eq("stub name"), eq("stub hash"))
+        .times(1)
+        .return_once(move |_, _, _| Ok(User::default()));
+
+    fixture
+        .mock_repository
+        .expect_verify_email()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("stub email"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(User::default()));
+
+    fixture
+        .mock_identities_repository
+        .expect_create_user_identity()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(UserIdentity::default()));
+
+    expect_new_session(&mut fixture);
+
+    let oidc_service = build_service(fixture);
+
+    // act
+    let response = oidc_service.callback(stub_callback(), None, None).await;
+
+    // assert
+    assert!(matches!(response, Ok(SignInOutcome::Authenticated(_, _))));
+}
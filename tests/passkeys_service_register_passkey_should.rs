@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        passkey::{DynPasskeysRepository, Passkey},
+        user::DynUsersRepository,
+        webauthn_challenge::{DynWebauthnChallengesRepository, WebauthnChallenge},
+    },
+    mocks::{PasskeysServiceTestFixture, SoftwareAuthenticator},
+    server::{
+        dtos::passkey_dto::RegisterPasskeyDto,
+        error::Error,
+        services::{
+            passkey_services::{PasskeysService, PasskeysServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::{token_utils, webauthn_utils::REGISTRATION_CEREMONY},
+    },
+};
+use uuid::{uuid, Uuid};
+
+const CHALLENGE: &str = "c3R1YiBjaGFsbGVuZ2U";
+
+fn stub_request(authenticator: &SoftwareAuthenticator) -> RegisterPasskeyDto {
+    RegisterPasskeyDto {
+        name: Some(String::from("stub name")),
+        credential: Some(authenticator.create(CHALLENGE)),
+    }
+}
+
+fn expect_challenge(fixture: &mut PasskeysServiceTestFixture, user_id: Uuid) {
+    fixture
+        .mock_challenges_repository
+        .expect_consume_webauthn_challenge()
+        .with(
+            eq(token_utils::hash_token(CHALLENGE)),
+            eq(REGISTRATION_CEREMONY),
+        )
+        .times(1)
+        .return_once(move |_, _| {
+            Ok(Some(WebauthnChallenge {
+                ceremony: String::from(REGISTRATION_CEREMONY),
+                user_id: Some(user_id),
+                ..Default::default()
+            }))
+        });
+}
+
+fn build_service(fixture: PasskeysServiceTestFixture) -> PasskeysService {
+    PasskeysService::new(
+        Arc::new(fixture.mock_repository) as DynPasskeysRepository,
+        Arc::new(fixture.mock_challenges_repository) as DynWebauthnChallengesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn store_credential_of_verified_registration() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let authenticator = SoftwareAuthenticator::default();
+
+    expect_challenge(&mut fixture, uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"));
+
+    fixture
+        .mock_repository
+        .expect_get_passkey_by_credential_id()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_create_passkey()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("stub name"),
+            eq(authenticator.encoded_credential_id()),
+            eq(authenticator.cose_public_key()),
+            eq(0),
+        )
+        .times(1)
+        .return_once(move |_, _, _, _, _| Ok(Passkey::default()));
+
+    let passkeys_service = build_service(fixture);
+
+    // act
+    let response = passkeys_service
+        .register_passkey(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(&authenticator),
+        )
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_bad_request_when_challenge_belongs_to_another_user() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let authenticator = SoftwareAuthenticator::default();
+
+    expect_challenge(&mut fixture, uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40"));
+
+    fixture.mock_repository.expect_create_passkey().never();
+
+    let passkeys_service = build_service(fixture);
+
+    // act
+    let response = passkeys_service
+        .register_passkey(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(&authenticator),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn return_conflict_when_credential_is_already_registered() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let authenticator = SoftwareAuthenticator::default();
+
+    expect_challenge(&mut fixture, uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"));
+
+    fixture
+        .mock_repository
+        .expect_get_passkey_by_credential_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Passkey::default())));
+
+    fixture.mock_repository.expect_create_passkey().never();
+
+    let passkeys_service = build_service(fixture);
+
+    // act
+    let response = passkeys_service
+        .register_passkey(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(&authenticator),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::ObjectConflict(_))));
+}
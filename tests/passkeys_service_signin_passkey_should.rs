@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        passkey::{DynPasskeysRepository, Passkey},
+        user::{DynUsersRepository, User},
+        webauthn_challenge::{DynWebauthnChallengesRepository, WebauthnChallenge},
+    },
+    mocks::{PasskeysServiceTestFixture, SoftwareAuthenticator},
+    server::{
+        dtos::session_dto::{NewSessionDto, SessionResponseDto},
+        error::Error,
+        services::{
+            passkey_services::{PasskeysService, PasskeysServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::{
+            token_utils,
+            webauthn_utils::{self, AUTHENTICATION_CEREMONY},
+        },
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+const CHALLENGE: &str = "c3R1YiBjaGFsbGVuZ2U";
+
+fn expect_passkey(fixture: &mut PasskeysServiceTestFixture, authenticator: &SoftwareAuthenticator) {
+    let passkey = Passkey {
+        credential_id: authenticator.encoded_credential_id(),
+        public_key: authenticator.cose_public_key(),
+        ..Default::default()
+    };
+
+    fixture
+        .mock_repository
+        .expect_get_passkey_by_credential_id()
+        .with(eq(authenticator.encoded_credential_id()))
+        .times(1)
+        .return_once(move |_| Ok(Some(passkey)));
+}
+
+fn expect_challenge(fixture: &mut PasskeysServiceTestFixture) {
+    fixture
+        .mock_challenges_repository
+        .expect_consume_webauthn_challenge()
+        .with(
+            eq(token_utils::hash_token(CHALLENGE)),
+            eq(AUTHENTICATION_CEREMONY),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(Some(WebauthnChallenge::default())));
+}
+
+fn build_service(fixture: PasskeysServiceTestFixture) -> PasskeysService {
+    PasskeysService::new(
+        Arc::new(fixture.mock_repository) as DynPasskeysRepository,
+        Arc::new(fixture.mock_challenges_repository) as DynWebauthnChallengesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn create_session_for_valid_assertion() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let mut authenticator = SoftwareAuthenticator::default();
+
+    expect_passkey(&mut fixture, &authenticator);
+    expect_challenge(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_use_passkey()
+        .with(eq(Passkey::default().id), eq(1))
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .withf(|request: &NewSessionDto| {
+            request.user_id == Some(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        })
+        .times(1)
+        .return_once(move |_| {
+            Ok(SessionResponseDto {
+                access_token: String::from("stub access token"),
+                refresh_token: String::from("stub refresh token"),
+            })
+        });
+
+    let passkeys_service = build_service(fixture);
+    let user_handle = webauthn_utils::user_handle(&uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"));
+
+    // act
+    let response = passkeys_service
+        .signin_passkey(
+            authenticator.get(CHALLENGE, Some(user_handle)),
+            Some(String::from("stub user agent")),
+            None,
+        )
+        .await;
+
+    // assert
+    assert!(matches!(
+        response,
+        Ok((_, refresh_token)) if refresh_token == "stub refresh token"
+    ));
+}
+
+#[tokio::test]
+async fn cancel_scheduled_deletion_when_user_signs_in_during_grace_period() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let mut authenticator = SoftwareAuthenticator::default();
+
+    expect_passkey(&mut fixture, &authenticator);
+    expect_challenge(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_use_passkey()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(User {
+                delete_after: Some(OffsetDateTime::now_utc()),
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_users_repository
+        .expect_cancel_user_deletion()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let passkeys_service = build_service(fixture);
+    let user_handle = webauthn_utils::user_handle(&uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"));
+
+    // act
+    let response = passkeys_service
+        .signin_passkey(authenticator.get(CHALLENGE, Some(user_handle)), None, None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_challenge_is_unknown() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let mut authenticator = SoftwareAuthenticator::default();
+
+    expect_passkey(&mut fixture, &authenticator);
+
+    fixture
+        .mock_challenges_repository
+        .expect_consume_webauthn_challenge()
+        .times(1)
+        .return_once(move |_, _| Ok(None));
+
+    fixture.mock_repository.expect_use_passkey().never();
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let passkeys_service = build_service(fixture);
+
+    // act
+    let response = passkeys_service
+        .signin_passkey(authenticator.get(CHALLENGE, None), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_user_handle_belongs_to_another_user() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let mut authenticator = SoftwareAuthenticator::default();
+
+    expect_passkey(&mut fixture, &authenticator);
+    expect_challenge(&mut fixture);
+
+    fixture.mock_repository.expect_use_passkey().never();
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let passkeys_service = build_service(fixture);
+    let user_handle = webauthn_utils::user_handle(&uuid!("0b4c3a1e-9d6f-4e27-8b5a-2f1c7d9e3a40"));
+
+    // act
+    let response = passkeys_service
+        .signin_passkey(authenticator.get(CHALLENGE, Some(user_handle)), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_signature_counter_goes_backwards() {
+    // arrange
+    let mut fixture = PasskeysServiceTestFixture::default();
+    let mut authenticator = SoftwareAuthenticator::default();
+
+    expect_passkey(&mut fixture, &authenticator);
+    expect_challenge(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_use_passkey()
+        .times(1)
+        .return_once(move |_, _| Ok(false));
+
+    fixture.mock_sessions_services.expect_new_session().never();
+
+    let passkeys_service = build_service(fixture);
+
+    // act
+    let response = passkeys_service
+        .signin_passkey(authenticator.get(CHALLENGE, None), None, None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
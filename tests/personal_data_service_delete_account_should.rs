@@ -0,0 +1,234 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::DynAccountsRepository,
+        api_key::DynApiKeysRepository,
+        budget::DynBudgetsRepository,
+        category::DynCategoriesRepository,
+        passkey::DynPasskeysRepository,
+        recurring_transaction::DynRecurringTransactionsRepository,
+        transaction::DynTransactionsRepository,
+        user::{DynUsersRepository, User},
+        user_identity::DynUserIdentitiesRepository,
+    },
+    mocks::{stub_config_with, PersonalDataServiceTestFixture},
+    server::{
+        dtos::personal_data_dto::DeleteAccountDto,
+        error::Error,
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            personal_data_services::{PersonalDataService, PersonalDataServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::argon_utils::DynArgonUtil,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn stub_request() -> DeleteAccountDto {
+    DeleteAccountDto {
+        password: Some(String::from("stub password")),
+    }
+}
+
+fn build_service(fixture: PersonalDataServiceTestFixture) -> PersonalDataService {
+    PersonalDataService::new(
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_api_keys_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_identities_repository) as DynUserIdentitiesRepository,
+        Arc::new(fixture.mock_passkeys_repository) as DynPasskeysRepository,
+        Arc::new(fixture.mock_transactions_repository) as DynTransactionsRepository,
+        Arc::new(fixture.mock_budgets_repository) as DynBudgetsRepository,
+        Arc::new(fixture.mock_accounts_repository) as DynAccountsRepository,
+        Arc::new(fixture.mock_recurring_transactions_repository)
+            as DynRecurringTransactionsRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn schedule_deletion_after_grace_period_and_revoke_sessions_and_api_keys() {
+    // arrange
+    let mut fixture = PersonalDataServiceTestFixture {
+        config: stub_config_with(&["--account-deletion-grace-days=7"]),
+        ..Default::default()
+    };
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .with(eq("stub email"), eq(None))
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .with(eq("hashed password"), eq(String::from("stub password")))
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_users_repository
+        .expect_schedule_user_deletion()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            function(|delete_after: &OffsetDateTime| {
+                let expected =
+                    OffsetDateTime::from(SystemTime::now() + Duration::from_secs(7 * 86400));
+                (expected - *delete_after).abs() < time::Duration::seconds(5)
+            }),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_api_keys_repository
+        .expect_delete_user_api_keys()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(2));
+
+    let personal_data_service = build_service(fixture);
+
+    // act
+    let response = personal_data_service
+        .delete_account(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(),
+            None,
+        )
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_invalid_login_attempt_when_password_is_incorrect() {
+    // arrange
+    let mut fixture = PersonalDataServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(false));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_record_failure()
+        .with(eq("stub email"), eq(None))
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_users_repository
+        .expect_schedule_user_deletion()
+        .never();
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .never();
+
+    fixture
+        .mock_api_keys_repository
+        .expect_delete_user_api_keys()
+        .never();
+
+    let personal_data_service = build_service(fixture);
+
+    // act
+    let response = personal_data_service
+        .delete_account(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(),
+            None,
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::InvalidLoginAttmpt)));
+}
+
+#[tokio::test]
+async fn return_too_many_login_attempts_when_account_is_locked_out() {
+    // arrange
+    let mut fixture = PersonalDataServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .with(eq("stub email"), eq(None))
+        .times(1)
+        .return_once(move |_, _| Err(Error::TooManyLoginAttempts));
+
+    fixture.mock_argon_util.expect_verify_password().never();
+
+    fixture
+        .mock_users_repository
+        .expect_schedule_user_deletion()
+        .never();
+
+    let personal_data_service = build_service(fixture);
+
+    // act
+    let response = personal_data_service
+        .delete_account(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            stub_request(),
+            None,
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::TooManyLoginAttempts)));
+}
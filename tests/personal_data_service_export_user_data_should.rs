@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        api_key::{ApiKey, DynApiKeysRepository},
+        budget::{Budget, DynBudgetsRepository},
+        category::{Category, DynCategoriesRepository},
+        passkey::{DynPasskeysRepository, Passkey},
+        recurring_transaction::{DynRecurringTransactionsRepository, RecurringTransaction},
+        transaction::{DynTransactionsRepository, Transaction, TransactionFilter},
+        user::{DynUsersRepository, User},
+        user_identity::{DynUserIdentitiesRepository, UserIdentity},
+    },
+    mocks::PersonalDataServiceTestFixture,
+    server::{
+        dtos::{personal_data_dto::UserExportDto, session_dto::SessionDto},
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            personal_data_services::{PersonalDataService, PersonalDataServiceTrait},
+            session_services::DynSessionsService,
+        },
+        utils::argon_utils::DynArgonUtil,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn build_service(fixture: PersonalDataServiceTestFixture) -> PersonalDataService {
+    PersonalDataService::new(
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_api_keys_repository) as DynApiKeysRepository,
+        Arc::new(fixture.mock_identities_repository) as DynUserIdentitiesRepository,
+        Arc::new(fixture.mock_passkeys_repository) as DynPasskeysRepository,
+        Arc::new(fixture.mock_transactions_repository) as DynTransactionsRepository,
+        Arc::new(fixture.mock_budgets_repository) as DynBudgetsRepository,
+        Arc::new(fixture.mock_accounts_repository) as DynAccountsRepository,
+        Arc::new(fixture.mock_recurring_transactions_repository)
+            as DynRecurringTransactionsRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn return_everything_stored_about_user() {
+    // arrange
+    let mut fixture = PersonalDataServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_categories_repository
+        .expect_get_categories()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Category::default()]));
+
+    fixture
+        .mock_sessions_services
+        .expect_get_sessions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![SessionDto {
+                id: uuid!("2bbd3c8b-8a5b-4a5c-9f5a-3f6f3c1b7e21"),
+                user_agent: String::from("stub user agent"),
+                ip_address: Some(String::from("203.0.113.7")),
+                browser: Some(String::from("Firefox")),
+                os: Some(String::from("Android")),
+                device: Some(String::from("mobile")),
+                created_at: OffsetDateTime::now_utc(),
+                last_seen_at: OffsetDateTime::now_utc(),
+                exp: OffsetDateTime::now_utc(),
+            }])
+        });
+
+    fixture
+        .mock_api_keys_repository
+        .expect_get_api_keys_by_user_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![ApiKey::default()]));
+
+    fixture
+        .mock_identities_repository
+        .expect_get_user_identities_by_user_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![UserIdentity::default()]));
+
+    fixture
+        .mock_passkeys_repository
+        .expect_get_passkeys_by_user_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Passkey::default()]));
+
+    fixture
+        .mock_transactions_repository
+        .expect_get_transactions()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq(TransactionFilter::default()),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(vec![Transaction::default()]));
+
+    fixture
+        .mock_budgets_repository
+        .expect_get_all_budgets()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Budget::default()]));
+
+    fixture
+        .mock_accounts_repository
+        .expect_get_accounts()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Account::default()]));
+
+    fixture
+        .mock_recurring_transactions_repository
+        .expect_get_recurring_transactions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![RecurringTransaction::default()]));
+
+    let personal_data_service = build_service(fixture);
+
+    // act
+    let body = personal_data_service
+        .export_user_data(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        .await
+        .unwrap();
+
+    // assert
+    let bytes = hyper::body::to_bytes(body).await.unwrap();
+    let export: UserExportDto = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(export.profile.email, "stub email");
+    assert_eq!(export.categories.len(), 1);
+    assert_eq!(export.sessions.len(), 1);
+    assert_eq!(export.api_keys.len(), 1);
+    assert_eq!(export.identities.len(), 1);
+    assert_eq!(export.passkeys.len(), 1);
+    assert_eq!(export.transactions.len(), 1);
+    assert_eq!(export.budgets.len(), 1);
+    assert_eq!(export.accounts.len(), 1);
+    assert_eq!(export.recurring_transactions.len(), 1);
+}
+
+#[tokio::test]
+async fn abort_the_archive_when_a_section_fails() {
+    // arrange
+    let mut fixture = PersonalDataServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_categories_repository
+        .expect_get_categories()
+        .times(1)
+        .return_once(move |_| Err(anyhow::anyhow!("stub error")));
+
+    fixture.mock_sessions_services.expect_get_sessions().never();
+
+    let personal_data_service = build_service(fixture);
+
+    // act
+    let body = personal_data_service
+        .export_user_data(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        .await
+        .unwrap();
+
+    // assert
+    assert!(hyper::body::to_bytes(body).await.is_err());
+}
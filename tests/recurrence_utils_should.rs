@@ -0,0 +1,125 @@
+use rest_api::database::recurring_transaction::{RecurrenceFrequency, RecurrenceRule};
+use rest_api::server::error::Error;
+use rest_api::server::utils::recurrence_utils;
+use time::macros::date;
+use time::Date;
+
+#[test]
+fn parse_supported_rule_parts() {
+    let rule = recurrence_utils::parse_rrule("RRULE:FREQ=weekly;INTERVAL=2;UNTIL=20231231T235959Z")
+        .unwrap();
+
+    assert_eq!(
+        rule,
+        RecurrenceRule {
+            frequency: RecurrenceFrequency::Weekly,
+            interval: 2,
+            until: Some(date!(2023 - 12 - 31)),
+            count: None,
+        }
+    );
+}
+
+#[test]
+fn reject_unsupported_or_conflicting_rules() {
+    let rules = [
+        "FREQ=MONTHLY;BYDAY=MO",
+        "INTERVAL=2",
+        "FREQ=HOURLY",
+        "FREQ=DAILY;COUNT=0",
+        "FREQ=DAILY;COUNT=2;UNTIL=20231231",
+        "FREQ=DAILY;FREQ=WEEKLY",
+        "FREQ=DAILY;UNTIL=2023-12-31",
+    ];
+
+    for rrule in rules {
+        assert!(
+            matches!(
+                recurrence_utils::parse_rrule(rrule),
+                Err(Error::BadRequest(_))
+            ),
+            "{} should be rejected",
+            rrule
+        );
+    }
+}
+
+#[test]
+fn reject_until_dates_with_multibyte_characters() {
+    for rrule in [
+        "FREQ=DAILY;UNTIL=123é567",
+        "FREQ=DAILY;UNTIL=2023é231",
+        "FREQ=DAILY;UNTIL=202312é1",
+    ] {
+        assert!(
+            matches!(
+                recurrence_utils::parse_rrule(rrule),
+                Err(Error::BadRequest(_))
+            ),
+            "{} should be rejected",
+            rrule
+        );
+    }
+}
+
+#[test]
+fn format_rules_back_into_rrules() {
+    for rrule in [
+        "FREQ=MONTHLY",
+        "FREQ=YEARLY;INTERVAL=2;COUNT=5",
+        "FREQ=DAILY;UNTIL=20230105",
+    ] {
+        let rule = recurrence_utils::parse_rrule(rrule).unwrap();
+
+        assert_eq!(recurrence_utils::format_rrule(&rule), rrule);
+    }
+}
+
+#[test]
+fn skip_months_without_the_start_day() {
+    let rule = recurrence_utils::parse_rrule("FREQ=MONTHLY;COUNT=4").unwrap();
+
+    let occurrences: Vec<Date> =
+        recurrence_utils::occurrences(&rule, date!(2023 - 01 - 31)).collect();
+
+    assert_eq!(
+        occurrences,
+        vec![
+            date!(2023 - 01 - 31),
+            date!(2023 - 03 - 31),
+            date!(2023 - 05 - 31),
+            date!(2023 - 07 - 31),
+        ]
+    );
+}
+
+#[test]
+fn stop_after_the_until_date() {
+    let rule = recurrence_utils::parse_rrule("FREQ=WEEKLY;INTERVAL=2;UNTIL=20230301").unwrap();
+
+    let occurrences: Vec<Date> =
+        recurrence_utils::occurrences(&rule, date!(2023 - 02 - 01)).collect();
+
+    assert_eq!(
+        occurrences,
+        vec![
+            date!(2023 - 02 - 01),
+            date!(2023 - 02 - 15),
+            date!(2023 - 03 - 01),
+        ]
+    );
+}
+
+#[test]
+fn repeat_leap_days_only_in_leap_years() {
+    let rule = recurrence_utils::parse_rrule("FREQ=YEARLY").unwrap();
+
+    let occurrences: Vec<Date> = recurrence_utils::occurrences(&rule, date!(2024 - 02 - 29))
+        .take(2)
+        .collect();
+
+    assert_eq!(
+        occurrences,
+        vec![date!(2024 - 02 - 29), date!(2028 - 02 - 29)]
+    );
+}
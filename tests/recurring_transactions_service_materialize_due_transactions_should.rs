@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::DynAccountsRepository,
+        category::DynCategoriesRepository,
+        recurring_transaction::{DynRecurringTransactionsRepository, RecurringTransaction},
+    },
+    mocks::RecurringTransactionsServiceTestFixture,
+    server::services::recurring_transaction_services::{
+        RecurringTransactionsService, RecurringTransactionsServiceTrait,
+    },
+};
+use time::macros::date;
+use time::Date;
+use uuid::uuid;
+
+fn build_service(fixture: RecurringTransactionsServiceTestFixture) -> RecurringTransactionsService {
+    RecurringTransactionsService::new(
+        Arc::new(fixture.mock_repository) as DynRecurringTransactionsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_accounts_repository) as DynAccountsRepository,
+    )
+}
+
+#[tokio::test]
+async fn catch_up_on_occurrences_missed_since_the_last_run() {
+    // arrange
+    let mut fixture = RecurringTransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_due_recurring_transactions()
+        .with(eq(date!(2023 - 06 - 15)))
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![RecurringTransaction {
+                next_date: Some(date!(2023 - 03 - 31)),
+                materialized_count: 1,
+                ..Default::default()
+            }])
+        });
+
+    fixture
+        .mock_repository
+        .expect_create_occurrences()
+        .withf(|_, dates: &Vec<Date>, next_date: &Option<Date>| {
+            *dates == vec![date!(2023 - 03 - 31), date!(2023 - 05 - 31)]
+                && *next_date == Some(date!(2023 - 07 - 31))
+        })
+        .times(1)
+        .return_once(move |_, dates, _| Ok(dates.len() as u64));
+
+    let recurring_transactions_service = build_service(fixture);
+
+    // act
+    let response = recurring_transactions_service
+        .materialize_due_transactions(date!(2023 - 06 - 15))
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn end_the_schedule_once_the_count_is_reached() {
+    // arrange
+    let mut fixture = RecurringTransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_due_recurring_transactions()
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![RecurringTransaction {
+                count: Some(2),
+                ..Default::default()
+            }])
+        });
+
+    fixture
+        .mock_repository
+        .expect_create_occurrences()
+        .withf(|_, dates: &Vec<Date>, next_date: &Option<Date>| {
+            *dates == vec![date!(2023 - 01 - 31), date!(2023 - 03 - 31)] && next_date.is_none()
+        })
+        .times(1)
+        .return_once(move |_, dates, _| Ok(dates.len() as u64));
+
+    let recurring_transactions_service = build_service(fixture);
+
+    // act
+    let response = recurring_transactions_service
+        .materialize_due_transactions(date!(2023 - 12 - 31))
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn keep_recording_other_schedules_when_one_fails() {
+    // arrange
+    let mut fixture = RecurringTransactionsServiceTestFixture::default();
+    let failing_id = uuid!("7c9e1b3d-5f7a-4b9c-8d0e-2f4a6b8c0d1e");
+
+    fixture
+        .mock_repository
+        .expect_get_due_recurring_transactions()
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![
+                RecurringTransaction {
+                    id: failing_id,
+                    ..Default::default()
+                },
+                RecurringTransaction::default(),
+            ])
+        });
+
+    fixture
+        .mock_repository
+        .expect_create_occurrences()
+        .times(2)
+        .returning(move |recurring_transaction, dates, _| {
+            if recurring_transaction.id == failing_id {
+                return Err(anyhow::anyhow!("stub failure"));
+            }
+
+            Ok(dates.len() as u64)
+        });
+
+    let recurring_transactions_service = build_service(fixture);
+
+    // act
+    let response = recurring_transactions_service
+        .materialize_due_transactions(date!(2023 - 02 - 15))
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap(), 1);
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        role::{DynRolesRepository, Role},
+        user::{DynUsersRepository, User},
+    },
+    mocks::RolesServiceTestFixture,
+    server::{
+        error::Error,
+        services::{
+            role_services::{RolesService, RolesServiceTrait},
+            session_services::DynSessionsService,
+        },
+    },
+};
+use uuid::uuid;
+
+#[tokio::test]
+async fn return_bad_request_when_admin_revokes_own_admin_role() {
+    // arrange
+    let mut fixture = RolesServiceTestFixture::default();
+    let admin_id = uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e");
+
+    fixture.mock_repository.expect_revoke_role().never();
+
+    let roles_service = RolesService::new(
+        Arc::new(fixture.mock_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+    );
+
+    // act
+    let response = roles_service
+        .revoke_role(admin_id, admin_id, String::from("admin"))
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn return_not_found_when_role_does_not_exist() {
+    // arrange
+    let mut fixture = RolesServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(User::default()));
+
+    fixture
+        .mock_repository
+        .expect_get_role_by_name()
+        .with(eq("auditor"))
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_repository.expect_revoke_role().never();
+
+    let roles_service = RolesService::new(
+        Arc::new(fixture.mock_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+    );
+
+    // act
+    let response = roles_service
+        .revoke_role(
+            uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9"),
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            String::from("auditor"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn return_remaining_roles_after_revoking() {
+    // arrange
+    let mut fixture = RolesServiceTestFixture::default();
+
+    fixture
+        .mock_users_repository
+        .expect_get_user_by_id()
+        .times(2)
+        .returning(move |_| Ok(User::default()));
+
+    fixture
+        .mock_repository
+        .expect_get_role_by_name()
+        .with(eq("admin"))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(Role {
+                id: uuid!("2e8c4a6f-0b3d-4f7e-9a1c-5d7b9e1f3a5c"),
+                name: String::from("admin"),
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_repository
+        .expect_revoke_role()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq(uuid!("2e8c4a6f-0b3d-4f7e-9a1c-5d7b9e1f3a5c")),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_access_tokens()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    let roles_service = RolesService::new(
+        Arc::new(fixture.mock_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_users_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+    );
+
+    // act
+    let response = roles_service
+        .revoke_role(
+            uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9"),
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            String::from("admin"),
+        )
+        .await;
+
+    // assert
+    let roles = response.unwrap();
+    assert_eq!(roles.len(), 1);
+    assert_eq!(roles[0].name, "user");
+}
@@ -0,0 +1,275 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        revoked_access_token::DynRevokedAccessTokensRepository,
+        role::{DynRolesRepository, Role},
+        session::DynSessionsRepository,
+    },
+    mocks::{stub_config_with, SessionsServiceTestFixture},
+    server::{
+        error::Error,
+        services::session_services::{SessionsService, SessionsServiceTrait},
+        utils::jwt_utils::{AccessToken, DynJwtUtil},
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+fn stub_access_token() -> AccessToken {
+    AccessToken {
+        user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+        jti: uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c"),
+        grants: Default::default(),
+        actor_id: None,
+        issued_at: OffsetDateTime::from(SystemTime::now()),
+        expires_at: OffsetDateTime::from(SystemTime::now()),
+    }
+}
+
+fn build_service(fixture: SessionsServiceTestFixture) -> SessionsService {
+    SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_token_is_revoked() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+    let access_token = stub_access_token();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .with(
+            eq(uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c")),
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq(access_token.issued_at),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(true));
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .ensure_access_token_active(&access_token)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_success_when_token_is_not_revoked() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .times(1)
+        .return_once(move |_, _, _| Ok(false));
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .ensure_access_token_active(&stub_access_token())
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+fn stub_impersonation_token() -> AccessToken {
+    AccessToken {
+        actor_id: Some(uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9")),
+        ..stub_access_token()
+    }
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_tokens_of_impersonating_admin_are_revoked() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+    let access_token = stub_impersonation_token();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .with(
+            eq(uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c")),
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq(access_token.issued_at),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(false));
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .with(
+            eq(uuid!("5c1e7a3b-9d2f-4b6e-8a0c-3f5d7b9e1a2c")),
+            eq(uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9")),
+            eq(access_token.issued_at),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(true));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .never();
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .ensure_access_token_active(&access_token)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_impersonating_admin_lost_the_admin_role() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .times(2)
+        .returning(move |_, _, _| Ok(false));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .with(eq(uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9")))
+        .times(1)
+        .return_once(move |_| Ok(vec![]));
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .ensure_access_token_active(&stub_impersonation_token())
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_success_when_impersonating_admin_is_still_an_admin() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_is_access_token_revoked()
+        .times(2)
+        .returning(move |_, _, _| Ok(false));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .with(eq(uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9")))
+        .times(1)
+        .return_once(move |_| {
+            Ok(vec![Role {
+                name: String::from("admin"),
+                ..Default::default()
+            }])
+        });
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .ensure_access_token_active(&stub_impersonation_token())
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn revoke_tokens_issued_up_to_and_including_now() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+    let revoked_at = OffsetDateTime::now_utc();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_revoke_user_access_tokens()
+        .withf(move |user_id, issued_before, expires_at| {
+            *user_id == uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")
+                && *issued_before > revoked_at
+                && *issued_before <= OffsetDateTime::now_utc() + Duration::from_secs(1)
+                && *expires_at > revoked_at
+        })
+        .times(1)
+        .return_once(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_delete_expired_revoked_access_tokens()
+        .times(1)
+        .return_once(move || Ok(()));
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .revoke_user_access_tokens(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn keep_user_revocation_until_impersonation_tokens_expire() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture {
+        config: stub_config_with(&[
+            "--access-token-ttl-seconds=300",
+            "--impersonation-ttl-seconds=3600",
+        ]),
+        ..Default::default()
+    };
+    let revoked_at = OffsetDateTime::now_utc();
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_revoke_user_access_tokens()
+        .withf(move |_, _, expires_at| *expires_at >= revoked_at + Duration::from_secs(3600))
+        .times(1)
+        .return_once(move |_, _, _| Ok(()));
+
+    fixture
+        .mock_revoked_access_tokens_repository
+        .expect_delete_expired_revoked_access_tokens()
+        .times(1)
+        .return_once(move || Ok(()));
+
+    let sessions_service = build_service(fixture);
+
+    // act
+    let response = sessions_service
+        .revoke_user_access_tokens(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"))
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
@@ -0,0 +1,166 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        revoked_access_token::DynRevokedAccessTokensRepository,
+        role::DynRolesRepository,
+        session::{DynSessionsRepository, Session, SessionMetadata},
+        user::User,
+    },
+    mocks::{stub_config_with, SessionsServiceTestFixture},
+    server::{
+        dtos::session_dto::NewSessionDto,
+        services::session_services::{SessionsService, SessionsServiceTrait},
+        utils::jwt_utils::DynJwtUtil,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+const FIREFOX_ON_ANDROID: &str =
+    "Mozilla/5.0 (Android 13; Mobile; rv:109.0) Gecko/114.0 Firefox/114.0";
+
+#[tokio::test]
+async fn expire_session_and_refresh_token_after_idle_timeout() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture {
+        config: stub_config_with(&["--session-idle-timeout-minutes=60"]),
+        ..Default::default()
+    };
+    let earliest_exp = OffsetDateTime::from(SystemTime::now() + Duration::from_secs(3600));
+    let session_exp = OffsetDateTime::from(SystemTime::now() + Duration::from_secs(3600));
+
+    fixture
+        .mock_repository
+        .expect_new_session()
+        .withf(move |user_id, metadata, exp| {
+            let latest_exp = OffsetDateTime::from(SystemTime::now() + Duration::from_secs(3600));
+
+            *user_id == uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")
+                && metadata.user_agent == "stub user agent"
+                && *exp >= earliest_exp
+                && *exp <= latest_exp
+        })
+        .times(1)
+        .return_once(move |_, _, _| {
+            Ok(Session {
+                exp: session_exp,
+                ..Default::default()
+            })
+        });
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_session_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![]));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_access_token()
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub access token")));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_refresh_token()
+        .with(always(), always(), eq(session_exp))
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub refresh token")));
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .new_session(NewSessionDto {
+            user_id: Some(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            user_agent: Some(String::from("stub user agent")),
+            ip_address: None,
+        })
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap().refresh_token, "stub refresh token");
+}
+
+#[tokio::test]
+async fn record_client_ip_and_device_of_session() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_new_session()
+        .with(
+            always(),
+            eq(SessionMetadata {
+                user_agent: String::from(FIREFOX_ON_ANDROID),
+                ip_address: Some(String::from("203.0.113.7")),
+                browser: Some(String::from("Firefox")),
+                os: Some(String::from("Android")),
+                device: Some(String::from("mobile")),
+            }),
+            always(),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(Session::default()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_session_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![]));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_access_token()
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub access token")));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_refresh_token()
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub refresh token")));
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .new_session(NewSessionDto {
+            user_id: Some(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            user_agent: Some(String::from(FIREFOX_ON_ANDROID)),
+            ip_address: Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))),
+        })
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
@@ -0,0 +1,298 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        revoked_access_token::DynRevokedAccessTokensRepository,
+        role::{AccessGrants, DynRolesRepository, Role},
+        session::{DynSessionsRepository, Session},
+        user::User,
+    },
+    mocks::SessionsServiceTestFixture,
+    server::{
+        error::Error,
+        services::session_services::{SessionsService, SessionsServiceTrait},
+        utils::jwt_utils::DynJwtUtil,
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+#[tokio::test]
+async fn return_new_refresh_token_when_token_is_current() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_rotate_refresh_token()
+        .with(
+            eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")),
+            eq(uuid!("0a4f6c62-3d8e-4b3c-8f0e-6d7f3b8f9a21")),
+            always(),
+            always(),
+        )
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(Some(Session::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_session_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture.mock_repository.expect_delete_session().times(0);
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_access_token()
+        .with(
+            always(),
+            always(),
+            eq(AccessGrants {
+                roles: vec![String::from("user")],
+                permissions: vec![
+                    String::from("categories:read"),
+                    String::from("categories:write"),
+                ],
+            }),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub access token")));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_refresh_token()
+        .with(
+            eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")),
+            eq(uuid!("2e3d5d1e-6f0f-4b0e-9d54-5b1a3f3f7c11")),
+            always(),
+        )
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub refresh token")));
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .refresh_access_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("0a4f6c62-3d8e-4b3c-8f0e-6d7f3b8f9a21"),
+        )
+        .await;
+
+    // assert
+    let (_, refresh_token) = response.unwrap();
+    assert_eq!(refresh_token, "stub refresh token");
+}
+
+#[tokio::test]
+async fn revoke_session_when_old_token_is_reused() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_rotate_refresh_token()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .with(eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")))
+        .times(1)
+        .return_once(move |_| Ok(Some(Session::default())));
+
+    fixture
+        .mock_repository
+        .expect_delete_session()
+        .with(eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture.mock_jwt_util.expect_new_refresh_token().times(0);
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .refresh_access_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("0a4f6c62-3d8e-4b3c-8f0e-6d7f3b8f9a21"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_when_session_does_not_exist() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_rotate_refresh_token()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_repository.expect_delete_session().times(0);
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .refresh_access_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("0a4f6c62-3d8e-4b3c-8f0e-6d7f3b8f9a21"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn extend_session_by_idle_timeout_without_exceeding_max_age() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+    let earliest_exp = OffsetDateTime::from(SystemTime::now() + Duration::from_secs(10080 * 60));
+    let extended_exp = OffsetDateTime::from(SystemTime::now() + Duration::from_secs(3600));
+
+    fixture
+        .mock_repository
+        .expect_rotate_refresh_token()
+        .withf(move |_, _, exp, max_age| {
+            let latest_exp =
+                OffsetDateTime::from(SystemTime::now() + Duration::from_secs(10080 * 60));
+
+            *exp >= earliest_exp
+                && *exp <= latest_exp
+                && *max_age == Duration::from_secs(30 * 86400)
+        })
+        .times(1)
+        .return_once(move |_, _, _, _| {
+            Ok(Some(Session {
+                exp: extended_exp,
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_session_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_roles_repository
+        .expect_get_roles_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(vec![Role::default()]));
+
+    fixture
+        .mock_jwt_util
+        .expect_new_access_token()
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub access token")));
+
+    // the refresh token never outlives the session it belongs to
+    fixture
+        .mock_jwt_util
+        .expect_new_refresh_token()
+        .with(always(), always(), eq(extended_exp))
+        .times(1)
+        .return_once(move |_, _, _| Ok(String::from("stub refresh token")));
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .refresh_access_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("2e3d5d1e-6f0f-4b0e-9d54-5b1a3f3f7c11"),
+        )
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_unauthorized_without_revoking_when_session_has_expired() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_rotate_refresh_token()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Session::default())));
+
+    fixture.mock_repository.expect_delete_session().times(0);
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .refresh_access_token(
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+            uuid!("2e3d5d1e-6f0f-4b0e-9d54-5b1a3f3f7c11"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
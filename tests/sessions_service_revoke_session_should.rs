@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        revoked_access_token::DynRevokedAccessTokensRepository,
+        role::DynRolesRepository,
+        session::{DynSessionsRepository, Session},
+    },
+    mocks::SessionsServiceTestFixture,
+    server::{
+        error::Error,
+        services::session_services::{SessionsService, SessionsServiceTrait},
+        utils::jwt_utils::DynJwtUtil,
+    },
+};
+use uuid::uuid;
+
+#[tokio::test]
+async fn return_success_when_session_belongs_to_user() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .with(eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")))
+        .times(1)
+        .return_once(move |_| Ok(Some(Session::default())));
+
+    fixture
+        .mock_repository
+        .expect_delete_session()
+        .with(eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .revoke_session(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+        )
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_forbidden_when_session_belongs_to_another_user() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .with(eq(uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b")))
+        .times(1)
+        .return_once(move |_| Ok(Some(Session::default())));
+
+    fixture.mock_repository.expect_delete_session().times(0);
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .revoke_session(
+            uuid!("a1b2c3d4-ffa3-4b58-91b0-612a1c801a5e"),
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
+
+#[tokio::test]
+async fn return_not_found_when_session_does_not_exist() {
+    // arrange
+    let mut fixture = SessionsServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_session_by_id()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_repository.expect_delete_session().times(0);
+
+    let sessions_service = SessionsService::new(
+        Arc::new(fixture.mock_repository) as DynSessionsRepository,
+        Arc::new(fixture.mock_roles_repository) as DynRolesRepository,
+        Arc::new(fixture.mock_revoked_access_tokens_repository) as DynRevokedAccessTokensRepository,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        fixture.config,
+    );
+
+    // act
+    let response = sessions_service
+        .revoke_session(
+            uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+            uuid!("8147a9f8-2845-4f92-9e1d-0c0c6c8db79b"),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::NotFound(_))));
+}
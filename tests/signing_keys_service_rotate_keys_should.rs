@@ -0,0 +1,165 @@
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use rest_api::{
+    config::JwtAlgorithm,
+    database::signing_key::{DynSigningKeysRepository, SigningKey},
+    mocks::{stub_config_with, SigningKeysServiceTestFixture},
+    server::{
+        services::signing_key_services::{SigningKeysService, SigningKeysServiceTrait},
+        utils::signing_key_utils::{self, KeyRing},
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+
+fn stored_key(activates_at: SystemTime) -> SigningKey {
+    let generated_key = signing_key_utils::generate_signing_key(JwtAlgorithm::EdDsa).unwrap();
+
+    SigningKey {
+        kid: generated_key.kid,
+        algorithm: String::from("EdDSA"),
+        private_key: generated_key.private_key,
+        public_jwk: serde_json::to_string(&generated_key.public_jwk).unwrap(),
+        activates_at: OffsetDateTime::from(activates_at),
+        ..Default::default()
+    }
+}
+
+fn build_service(fixture: SigningKeysServiceTestFixture) -> SigningKeysService {
+    SigningKeysService::new(
+        Arc::new(fixture.mock_repository) as DynSigningKeysRepository,
+        Arc::new(KeyRing::default()),
+        fixture.config,
+    )
+}
+
+#[tokio::test]
+async fn create_key_active_right_away_when_there_is_none() {
+    // arrange
+    let mut fixture = SigningKeysServiceTestFixture::default();
+    let created_key = stored_key(SystemTime::now());
+
+    fixture
+        .mock_repository
+        .expect_delete_expired_signing_keys()
+        .times(1)
+        .return_once(move || Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_signing_keys()
+        .times(1)
+        .return_once(move || Ok(vec![]));
+
+    fixture
+        .mock_repository
+        .expect_create_signing_key()
+        .withf(|_, algorithm, _, _, activates_at, _| {
+            algorithm == "EdDSA" && *activates_at <= OffsetDateTime::now_utc()
+        })
+        .times(1)
+        .return_once(move |_, _, _, _, _, _| Ok(SigningKey::default()));
+
+    fixture
+        .mock_repository
+        .expect_get_signing_keys()
+        .times(1)
+        .return_once(move || Ok(vec![created_key]));
+
+    let signing_keys_service = build_service(fixture);
+
+    // act
+    let response = signing_keys_service.rotate_keys().await;
+
+    // assert
+    assert!(response.is_ok());
+    assert_eq!(signing_keys_service.get_jwks().keys.len(), 1);
+}
+
+#[tokio::test]
+async fn keep_active_key_until_it_is_due() {
+    // arrange
+    let mut fixture = SigningKeysServiceTestFixture::default();
+    let active_key = stored_key(SystemTime::now() - Duration::from_secs(3600));
+
+    fixture
+        .mock_repository
+        .expect_delete_expired_signing_keys()
+        .times(1)
+        .return_once(move || Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_signing_keys()
+        .times(2)
+        .returning(move || Ok(vec![active_key.clone()]));
+
+    fixture.mock_repository.expect_create_signing_key().never();
+
+    let signing_keys_service = build_service(fixture);
+
+    // act
+    let response = signing_keys_service.rotate_keys().await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn publish_next_key_ahead_of_activation_when_active_key_is_due() {
+    // arrange
+    let mut fixture = SigningKeysServiceTestFixture::default();
+    let due_key = stored_key(SystemTime::now() - Duration::from_secs(720 * 3600));
+
+    fixture
+        .mock_repository
+        .expect_delete_expired_signing_keys()
+        .times(1)
+        .return_once(move || Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_signing_keys()
+        .times(2)
+        .returning(move || Ok(vec![due_key.clone()]));
+
+    fixture
+        .mock_repository
+        .expect_create_signing_key()
+        .withf(|_, _, _, _, activates_at, expires_at| {
+            *activates_at > OffsetDateTime::from(SystemTime::now().add(Duration::from_secs(60)))
+                && *expires_at > *activates_at
+        })
+        .times(1)
+        .return_once(move |_, _, _, _, _, _| Ok(SigningKey::default()));
+
+    let signing_keys_service = build_service(fixture);
+
+    // act
+    let response = signing_keys_service.rotate_keys().await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn not_manage_keys_when_signing_with_shared_secret() {
+    // arrange
+    let mut fixture = SigningKeysServiceTestFixture {
+        config: stub_config_with(&["--jwt-algorithm=hs256"]),
+        ..Default::default()
+    };
+
+    fixture.mock_repository.expect_get_signing_keys().never();
+    fixture.mock_repository.expect_create_signing_key().never();
+
+    let signing_keys_service = build_service(fixture);
+
+    // act
+    let response = signing_keys_service.rotate_keys().await;
+
+    // assert
+    assert!(response.is_ok());
+    assert!(signing_keys_service.get_jwks().keys.is_empty());
+}
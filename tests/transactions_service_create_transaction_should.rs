@@ -0,0 +1,182 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        category::{Category, DynCategoriesRepository},
+        transaction::{DynTransactionsRepository, Transaction, TransactionFields},
+    },
+    mocks::TransactionsServiceTestFixture,
+    server::{
+        dtos::transaction_dto::TransactionCreateDto,
+        error::Error,
+        services::transaction_services::{TransactionsService, TransactionsServiceTrait},
+    },
+};
+use rust_decimal::Decimal;
+use time::macros::date;
+use uuid::uuid;
+
+fn build_service(fixture: TransactionsServiceTestFixture) -> TransactionsService {
+    TransactionsService::new(
+        Arc::new(fixture.mock_repository) as DynTransactionsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_accounts_repository) as DynAccountsRepository,
+    )
+}
+
+fn stub_request(amount: &str) -> TransactionCreateDto {
+    TransactionCreateDto {
+        amount: Some(Decimal::from_str(amount).unwrap()),
+        currency: Some(String::from("usd")),
+        date: Some(date!(2023 - 07 - 17)),
+        payee: Some(String::from("stub payee")),
+        category_id: Some(Category::default().id),
+        account_id: Some(Account::default().id),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn store_exact_amount_with_uppercase_currency() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .with(eq(Category::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Category::default())));
+
+    fixture
+        .mock_accounts_repository
+        .expect_get_account_by_id()
+        .with(eq(Account::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Account::default())));
+
+    fixture
+        .mock_repository
+        .expect_create_transaction()
+        .withf(|_, fields: &TransactionFields| {
+            fields.amount.to_string() == "0.10" && fields.currency == "USD"
+        })
+        .times(1)
+        .return_once(move |_, fields| {
+            Ok(Transaction {
+                amount: fields.amount,
+                currency: fields.currency,
+                ..Default::default()
+            })
+        });
+
+    let transactions_service = build_service(fixture);
+
+    // act
+    let response = transactions_service
+        .create_transaction(Category::default().user_id, stub_request("0.10"))
+        .await;
+
+    // assert
+    let transaction = response.unwrap();
+    assert_eq!(transaction.amount.to_string(), "0.1");
+    assert_eq!(transaction.currency, "USD");
+}
+
+#[tokio::test]
+async fn return_forbidden_for_category_of_another_user() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(Category {
+                user_id: uuid!("9b2d4f6a-8c1e-4a3b-b5d7-e9f1a3c5b7d9"),
+                ..Default::default()
+            }))
+        });
+
+    fixture.mock_repository.expect_create_transaction().never();
+
+    let transactions_service = build_service(fixture);
+
+    // act
+    let response = transactions_service
+        .create_transaction(Category::default().user_id, stub_request("12.50"))
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Forbidden)));
+}
+
+#[tokio::test]
+async fn return_bad_request_when_amount_is_not_positive() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Category::default())));
+
+    fixture
+        .mock_accounts_repository
+        .expect_get_account_by_id()
+        .with(eq(Account::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Account::default())));
+
+    fixture.mock_repository.expect_create_transaction().never();
+
+    let transactions_service = build_service(fixture);
+
+    // act
+    let response = transactions_service
+        .create_transaction(Category::default().user_id, stub_request("-5.00"))
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn return_bad_request_when_currency_differs_from_account() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    fixture
+        .mock_categories_repository
+        .expect_get_category_by_id()
+        .times(1)
+        .return_once(move |_| Ok(Some(Category::default())));
+
+    fixture
+        .mock_accounts_repository
+        .expect_get_account_by_id()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(Account {
+                currency: String::from("EUR"),
+                ..Default::default()
+            }))
+        });
+
+    fixture.mock_repository.expect_create_transaction().never();
+
+    let transactions_service = build_service(fixture);
+
+    // act
+    let response = transactions_service
+        .create_transaction(Category::default().user_id, stub_request("12.50"))
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
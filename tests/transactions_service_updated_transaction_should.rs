@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        account::{Account, DynAccountsRepository},
+        category::DynCategoriesRepository,
+        transaction::{DynTransactionsRepository, Transaction, TransactionFields},
+    },
+    mocks::TransactionsServiceTestFixture,
+    server::{
+        dtos::transaction_dto::TransactionUpdateDto,
+        services::transaction_services::{TransactionsService, TransactionsServiceTrait},
+    },
+};
+
+fn build_service(fixture: TransactionsServiceTestFixture) -> TransactionsService {
+    TransactionsService::new(
+        Arc::new(fixture.mock_repository) as DynTransactionsRepository,
+        Arc::new(fixture.mock_categories_repository) as DynCategoriesRepository,
+        Arc::new(fixture.mock_accounts_repository) as DynAccountsRepository,
+    )
+}
+
+/// Expects a transaction with a note to be updated, checking the note it ends up with.
+fn expect_update(fixture: &mut TransactionsServiceTestFixture, expected_note: Option<String>) {
+    fixture
+        .mock_repository
+        .expect_get_transaction_by_id()
+        .with(eq(Transaction::default().id))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(Transaction {
+                note: Some(String::from("stub note")),
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_accounts_repository
+        .expect_get_account_by_id()
+        .with(eq(Account::default().id))
+        .times(1)
+        .return_once(move |_| Ok(Some(Account::default())));
+
+    fixture
+        .mock_repository
+        .expect_update_transaction()
+        .withf(move |_, fields: &TransactionFields| fields.note == expected_note)
+        .times(1)
+        .return_once(move |_, fields| {
+            Ok(Transaction {
+                note: fields.note,
+                ..Default::default()
+            })
+        });
+}
+
+#[tokio::test]
+async fn clear_note_when_an_empty_note_is_requested() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    expect_update(&mut fixture, None);
+
+    let transactions_service = build_service(fixture);
+    let request = TransactionUpdateDto {
+        note: Some(String::new()),
+        ..Default::default()
+    };
+
+    // act
+    let response = transactions_service
+        .updated_transaction(
+            Transaction::default().id,
+            Transaction::default().user_id,
+            request,
+        )
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap().note, None);
+}
+
+#[tokio::test]
+async fn keep_note_when_no_note_is_requested() {
+    // arrange
+    let mut fixture = TransactionsServiceTestFixture::default();
+
+    expect_update(&mut fixture, Some(String::from("stub note")));
+
+    let transactions_service = build_service(fixture);
+
+    // act
+    let response = transactions_service
+        .updated_transaction(
+            Transaction::default().id,
+            Transaction::default().user_id,
+            TransactionUpdateDto::default(),
+        )
+        .await;
+
+    // assert
+    assert_eq!(response.unwrap().note, Some(String::from("stub note")));
+}
@@ -0,0 +1,40 @@
+use rest_api::server::utils::user_agent_utils::{self, UserAgentInfo};
+
+#[test]
+fn parse_desktop_browser() {
+    let user_agent_info = user_agent_utils::parse_user_agent(
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/114.0.0.0 Safari/537.36",
+    );
+
+    assert_eq!(
+        user_agent_info,
+        UserAgentInfo {
+            browser: Some(String::from("Chrome")),
+            os: Some(String::from("Windows 10")),
+            device: Some(String::from("desktop")),
+        }
+    );
+}
+
+#[test]
+fn parse_mobile_browser() {
+    let user_agent_info = user_agent_utils::parse_user_agent(
+        "Mozilla/5.0 (iPhone; CPU iPhone OS 16_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.5 Mobile/15E148 Safari/604.1",
+    );
+
+    assert_eq!(
+        user_agent_info,
+        UserAgentInfo {
+            browser: Some(String::from("Safari")),
+            os: Some(String::from("iPhone")),
+            device: Some(String::from("mobile")),
+        }
+    );
+}
+
+#[test]
+fn leave_unknown_user_agent_empty() {
+    let user_agent_info = user_agent_utils::parse_user_agent("");
+
+    assert_eq!(user_agent_info, UserAgentInfo::default());
+}
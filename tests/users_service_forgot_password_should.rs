@@ -0,0 +1,129 @@
+use std::sync::{Arc, Mutex};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        email_verification::DynEmailVerificationsRepository,
+        password_reset::{DynPasswordResetsRepository, PasswordResetToken},
+        recovery_code::DynRecoveryCodesRepository,
+        user::{DynUsersRepository, User},
+    },
+    mocks::UsersServiceTestFixture,
+    server::{
+        dtos::user_dto::ForgotPasswordDto,
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            session_services::DynSessionsService,
+            user_services::{UsersService, UsersServiceTrait},
+        },
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::DynJwtUtil,
+            mailer_utils::{DynMailer, InMemoryMailer},
+            token_utils,
+        },
+    },
+};
+
+#[tokio::test]
+async fn send_reset_email_with_token_matching_stored_hash() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+    let mailer = Arc::new(InMemoryMailer::default());
+    let stored_hash = Arc::new(Mutex::new(String::new()));
+    let captured_hash = stored_hash.clone();
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_password_resets_repository
+        .expect_create_password_reset()
+        .times(1)
+        .return_once(move |_, token_hash, _| {
+            *captured_hash.lock().unwrap() = token_hash.to_string();
+            Ok(PasswordResetToken::default())
+        });
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        mailer.clone() as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .forgot_password(ForgotPasswordDto {
+            email: Some(String::from("stub email")),
+        })
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+
+    let messages = mailer.messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].to, "stub email");
+
+    let token = messages[0]
+        .body
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap();
+    assert_eq!(token_utils::hash_token(token), *stored_hash.lock().unwrap());
+}
+
+#[tokio::test]
+async fn return_success_without_email_when_user_does_not_exist() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture
+        .mock_password_resets_repository
+        .expect_create_password_reset()
+        .times(0);
+
+    fixture.mock_mailer.expect_send().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .forgot_password(ForgotPasswordDto {
+            email: Some(String::from("stub email")),
+        })
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
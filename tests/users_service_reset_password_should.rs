@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        email_verification::DynEmailVerificationsRepository,
+        password_reset::{DynPasswordResetsRepository, PasswordResetToken},
+        recovery_code::DynRecoveryCodesRepository,
+        user::DynUsersRepository,
+    },
+    mocks::UsersServiceTestFixture,
+    server::{
+        dtos::user_dto::ResetPasswordDto,
+        error::Error,
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            session_services::DynSessionsService,
+            user_services::{UsersService, UsersServiceTrait},
+        },
+        utils::{
+            argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil, mailer_utils::DynMailer, token_utils,
+        },
+    },
+};
+use uuid::uuid;
+
+#[tokio::test]
+async fn update_password_and_revoke_sessions_when_token_is_valid() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_password_resets_repository
+        .expect_consume_password_reset()
+        .with(eq(token_utils::hash_token("stub token")))
+        .times(1)
+        .return_once(move |_| Ok(Some(PasswordResetToken::default())));
+
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .with(eq("stub password"))
+        .times(1)
+        .return_once(move |_| Ok(String::from("hashed password")));
+
+    fixture
+        .mock_repository
+        .expect_update_password()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("hashed password"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_password_resets_repository
+        .expect_delete_password_resets_by_user_id()
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_unlock_user()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .reset_password(ResetPasswordDto {
+            token: Some(String::from("stub token")),
+            password: Some(String::from("stub password")),
+        })
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_error_when_token_is_invalid_or_used() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_password_resets_repository
+        .expect_consume_password_reset()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_argon_util.expect_hash_password().times(0);
+
+    fixture.mock_repository.expect_update_password().times(0);
+
+    fixture
+        .mock_sessions_services
+        .expect_revoke_user_sessions()
+        .times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .reset_password(ResetPasswordDto {
+            token: Some(String::from("stub token")),
+            password: Some(String::from("stub password")),
+        })
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
@@ -0,0 +1,328 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        email_verification::DynEmailVerificationsRepository,
+        password_reset::DynPasswordResetsRepository,
+        recovery_code::{DynRecoveryCodesRepository, RecoveryCode},
+        user::{DynUsersRepository, User},
+    },
+    mocks::UsersServiceTestFixture,
+    server::{
+        dtos::{session_dto::SessionResponseDto, user_dto::SignInMfaDto},
+        error::Error,
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            session_services::DynSessionsService,
+            user_services::{UsersService, UsersServiceTrait},
+        },
+        utils::{
+            argon_utils::DynArgonUtil,
+            jwt_utils::{DynJwtUtil, MfaToken},
+            mailer_utils::DynMailer,
+            token_utils, totp_utils,
+        },
+    },
+};
+use sqlx::types::time::OffsetDateTime;
+use uuid::uuid;
+
+const STUB_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+fn mfa_user() -> User {
+    User {
+        mfa_secret: Some(String::from(STUB_SECRET)),
+        mfa_enabled_at: Some(OffsetDateTime::now_utc()),
+        ..Default::default()
+    }
+}
+
+fn current_code() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    totp_utils::generate_code(STUB_SECRET, now).unwrap()
+}
+
+fn stub_mfa_token() -> MfaToken {
+    MfaToken {
+        user_id: uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e"),
+        jti: uuid!("6d8f0b2c-4e1a-4c3d-9b5e-7f9a1c3e5b7d"),
+        expires_at: OffsetDateTime::from_unix_timestamp(1_900_000_000).unwrap(),
+    }
+}
+
+/// Expects a fresh MFA token of a user that is not locked out.
+fn allow_attempt(fixture: &mut UsersServiceTestFixture) {
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_mfa_allowed()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")), eq(None))
+        .times(1)
+        .return_once(|_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_consume_mfa_token()
+        .with(eq(stub_mfa_token()))
+        .times(1)
+        .return_once(|_| Ok(()));
+}
+
+fn build_service(fixture: UsersServiceTestFixture) -> UsersService {
+    UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    )
+}
+
+fn mfa_request(code: String) -> SignInMfaDto {
+    SignInMfaDto {
+        mfa_token: Some(String::from("stub mfa token")),
+        code: Some(code),
+    }
+}
+
+#[tokio::test]
+async fn return_session_when_totp_code_is_valid() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_mfa_token()
+        .with(eq(String::from("stub mfa token")))
+        .times(1)
+        .return_once(move |_| Ok(stub_mfa_token()));
+
+    allow_attempt(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(mfa_user()));
+
+    fixture
+        .mock_repository
+        .expect_use_mfa_step()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_mfa()
+        .times(1)
+        .return_once(|_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let users_service = build_service(fixture);
+
+    // act
+    let response = users_service
+        .signin_user_mfa(mfa_request(current_code()), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_error_when_totp_code_is_replayed() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_mfa_token()
+        .times(1)
+        .return_once(move |_| Ok(stub_mfa_token()));
+
+    allow_attempt(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(mfa_user()));
+
+    fixture
+        .mock_repository
+        .expect_use_mfa_step()
+        .times(1)
+        .return_once(move |_, _| Ok(false));
+
+    fixture
+        .mock_recovery_codes_repository
+        .expect_consume_recovery_code()
+        .times(0);
+
+    fixture
+        .mock_login_throttles_services
+        .expect_record_mfa_failure()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")), eq(None))
+        .times(1)
+        .return_once(|_, _| Ok(()));
+
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = build_service(fixture);
+
+    // act
+    let response = users_service
+        .signin_user_mfa(mfa_request(current_code()), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::InvalidLoginAttmpt)));
+}
+
+#[tokio::test]
+async fn return_session_when_recovery_code_is_unused() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_mfa_token()
+        .times(1)
+        .return_once(move |_| Ok(stub_mfa_token()));
+
+    allow_attempt(&mut fixture);
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_id()
+        .times(1)
+        .return_once(move |_| Ok(mfa_user()));
+
+    fixture.mock_repository.expect_use_mfa_step().times(0);
+
+    fixture
+        .mock_recovery_codes_repository
+        .expect_consume_recovery_code()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq(token_utils::hash_token("abcdef-123456")),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(Some(RecoveryCode::default())));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_mfa()
+        .times(1)
+        .return_once(|_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let users_service = build_service(fixture);
+
+    // act
+    let response = users_service
+        .signin_user_mfa(
+            mfa_request(String::from("ABCDEF-123456")),
+            Some("test".to_string()),
+            None,
+        )
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_error_when_user_is_locked_out() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_mfa_token()
+        .times(1)
+        .return_once(move |_| Ok(stub_mfa_token()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_mfa_allowed()
+        .times(1)
+        .return_once(|_, _| Err(Error::TooManyLoginAttempts));
+
+    fixture
+        .mock_sessions_services
+        .expect_consume_mfa_token()
+        .times(0);
+    fixture.mock_repository.expect_get_user_by_id().times(0);
+    fixture.mock_repository.expect_use_mfa_step().times(0);
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = build_service(fixture);
+
+    // act
+    let response = users_service
+        .signin_user_mfa(mfa_request(current_code()), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::TooManyLoginAttempts)));
+}
+
+#[tokio::test]
+async fn return_error_when_mfa_token_is_replayed() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_jwt_util
+        .expect_get_mfa_token()
+        .times(1)
+        .return_once(move |_| Ok(stub_mfa_token()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_mfa_allowed()
+        .times(1)
+        .return_once(|_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_consume_mfa_token()
+        .with(eq(stub_mfa_token()))
+        .times(1)
+        .return_once(|_| Err(Error::Unauthorized));
+
+    fixture.mock_repository.expect_get_user_by_id().times(0);
+    fixture.mock_repository.expect_use_mfa_step().times(0);
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = build_service(fixture);
+
+    // act
+    let response = users_service
+        .signin_user_mfa(mfa_request(current_code()), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::Unauthorized)));
+}
@@ -2,17 +2,29 @@ use std::sync::Arc;
 
 use mockall::predicate::*;
 use rest_api::{
-    database::user::{DynUsersRepository, User},
-    mocks::UsersServiceTestFixture,
+    database::{
+        email_verification::DynEmailVerificationsRepository,
+        password_reset::DynPasswordResetsRepository,
+        recovery_code::DynRecoveryCodesRepository,
+        user::{DynUsersRepository, User},
+    },
+    mocks::{stub_config_with, UsersServiceTestFixture},
     server::{
-        dtos::user_dto::{SignInUserDto, SignUpUserDto},
+        dtos::{
+            session_dto::SessionResponseDto,
+            user_dto::{SignInOutcome, SignInUserDto, SignUpUserDto},
+        },
+        error::Error,
         services::{
+            login_throttle_services::DynLoginThrottlesService,
             session_services::DynSessionsService,
             user_services::{UsersService, UsersServiceTrait},
         },
-        utils::{argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil},
+        utils::{argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil, mailer_utils::DynMailer},
     },
 };
+use sqlx::types::time::OffsetDateTime;
+use std::net::{IpAddr, Ipv4Addr};
 use uuid::uuid;
 
 #[tokio::test]
@@ -20,6 +32,19 @@ async fn return_success_when_downstream_services_succeedand_user_exists() {
     // arrange
     let mut fixture = UsersServiceTestFixture::default();
 
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
     fixture
         .mock_repository
         .expect_get_user_by_email()
@@ -34,26 +59,45 @@ async fn return_success_when_downstream_services_succeedand_user_exists() {
         .times(1)
         .return_once(move |_, _| Ok(true));
 
+    fixture
+        .mock_argon_util
+        .expect_needs_rehash()
+        .times(1)
+        .return_once(move |_| false);
+
     fixture
         .mock_jwt_util
         .expect_new_access_token()
         .with(
             eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
             eq("stub email"),
+            always(),
         )
         .times(0)
-        .return_once(move |_, _| Ok(String::from("stub token")));
+        .return_once(move |_, _, _| Ok(String::from("stub token")));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
 
     let users_service = UsersService::new(
         Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
         Arc::new(fixture.mock_argon_util) as DynArgonUtil,
         Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
         Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
     );
 
     // act
     let response = users_service
-        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()))
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
         .await;
 
     // assert
@@ -80,9 +124,15 @@ async fn return_error_when_user_exixsts() {
 
     let users_service = UsersService::new(
         Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
         Arc::new(fixture.mock_argon_util) as DynArgonUtil,
         Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
         Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
     );
 
     // act
@@ -91,3 +141,453 @@ async fn return_error_when_user_exixsts() {
     // assert
     assert!(response.is_err());
 }
+
+#[tokio::test]
+async fn return_error_when_email_is_not_verified_and_verification_is_required() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_argon_util
+        .expect_needs_rehash()
+        .times(1)
+        .return_once(move |_| false);
+
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        stub_config_with(&["--require-email-verification"]),
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::EmailNotVerified)));
+}
+
+#[tokio::test]
+async fn return_mfa_token_when_mfa_is_enabled() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(User {
+                mfa_secret: Some(String::from("stub secret")),
+                mfa_enabled_at: Some(OffsetDateTime::now_utc()),
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_argon_util
+        .expect_needs_rehash()
+        .times(1)
+        .return_once(move |_| false);
+
+    fixture
+        .mock_jwt_util
+        .expect_new_mfa_token()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(String::from("stub mfa token")));
+
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Ok(SignInOutcome::MfaRequired(token)) if token == "stub mfa token"));
+}
+
+#[tokio::test]
+async fn return_invalid_login_attempt_when_email_does_not_exist() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+    let ip_address = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("stub email"))
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    // the attempted password is still hashed so unknown emails take as long as wrong passwords
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .with(eq("stub password"))
+        .times(1)
+        .return_once(move |_| Ok(String::from("hashed password")));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_record_failure()
+        .with(eq("stub email"), eq(Some(ip_address)))
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture.mock_sessions_services.expect_new_session().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(
+            SignInUserDto::new_stub(),
+            Some("test".to_string()),
+            Some(ip_address),
+        )
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::InvalidLoginAttmpt)));
+}
+
+#[tokio::test]
+async fn record_failure_when_password_is_incorrect() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(false));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_record_failure()
+        .with(eq("stub email"), eq(None))
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::InvalidLoginAttmpt)));
+}
+
+#[tokio::test]
+async fn return_too_many_login_attempts_without_checking_password_when_locked() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Err(Error::TooManyLoginAttempts));
+
+    fixture.mock_repository.expect_get_user_by_email().times(0);
+    fixture.mock_argon_util.expect_verify_password().times(0);
+    fixture
+        .mock_login_throttles_services
+        .expect_record_failure()
+        .times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::TooManyLoginAttempts)));
+}
+
+#[tokio::test]
+async fn rehash_password_when_hash_is_outdated() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| Ok(Some(User::default())));
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_argon_util
+        .expect_needs_rehash()
+        .with(eq("hashed password"))
+        .times(1)
+        .return_once(move |_| true);
+
+    fixture
+        .mock_argon_util
+        .expect_hash_password()
+        .with(eq("stub password"))
+        .times(1)
+        .return_once(move |_| Ok(String::from("rehashed password")));
+
+    fixture
+        .mock_repository
+        .expect_update_password()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("rehashed password"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn cancel_scheduled_deletion_when_user_signs_in() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_login_throttles_services
+        .expect_ensure_allowed()
+        .times(1)
+        .return_once(move |_, _| Ok(()));
+
+    fixture
+        .mock_login_throttles_services
+        .expect_clear_account()
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(User {
+                delete_after: Some(OffsetDateTime::now_utc()),
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_argon_util
+        .expect_verify_password()
+        .times(1)
+        .return_once(move |_, _| Ok(true));
+
+    fixture
+        .mock_argon_util
+        .expect_needs_rehash()
+        .times(1)
+        .return_once(move |_| false);
+
+    fixture
+        .mock_repository
+        .expect_cancel_user_deletion()
+        .with(eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")))
+        .times(1)
+        .return_once(move |_| Ok(()));
+
+    fixture
+        .mock_sessions_services
+        .expect_new_session()
+        .times(1)
+        .return_once(move |_| Ok(SessionResponseDto::default()));
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .signin_user(SignInUserDto::new_stub(), Some("test".to_string()), None)
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
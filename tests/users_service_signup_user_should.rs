@@ -2,15 +2,21 @@ use std::sync::Arc;
 
 use mockall::predicate::*;
 use rest_api::{
-    database::user::{DynUsersRepository, User},
+    database::{
+        email_verification::{DynEmailVerificationsRepository, EmailVerificationToken},
+        password_reset::DynPasswordResetsRepository,
+        recovery_code::DynRecoveryCodesRepository,
+        user::{DynUsersRepository, User},
+    },
     mocks::UsersServiceTestFixture,
     server::{
         dtos::user_dto::SignUpUserDto,
         services::{
+            login_throttle_services::DynLoginThrottlesService,
             session_services::DynSessionsService,
             user_services::{UsersService, UsersServiceTrait},
         },
-        utils::{argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil},
+        utils::{argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil, mailer_utils::DynMailer},
     },
 };
 use uuid::uuid;
@@ -41,21 +47,46 @@ async fn return_success_when_downstream_services_succeed() {
         .times(1)
         .return_once(move |_| Ok(String::from("hashed password")));
 
+    fixture
+        .mock_email_verifications_repository
+        .expect_create_email_verification()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("stub email"),
+            always(),
+            always(),
+        )
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(EmailVerificationToken::default()));
+
+    fixture
+        .mock_mailer
+        .expect_send()
+        .times(1)
+        .return_once(move |_| Ok(()));
+
     fixture
         .mock_jwt_util
         .expect_new_access_token()
         .with(
             eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
             eq("stub email"),
+            always(),
         )
         .times(0)
-        .return_once(move |_, _| Ok(String::from("stub token")));
+        .return_once(move |_, _, _| Ok(String::from("stub token")));
 
     let users_service = UsersService::new(
         Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
         Arc::new(fixture.mock_argon_util) as DynArgonUtil,
         Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
         Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
     );
 
     // act
@@ -85,9 +116,15 @@ async fn return_error_when_user_exixsts() {
 
     let users_service = UsersService::new(
         Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
         Arc::new(fixture.mock_argon_util) as DynArgonUtil,
         Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
         Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
     );
 
     // act
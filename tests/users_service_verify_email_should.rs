@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use mockall::predicate::*;
+use rest_api::{
+    database::{
+        email_verification::{DynEmailVerificationsRepository, EmailVerificationToken},
+        password_reset::DynPasswordResetsRepository,
+        recovery_code::DynRecoveryCodesRepository,
+        user::{DynUsersRepository, User},
+    },
+    mocks::UsersServiceTestFixture,
+    server::{
+        dtos::user_dto::VerifyEmailDto,
+        error::Error,
+        services::{
+            login_throttle_services::DynLoginThrottlesService,
+            session_services::DynSessionsService,
+            user_services::{UsersService, UsersServiceTrait},
+        },
+        utils::{
+            argon_utils::DynArgonUtil, jwt_utils::DynJwtUtil, mailer_utils::DynMailer, token_utils,
+        },
+    },
+};
+use uuid::uuid;
+
+#[tokio::test]
+async fn apply_email_when_token_is_valid() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_email_verifications_repository
+        .expect_consume_email_verification()
+        .with(eq(token_utils::hash_token("stub token")))
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(EmailVerificationToken {
+                email: String::from("new email"),
+                ..Default::default()
+            }))
+        });
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .with(eq("new email"))
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture
+        .mock_repository
+        .expect_verify_email()
+        .with(
+            eq(uuid!("f3f898aa-ffa3-4b58-91b0-612a1c801a5e")),
+            eq("new email"),
+        )
+        .times(1)
+        .return_once(move |_, _| Ok(User::default()));
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .verify_email(VerifyEmailDto {
+            token: Some(String::from("stub token")),
+        })
+        .await;
+
+    // assert
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn return_conflict_when_email_was_taken_meanwhile() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_email_verifications_repository
+        .expect_consume_email_verification()
+        .times(1)
+        .return_once(move |_| Ok(Some(EmailVerificationToken::default())));
+
+    fixture
+        .mock_repository
+        .expect_get_user_by_email()
+        .times(1)
+        .return_once(move |_| {
+            Ok(Some(User {
+                id: uuid!("0c1e5f44-2d5b-4a8e-9c3f-7b2a6d4e8f10"),
+                ..Default::default()
+            }))
+        });
+
+    fixture.mock_repository.expect_verify_email().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .verify_email(VerifyEmailDto {
+            token: Some(String::from("stub token")),
+        })
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::ObjectConflict(_))));
+}
+
+#[tokio::test]
+async fn return_error_when_token_is_invalid_or_used() {
+    // arrange
+    let mut fixture = UsersServiceTestFixture::default();
+
+    fixture
+        .mock_email_verifications_repository
+        .expect_consume_email_verification()
+        .times(1)
+        .return_once(move |_| Ok(None));
+
+    fixture.mock_repository.expect_verify_email().times(0);
+
+    let users_service = UsersService::new(
+        Arc::new(fixture.mock_repository) as DynUsersRepository,
+        Arc::new(fixture.mock_password_resets_repository) as DynPasswordResetsRepository,
+        Arc::new(fixture.mock_email_verifications_repository) as DynEmailVerificationsRepository,
+        Arc::new(fixture.mock_recovery_codes_repository) as DynRecoveryCodesRepository,
+        Arc::new(fixture.mock_argon_util) as DynArgonUtil,
+        Arc::new(fixture.mock_jwt_util) as DynJwtUtil,
+        Arc::new(fixture.mock_sessions_services) as DynSessionsService,
+        Arc::new(fixture.mock_login_throttles_services) as DynLoginThrottlesService,
+        Arc::new(fixture.mock_mailer) as DynMailer,
+        fixture.config,
+    );
+
+    // act
+    let response = users_service
+        .verify_email(VerifyEmailDto {
+            token: Some(String::from("stub token")),
+        })
+        .await;
+
+    // assert
+    assert!(matches!(response, Err(Error::BadRequest(_))));
+}
@@ -0,0 +1,161 @@
+use rest_api::mocks::SoftwareAuthenticator;
+use rest_api::server::utils::webauthn_utils;
+
+const CHALLENGE: &str = "c3R1YiBjaGFsbGVuZ2U";
+
+fn decode(value: &str) -> Vec<u8> {
+    webauthn_utils::decode(value).unwrap()
+}
+
+#[test]
+fn return_challenge_of_matching_client_data() {
+    // arrange
+    let authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let credential = authenticator.create(CHALLENGE);
+
+    // act
+    let challenge = webauthn_utils::verify_client_data(
+        &decode(&credential.response.client_data_json),
+        "webauthn.create",
+        "http://localhost:3000",
+    );
+
+    // assert
+    assert_eq!(challenge.unwrap(), CHALLENGE);
+}
+
+#[test]
+fn reject_client_data_from_another_origin() {
+    // arrange
+    let authenticator = SoftwareAuthenticator::new("localhost", "https://evil.example.com");
+    let credential = authenticator.create(CHALLENGE);
+
+    // act
+    let challenge = webauthn_utils::verify_client_data(
+        &decode(&credential.response.client_data_json),
+        "webauthn.create",
+        "http://localhost:3000",
+    );
+
+    // assert
+    assert!(challenge.is_err());
+}
+
+#[test]
+fn extract_credential_from_registration() {
+    // arrange
+    let authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let credential = authenticator.create(CHALLENGE);
+
+    // act
+    let registered_credential = webauthn_utils::verify_registration(
+        &decode(&credential.response.attestation_object),
+        &authenticator.credential_id,
+        "localhost",
+    )
+    .unwrap();
+
+    // assert
+    assert_eq!(
+        registered_credential.credential_id,
+        authenticator.encoded_credential_id()
+    );
+    assert_eq!(
+        registered_credential.public_key,
+        authenticator.cose_public_key()
+    );
+}
+
+#[test]
+fn reject_registration_for_another_relying_party() {
+    // arrange
+    let authenticator = SoftwareAuthenticator::new("evil.example.com", "http://localhost:3000");
+    let credential = authenticator.create(CHALLENGE);
+
+    // act
+    let registered_credential = webauthn_utils::verify_registration(
+        &decode(&credential.response.attestation_object),
+        &authenticator.credential_id,
+        "localhost",
+    );
+
+    // assert
+    assert!(registered_credential.is_err());
+}
+
+#[test]
+fn reject_registration_without_user_verification() {
+    // arrange
+    let mut authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    authenticator.user_verified = false;
+    let credential = authenticator.create(CHALLENGE);
+
+    // act
+    let registered_credential = webauthn_utils::verify_registration(
+        &decode(&credential.response.attestation_object),
+        &authenticator.credential_id,
+        "localhost",
+    );
+
+    // assert
+    assert!(registered_credential.is_err());
+}
+
+#[test]
+fn return_sign_count_of_valid_assertion() {
+    // arrange
+    let mut authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let assertion = authenticator.get(CHALLENGE, None);
+
+    // act
+    let sign_count = webauthn_utils::verify_assertion(
+        &authenticator.cose_public_key(),
+        &decode(&assertion.response.authenticator_data),
+        &decode(&assertion.response.client_data_json),
+        &decode(&assertion.response.signature),
+        "localhost",
+    );
+
+    // assert
+    assert_eq!(sign_count.unwrap(), 1);
+}
+
+#[test]
+fn reject_assertion_signed_by_another_key() {
+    // arrange
+    let mut authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let other_authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let assertion = authenticator.get(CHALLENGE, None);
+
+    // act
+    let sign_count = webauthn_utils::verify_assertion(
+        &other_authenticator.cose_public_key(),
+        &decode(&assertion.response.authenticator_data),
+        &decode(&assertion.response.client_data_json),
+        &decode(&assertion.response.signature),
+        "localhost",
+    );
+
+    // assert
+    assert!(sign_count.is_err());
+}
+
+#[test]
+fn reject_assertion_with_tampered_client_data() {
+    // arrange
+    let mut authenticator = SoftwareAuthenticator::new("localhost", "http://localhost:3000");
+    let assertion = authenticator.get(CHALLENGE, None);
+    let tampered_assertion = authenticator.get("b3RoZXIgY2hhbGxlbmdl", None);
+
+    // act
+    let sign_count = webauthn_utils::verify_assertion(
+        &authenticator.cose_public_key(),
+        &decode(&assertion.response.authenticator_data),
+        &decode(&tampered_assertion.response.client_data_json),
+        &decode(&assertion.response.signature),
+        "localhost",
+    );
+
+    // assert
+    assert!(sign_count.is_err());
+}